@@ -0,0 +1,40 @@
+extern crate dialoguer;
+
+use std::thread;
+use std::time::Duration;
+
+use dialoguer::{theme::ColorfulTheme, Confirmation, ProgressBar, Spinner};
+
+fn main() {
+    let theme = ColorfulTheme::default();
+
+    let mut spinner = Spinner::with_theme(&theme);
+    spinner.with_message("Resolving mirrors");
+    for _ in 0..20 {
+        spinner.tick().unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+    spinner.finish_with_message("Found 3 mirrors").unwrap();
+
+    let mut bar = ProgressBar::with_theme(&theme, 40);
+    bar.with_message("Downloading");
+    for step in 0..40 {
+        if step == 20 {
+            let resume = bar
+                .suspend(|| {
+                    Confirmation::with_theme(&theme)
+                        .with_text("Halfway there, continue?")
+                        .interact()
+                })
+                .unwrap()
+                .unwrap();
+            if !resume {
+                bar.finish_with_message("Download cancelled").unwrap();
+                return;
+            }
+        }
+        bar.inc(1).unwrap();
+        thread::sleep(Duration::from_millis(25));
+    }
+    bar.finish_with_message("Downloaded").unwrap();
+}
@@ -12,6 +12,7 @@ fn main() {
 
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Pick your flavor")
+        .with_help("press ? for all keys")
         .default(0)
         .items(&selections[..])
         .interact()
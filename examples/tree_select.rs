@@ -0,0 +1,20 @@
+extern crate dialoguer;
+
+use dialoguer::{theme::ColorfulTheme, TreeSelect};
+
+fn main() {
+    let regions = &[
+        ("eu-west-1", &["eu-west-1a", "eu-west-1b", "eu-west-1c"][..]),
+        ("us-east-1", &["us-east-1a", "us-east-1b"][..]),
+        ("ap-south-1", &["ap-south-1a"][..]),
+    ];
+
+    let theme = ColorfulTheme::default();
+    let mut tree = TreeSelect::with_theme(&theme);
+    tree.with_prompt("Availability zone");
+    for &(region, zones) in regions {
+        tree.group(region).items(zones).end_group();
+    }
+    let path = tree.interact().unwrap();
+    println!("Deploying to {}", regions[path[0]].1[path[1]]);
+}
@@ -0,0 +1,388 @@
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use console::{Key, Style};
+
+use backend::{Backend, TermGuard};
+use events::{pop_grapheme, read_key, read_plain_line, wait_for_event, Event};
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{
+    get_default_theme, SelectionStyle, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef,
+};
+use validate::{Validation, Validator};
+
+type Source<'a> = Box<dyn Fn(&str) -> Vec<String> + Send + Sync + 'a>;
+type LiveValidator<'a> = Box<dyn Fn(&str) -> Validation<String> + Send + Sync + 'a>;
+
+/// Renders a text input with a live list of suggestions.
+///
+/// The suggestions are fetched from the source on every key press or,
+/// with `debounce`, once the user pauses typing.  The arrow keys highlight
+/// a suggestion, tab copies it into the input and enter submits it.
+/// Unlike a searchable `Select` any text can be submitted.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::Autocomplete;
+///
+/// let city = Autocomplete::new()
+///     .with_prompt("City")
+///     .items(&["Berlin", "Bern", "Bergen", "Vienna"])
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Autocomplete<'a> {
+    prompt: String,
+    source: Option<Source<'a>>,
+    validator: Option<LiveValidator<'a>>,
+    debounce: Option<Duration>,
+    max_suggestions: usize,
+    allow_empty: bool,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a> Autocomplete<'a> {
+    /// Creates a new autocomplete prompt.
+    pub fn new() -> Autocomplete<'static> {
+        Autocomplete::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Autocomplete<'a> {
+        Autocomplete::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Autocomplete<'static> {
+        Autocomplete::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Autocomplete<'a> {
+        Autocomplete {
+            prompt: "".into(),
+            source: None,
+            validator: None,
+            debounce: None,
+            max_suggestions: 5,
+            allow_empty: false,
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the input prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut Autocomplete<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Autocomplete<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted item for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut Autocomplete<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Suggests the items containing the typed text, ignoring case.
+    pub fn items<T: ToString>(&mut self, items: &[T]) -> &mut Autocomplete<'a> {
+        let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        self.with_source(move |input: &str| {
+            let input = input.to_lowercase();
+            items
+                .iter()
+                .filter(|item| item.to_lowercase().contains(&input))
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Sets a function that returns the suggestions for the typed text.
+    ///
+    /// The function is called on every key press, so slow sources such as
+    /// network lookups should cache their results or be debounced.
+    pub fn with_source<F>(&mut self, source: F) -> &mut Autocomplete<'a>
+    where
+        F: Fn(&str) -> Vec<String> + Send + Sync + 'a,
+    {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Registers a validator that checks the text while it is typed.
+    ///
+    /// Its message is shown below the input.  Errors keep the text from
+    /// being submitted, warnings do not.
+    pub fn validate_with<V>(&mut self, validator: V) -> &mut Autocomplete<'a>
+    where
+        V: Validator + Send + Sync + 'a,
+    {
+        self.validator = Some(Box::new(move |text: &str| match validator.check(text) {
+            Validation::Valid => Validation::Valid,
+            Validation::Warning(warning) => Validation::Warning(warning.to_string()),
+            Validation::Error(err) => Validation::Error(err.to_string()),
+        }));
+        self
+    }
+
+    /// Delays the suggestions and live validation until the user paused
+    /// typing for the given time.
+    ///
+    /// By default they are updated on every key press.
+    pub fn debounce(&mut self, pause: Duration) -> &mut Autocomplete<'a> {
+        self.debounce = Some(pause);
+        self
+    }
+
+    /// Sets how many suggestions are shown at most.
+    ///
+    /// The default is 5.
+    pub fn max_suggestions(&mut self, val: usize) -> &mut Autocomplete<'a> {
+        self.max_suggestions = val;
+        self
+    }
+
+    /// Enables or disables an empty input.
+    pub fn allow_empty(&mut self, val: bool) -> &mut Autocomplete<'a> {
+        self.allow_empty = val;
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Autocomplete<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the entered text.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<String> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<String>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Autocomplete::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<String> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term),
+            |value| Some(value.clone()),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<String> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+        let mut input = String::new();
+        let mut suggestions = self.suggest(&input);
+        let mut check: Validation<String> = Validation::Valid;
+        let mut changed: Option<Instant> = None;
+        let mut sel: Option<usize> = None;
+        loop {
+            render.begin_frame();
+            render.input_line(&self.prompt, &input)?;
+            match check {
+                Validation::Valid => {}
+                Validation::Warning(ref warning) => render.warning(warning)?,
+                Validation::Error(ref err) => render.error(err)?,
+            }
+            for (idx, suggestion) in suggestions.iter().enumerate() {
+                render.selection(
+                    suggestion,
+                    if sel == Some(idx) {
+                        SelectionStyle::MenuSelected
+                    } else {
+                        SelectionStyle::MenuUnselected
+                    },
+                )?;
+            }
+            render.end_frame()?;
+            let pause = match (self.debounce, changed) {
+                (Some(debounce), Some(changed)) => Some(debounce.saturating_sub(changed.elapsed())),
+                _ => None,
+            };
+            match wait_for_event(term, pause, true)? {
+                Event::Key => {}
+                Event::Resize => {
+                    render.clear()?;
+                    continue;
+                }
+                Event::Timeout => {
+                    changed = None;
+                    suggestions = self.suggest(&input);
+                    check = self.check(&input);
+                    continue;
+                }
+            }
+            let len = suggestions.len();
+            match render.clear_interrupted(read_key(term))? {
+                Key::ArrowDown if len > 0 => {
+                    sel = match sel {
+                        Some(idx) if idx + 1 < len => Some(idx + 1),
+                        Some(_) => None,
+                        None => Some(0),
+                    };
+                    continue;
+                }
+                Key::ArrowUp if len > 0 => {
+                    sel = match sel {
+                        Some(0) => None,
+                        Some(idx) => Some(idx - 1),
+                        None => Some(len - 1),
+                    };
+                    continue;
+                }
+                Key::Escape => {
+                    sel = None;
+                    continue;
+                }
+                Key::Tab => match sel {
+                    Some(idx) => input = suggestions[idx].clone(),
+                    None if len > 0 => input = suggestions[0].clone(),
+                    None => continue,
+                },
+                Key::Enter => {
+                    let value = match sel {
+                        Some(idx) => suggestions[idx].clone(),
+                        None => input.clone(),
+                    };
+                    if value.is_empty() && !self.allow_empty {
+                        continue;
+                    }
+                    check = self.check(&value);
+                    if let Validation::Error(_) = check {
+                        continue;
+                    }
+                    render.clear()?;
+                    render.single_prompt_selection(&self.prompt, &value)?;
+                    return Ok(value);
+                }
+                Key::Backspace => {
+                    pop_grapheme(&mut input);
+                }
+                Key::Char(c) => input.push(c),
+                _ => continue,
+            }
+            sel = None;
+            if self.debounce.is_some() {
+                changed = Some(Instant::now());
+            } else {
+                suggestions = self.suggest(&input);
+                check = self.check(&input);
+            }
+        }
+    }
+
+    /// Reads the text as a plain line for the accessible render mode.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<String> {
+        loop {
+            render.input_prompt(&self.prompt, None)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            if input.is_empty() && !self.allow_empty {
+                continue;
+            }
+            match self.check(&input) {
+                Validation::Error(err) => render.error(&err)?,
+                Validation::Warning(warning) => {
+                    render.warning(&warning)?;
+                    return Ok(input);
+                }
+                Validation::Valid => return Ok(input),
+            }
+        }
+    }
+
+    fn check(&self, input: &str) -> Validation<String> {
+        match self.validator {
+            Some(ref validator) => validator(input),
+            None => Validation::Valid,
+        }
+    }
+
+    fn suggest(&self, input: &str) -> Vec<String> {
+        match self.source {
+            Some(ref source) => {
+                let mut suggestions = source(input);
+                suggestions.truncate(self.max_suggestions);
+                suggestions
+            }
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let mut prompt = Autocomplete::new();
+        prompt
+            .items(&["Berlin", "Bern", "Bergen", "Vienna"])
+            .max_suggestions(2);
+        assert_eq!(prompt.suggest("ber"), vec!["Berlin", "Bern"]);
+        assert_eq!(prompt.suggest("enn"), vec!["Vienna"]);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut prompt = Autocomplete::new();
+        prompt.validate_with(|text: &str| {
+            if text.contains(' ') {
+                Err("no spaces")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(prompt.check("a b"), Validation::Error("no spaces".into()));
+        assert_eq!(prompt.check("ab"), Validation::Valid);
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(Autocomplete::new());
+        assert_send(Autocomplete::with_owned_theme(ColorfulTheme::default()));
+    }
+}
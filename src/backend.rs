@@ -0,0 +1,302 @@
+//! The terminals prompts can be rendered to.
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use console::{Key, Term};
+
+use events::poll_input;
+
+/// A terminal prompts render to and read keys from.
+///
+/// `console::Term` is the backend used by `interact`.  Other backends let
+/// prompts run over an SSH channel, a socket or a terminal widget: they
+/// only need to accept text with ANSI escape sequences and to deliver
+/// key presses.
+///
+/// ```rust,no_run
+/// # extern crate console;
+/// # extern crate dialoguer;
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::io;
+///
+/// use console::Key;
+/// use dialoguer::{Backend, Confirmation};
+///
+/// struct Session;
+///
+/// impl Backend for Session {
+///     fn write_str(&self, s: &str) -> io::Result<()> {
+///         // send `s` to the remote terminal
+///         Ok(())
+///     }
+///
+///     fn read_key(&self) -> io::Result<Key> {
+///         // wait for the next key of the remote terminal
+///         Ok(Key::Enter)
+///     }
+///
+///     fn size(&self) -> (u16, u16) {
+///         (24, 80)
+///     }
+/// }
+///
+/// let ok = Confirmation::new().with_text("Deploy?").interact_on(&Session)?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub trait Backend {
+    /// Writes text which may contain ANSI escape sequences.
+    fn write_str(&self, s: &str) -> io::Result<()>;
+
+    /// Reads the next key press.
+    ///
+    /// Ctrl+C must be reported as `Key::CtrlC`.  Characters must arrive
+    /// whole: a backend reading bytes has to hold back an incomplete UTF-8
+    /// sequence until the rest of it arrives, as `console::Term` does.
+    fn read_key(&self) -> io::Result<Key>;
+
+    /// Returns the size of the terminal as rows and columns.
+    fn size(&self) -> (u16, u16);
+
+    /// Flushes buffered output.
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns `true` if this is an interactive terminal.
+    fn is_term(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the terminal can display colors.
+    fn colors_supported(&self) -> bool {
+        true
+    }
+
+    /// Waits up to `timeout` for a key press and returns whether one is
+    /// ready to be read.
+    ///
+    /// The default returns `true` right away, so timeouts and resize
+    /// detection are not supported unless this is implemented.
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        let _ = timeout;
+        Ok(true)
+    }
+
+    /// Writes a line of text.
+    fn write_line(&self, s: &str) -> io::Result<()> {
+        self.write_str(&format!("{}\n", s))
+    }
+
+    /// Clears the line the cursor is on.
+    fn clear_line(&self) -> io::Result<()> {
+        self.write_str("\r\x1b[2K")
+    }
+
+    /// Clears the last `n` characters before the cursor.
+    fn clear_chars(&self, n: usize) -> io::Result<()> {
+        if n == 0 {
+            return Ok(());
+        }
+        self.write_str(&format!("\x1b[{}D\x1b[0K", n))
+    }
+
+    /// Clears the last `n` lines and moves the cursor to the first of them.
+    fn clear_last_lines(&self, n: usize) -> io::Result<()> {
+        if n == 0 {
+            return Ok(());
+        }
+        self.write_str(&format!("\x1b[{}A\r\x1b[0J", n))
+    }
+
+    /// Makes the cursor visible.
+    fn show_cursor(&self) -> io::Result<()> {
+        self.write_str("\x1b[?25h")
+    }
+
+    /// Makes the cursor invisible.
+    fn hide_cursor(&self) -> io::Result<()> {
+        self.write_str("\x1b[?25l")
+    }
+}
+
+/// Hides the cursor until it is dropped.
+///
+/// The cursor is shown again on every way out of a prompt, including
+/// errors and panics.
+pub(crate) struct HiddenCursor<'a> {
+    term: &'a dyn Backend,
+}
+
+/// The number of `HiddenCursor`s alive on terminals.
+static HIDDEN_CURSORS: AtomicUsize = AtomicUsize::new(0);
+
+impl<'a> HiddenCursor<'a> {
+    pub fn new(term: &'a dyn Backend) -> io::Result<HiddenCursor<'a>> {
+        if term.is_term() {
+            term.hide_cursor()?;
+            term.flush()?;
+            HIDDEN_CURSORS.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(HiddenCursor { term })
+    }
+}
+
+impl<'a> Drop for HiddenCursor<'a> {
+    fn drop(&mut self) {
+        if self.term.is_term() {
+            HIDDEN_CURSORS.fetch_sub(1, Ordering::SeqCst);
+            let _ = self.term.show_cursor();
+            let _ = self.term.flush();
+        }
+    }
+}
+
+/// Returns `true` while a prompt keeps the cursor hidden.
+pub(crate) fn cursor_hidden() -> bool {
+    HIDDEN_CURSORS.load(Ordering::SeqCst) > 0
+}
+
+/// Restores the terminal if a prompt is left by a panic.
+///
+/// A panic in a validator or completer would otherwise leave the cursor
+/// hidden and the shell prompt on the line of the aborted prompt.  Raw
+/// mode needs no restoring since it is only entered while reading a key.
+pub(crate) struct TermGuard<'a> {
+    term: &'a dyn Backend,
+}
+
+impl<'a> TermGuard<'a> {
+    pub fn new(term: &'a dyn Backend) -> TermGuard<'a> {
+        TermGuard { term }
+    }
+}
+
+impl<'a> Drop for TermGuard<'a> {
+    fn drop(&mut self) {
+        if thread::panicking() && self.term.is_term() {
+            let _ = self.term.show_cursor();
+            let _ = self.term.write_line("");
+            let _ = self.term.flush();
+        }
+    }
+}
+
+impl Backend for Term {
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        Term::write_str(self, s)
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        self.read_key_raw()
+    }
+
+    fn size(&self) -> (u16, u16) {
+        Term::size(self)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Term::flush(self)
+    }
+
+    fn is_term(&self) -> bool {
+        Term::is_term(self)
+    }
+
+    fn colors_supported(&self) -> bool {
+        self.features().colors_supported()
+    }
+
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        let mut polled = false;
+        poll_input(|| {
+            if polled {
+                return None;
+            }
+            polled = true;
+            Some(timeout)
+        })
+    }
+
+    fn write_line(&self, s: &str) -> io::Result<()> {
+        Term::write_line(self, s)
+    }
+
+    fn clear_line(&self) -> io::Result<()> {
+        Term::clear_line(self)
+    }
+
+    fn clear_chars(&self, n: usize) -> io::Result<()> {
+        Term::clear_chars(self, n)
+    }
+
+    fn clear_last_lines(&self, n: usize) -> io::Result<()> {
+        Term::clear_last_lines(self, n)
+    }
+
+    fn show_cursor(&self) -> io::Result<()> {
+        Term::show_cursor(self)
+    }
+
+    fn hide_cursor(&self) -> io::Result<()> {
+        Term::hide_cursor(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::*;
+    use prompts::Confirmation;
+
+    struct Script {
+        keys: RefCell<Vec<Key>>,
+        output: RefCell<String>,
+    }
+
+    impl Backend for Script {
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            self.output.borrow_mut().push_str(s);
+            Ok(())
+        }
+
+        fn read_key(&self) -> io::Result<Key> {
+            Ok(self.keys.borrow_mut().remove(0))
+        }
+
+        fn size(&self) -> (u16, u16) {
+            (24, 80)
+        }
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        let script = Script {
+            keys: RefCell::new(vec![Key::Char('x'), Key::Char('y')]),
+            output: RefCell::new(String::new()),
+        };
+        let rv = Confirmation::new()
+            .with_text("Deploy?")
+            .interact_on(&script)
+            .unwrap();
+        assert!(rv);
+        assert!(script.output.borrow().contains("Deploy?"));
+    }
+
+    #[test]
+    fn test_term_guard() {
+        let script = Script {
+            keys: RefCell::new(vec![]),
+            output: RefCell::new(String::new()),
+        };
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard = TermGuard::new(&script);
+            let _cursor = HiddenCursor::new(&script);
+            panic!("validator failed");
+        }));
+        assert_eq!(*script.output.borrow(), "\x1b[?25l\x1b[?25h\x1b[?25h\n");
+    }
+}
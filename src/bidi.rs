@@ -0,0 +1,84 @@
+//! Display of right-to-left text such as Arabic or Hebrew.
+//!
+//! Terminals print characters in the order they arrive, so text in a
+//! right-to-left script has to be reordered before it is written.  With
+//! the `bidi` feature enabled this is done with the Unicode bidirectional
+//! algorithm, keeping the line itself left-to-right.
+use std::borrow::Cow;
+
+#[cfg(feature = "bidi")]
+use unicode_bidi::{BidiInfo, Level};
+
+/// Returns `true` if the text contains characters written right-to-left.
+#[cfg(feature = "bidi")]
+pub(crate) fn has_rtl(text: &str) -> bool {
+    BidiInfo::new(text, Some(Level::ltr())).has_rtl()
+}
+
+#[cfg(not(feature = "bidi"))]
+pub(crate) fn has_rtl(_text: &str) -> bool {
+    false
+}
+
+/// Reorders text from logical into display order.
+///
+/// ANSI escape sequences stay in place; the text between them is
+/// reordered line by line.
+#[cfg(feature = "bidi")]
+pub(crate) fn visual_order(text: &str) -> Cow<'_, str> {
+    if !has_rtl(text) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let plain = rest.find('\x1b').unwrap_or(rest.len());
+        for (idx, line) in rest[..plain].split('\n').enumerate() {
+            if idx > 0 {
+                out.push('\n');
+            }
+            let info = BidiInfo::new(line, Some(Level::ltr()));
+            for para in &info.paragraphs {
+                out.push_str(&info.reorder_line(para, para.range.clone()));
+            }
+        }
+        rest = &rest[plain..];
+        let escape = escape_len(rest);
+        out.push_str(&rest[..escape]);
+        rest = &rest[escape..];
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(not(feature = "bidi"))]
+pub(crate) fn visual_order(text: &str) -> Cow<'_, str> {
+    Cow::Borrowed(text)
+}
+
+/// Returns the length of the escape sequence `text` starts with.
+#[cfg(feature = "bidi")]
+fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    match (chars.next(), chars.next()) {
+        (Some((_, '\x1b')), Some((_, '['))) => chars
+            .find(|&(_, c)| ('@'..='~').contains(&c))
+            .map_or(text.len(), |(idx, c)| idx + c.len_utf8()),
+        (Some((_, '\x1b')), Some((idx, c))) => idx + c.len_utf8(),
+        _ => text.len().min(1),
+    }
+}
+
+#[cfg(all(test, feature = "bidi"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(visual_order("> abc"), "> abc");
+        assert_eq!(visual_order("> שלום"), "> םולש");
+        assert_eq!(
+            visual_order("\x1b[1mשלום\x1b[0m: ok\nאב"),
+            "\x1b[1mםולש\x1b[0m: ok\nבא"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Provides tab completion for text inputs
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use state;
+
+/// The number of values a history keeps in the state store.
+const STORED_HISTORY: usize = 100;
+
+/// Completes text inputs when the user hits tab.
+pub trait Completer {
+    /// Invoked with the current input when the user hits tab.
+    ///
+    /// If this produces a value it replaces the input.
+    fn complete(&self, input: &str) -> Option<String>;
+
+    /// Invoked with every value the user submits.
+    fn remember(&self, value: &str) {
+        let _ = value;
+    }
+}
+
+impl<C: Completer + ?Sized> Completer for &C {
+    fn complete(&self, input: &str) -> Option<String> {
+        (**self).complete(input)
+    }
+
+    fn remember(&self, value: &str) {
+        (**self).remember(value)
+    }
+}
+
+/// Decides which part of the input a completer is asked to complete.
+///
+/// By default the whole line is completed.  A splitter returns the byte
+/// offset at which the word under completion starts, so that e.g. only
+/// the value of a `key=value` input is completed.
+pub trait WordSplitter {
+    /// Returns the byte offset of the last word in `input`.
+    fn word_start(&self, input: &str) -> usize;
+}
+
+impl<F: Fn(&str) -> usize> WordSplitter for F {
+    fn word_start(&self, input: &str) -> usize {
+        self(input)
+    }
+}
+
+/// Splits words at any of a set of delimiter characters.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::{Delimiters, HistoryCompleter, Input};
+///
+/// let values = HistoryCompleter::new();
+/// let setting = Input::<String>::new()
+///     .with_prompt("Setting")
+///     .with_completer(&values)
+///     .with_word_splitter(Delimiters::new("=,:"))
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Delimiters {
+    chars: Vec<char>,
+}
+
+impl Delimiters {
+    /// Creates a splitter for the characters in `chars`.
+    pub fn new(chars: &str) -> Delimiters {
+        Delimiters {
+            chars: chars.chars().collect(),
+        }
+    }
+}
+
+impl WordSplitter for Delimiters {
+    fn word_start(&self, input: &str) -> usize {
+        input
+            .rfind(&self.chars[..])
+            .map(|idx| idx + input[idx..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0)
+    }
+}
+
+/// Completes only the last word of the input.
+pub(crate) struct WordCompleter<'a> {
+    pub completer: &'a dyn Completer,
+    pub splitter: &'a dyn WordSplitter,
+}
+
+impl<'a> Completer for WordCompleter<'a> {
+    fn complete(&self, input: &str) -> Option<String> {
+        let mut start = self.splitter.word_start(input).min(input.len());
+        while !input.is_char_boundary(start) {
+            start -= 1;
+        }
+        self.completer
+            .complete(&input[start..])
+            .map(|word| format!("{}{}", &input[..start], word))
+    }
+}
+
+/// Completes inputs from previously entered values.
+///
+/// The most recent value starting with the typed text wins.  A history
+/// can be shared by several prompts and optionally be kept in a file, one
+/// value per line, or in the `StateStore`.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::{HistoryCompleter, Input};
+///
+/// let history = HistoryCompleter::from_file(".hosts")?;
+/// let host = Input::<String>::new()
+///     .with_prompt("Host")
+///     .with_completer(&history)
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+#[derive(Debug, Default)]
+pub struct HistoryCompleter {
+    entries: Mutex<Vec<String>>,
+    file: Option<PathBuf>,
+    key: Option<String>,
+}
+
+impl HistoryCompleter {
+    /// Creates an empty history that is kept in memory.
+    pub fn new() -> HistoryCompleter {
+        HistoryCompleter::default()
+    }
+
+    /// Loads the history from a file and appends new values to it.
+    ///
+    /// A missing file is created once the first value is remembered.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<HistoryCompleter> {
+        let path = path.as_ref().to_path_buf();
+        let history = HistoryCompleter {
+            entries: Mutex::new(vec![]),
+            file: Some(path.clone()),
+            key: None,
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                for line in contents.lines() {
+                    history.push(line);
+                }
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        Ok(history)
+    }
+
+    /// Loads the history remembered under `key` and remembers new values
+    /// there.
+    ///
+    /// The history lasts across runs if a `StateStore` is set, which keeps
+    /// the last 100 values.
+    pub fn from_state(key: &str) -> HistoryCompleter {
+        let history = HistoryCompleter {
+            key: Some(key.into()),
+            ..HistoryCompleter::default()
+        };
+        for value in state::recall_recent(key) {
+            history.push(&value);
+        }
+        history
+    }
+
+    /// Returns the remembered values, oldest first.
+    pub fn entries(&self) -> Vec<String> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    fn push(&self, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| entry != value);
+        entries.push(value.to_string());
+    }
+}
+
+impl Completer for HistoryCompleter {
+    fn complete(&self, input: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|entry| entry.len() > input.len() && entry.starts_with(input))
+            .cloned()
+    }
+
+    fn remember(&self, value: &str) {
+        if value.is_empty() || value.contains('\n') {
+            return;
+        }
+        self.push(value);
+        if let Some(ref key) = self.key {
+            state::remember_recent(key, value, STORED_HISTORY);
+        }
+        if let Some(ref path) = self.file {
+            // a history that cannot be written is not worth failing over
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut f| writeln!(f, "{}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_history_completer() {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "alpha\nbeta\nalpine\n").unwrap();
+
+        let history = HistoryCompleter::from_file(file.path()).unwrap();
+        assert_eq!(history.complete("al"), Some("alpine".into()));
+        assert_eq!(history.complete("alpine"), None);
+        history.remember("alpha");
+        assert_eq!(history.complete("al"), Some("alpha".into()));
+        assert_eq!(history.entries(), vec!["beta", "alpine", "alpha"]);
+
+        let reloaded = HistoryCompleter::from_file(file.path()).unwrap();
+        assert_eq!(reloaded.entries(), history.entries());
+    }
+
+    #[test]
+    fn test_history_from_state() {
+        let history = HistoryCompleter::from_state("test-history");
+        history.remember("deploy");
+        history.remember("debug");
+        let reloaded = HistoryCompleter::from_state("test-history");
+        assert_eq!(reloaded.entries(), vec!["deploy", "debug"]);
+        assert_eq!(reloaded.complete("de"), Some("debug".into()));
+    }
+
+    #[test]
+    fn test_word_completer() {
+        let history = HistoryCompleter::new();
+        history.remember("release");
+        let splitter = Delimiters::new("=,");
+        let completer = WordCompleter {
+            completer: &history,
+            splitter: &splitter,
+        };
+        assert_eq!(
+            completer.complete("mode=debug,re"),
+            Some("mode=debug,release".into())
+        );
+        assert_eq!(completer.complete("re"), Some("release".into()));
+        assert_eq!(completer.complete("mode="), Some("mode=release".into()));
+    }
+}
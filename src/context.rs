@@ -0,0 +1,402 @@
+//! Settings shared by all prompts of an application.
+use std::fmt::{Debug, Display};
+use std::io;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use backend::Backend;
+use keymap::KeyMap;
+use prompts::{Confirmation, Input, PasswordInput};
+use select::{Checkboxes, Select};
+use stream::Stream;
+use theme::{get_default_theme, Theme, ThemeRef};
+
+/// What a prompt does if it is not connected to a terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonInteractive {
+    /// Prompts anyway, e.g. to read answers piped into the accessible mode.
+    #[default]
+    Prompt,
+    /// Returns the default without prompting, or fails if there is none.
+    UseDefault,
+    /// Fails with an error of kind `NotConnected`.
+    Fail,
+}
+
+impl NonInteractive {
+    /// Returns the answer of a prompt on `term` if it must not prompt.
+    pub(crate) fn answer<T>(self, term: &dyn Backend, default: Option<T>) -> Option<io::Result<T>> {
+        if term.is_term() {
+            return None;
+        }
+        match self {
+            NonInteractive::Prompt => None,
+            NonInteractive::UseDefault => Some(default.ok_or_else(not_connected)),
+            NonInteractive::Fail => Some(Err(not_connected())),
+        }
+    }
+}
+
+/// Whether prompts answer themselves, e.g. for a `--yes` flag.
+///
+/// Unlike `NonInteractive` the policy applies on a terminal as well.
+/// Confirmations, inputs and menus follow it.  An input or menu without
+/// a default still asks, and so do password prompts, which have none.
+/// The default of a checkbox menu is no checked items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssumePolicy {
+    /// Asks as usual.
+    #[default]
+    Interactive,
+    /// Answers with the default.
+    AssumeDefault,
+    /// Answers confirmations with yes and menus with their default.
+    AssumeYes,
+}
+
+impl AssumePolicy {
+    /// Returns the answer of a confirmation with the default `default`.
+    pub(crate) fn confirmation(self, default: bool) -> Option<bool> {
+        match self {
+            AssumePolicy::Interactive => None,
+            AssumePolicy::AssumeDefault => Some(default),
+            AssumePolicy::AssumeYes => Some(true),
+        }
+    }
+
+    /// Returns the answer of a menu or input with the default `default`,
+    /// if any.
+    pub(crate) fn selection<T>(self, default: Option<T>) -> Option<T> {
+        match self {
+            AssumePolicy::Interactive => None,
+            AssumePolicy::AssumeDefault | AssumePolicy::AssumeYes => default,
+        }
+    }
+}
+
+fn not_connected() -> io::Error {
+    io::Error::new(io::ErrorKind::NotConnected, "Not a terminal")
+}
+
+/// Carries the settings an application wants for all of its prompts.
+///
+/// The prompts created by the context start out with its theme, stream,
+/// key bindings, timeout, non-interactive and assume policy; each of them
+/// can still be changed on the individual prompt.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::theme::ColorfulTheme;
+/// use dialoguer::{AssumePolicy, Dialoguer, NonInteractive};
+///
+/// # let yes = false;
+/// let mut ctx = Dialoguer::with_owned_theme(ColorfulTheme::default());
+/// ctx.non_interactive(NonInteractive::UseDefault);
+/// if yes {
+///     ctx.assume(AssumePolicy::AssumeYes);
+/// }
+///
+/// let name = ctx.input::<String>().with_prompt("Name").interact()?;
+/// if ctx.confirm().with_text("Continue?").interact()? {
+///     let color = ctx.select().items(&["red", "green"]).interact()?;
+/// }
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Dialoguer<'a> {
+    theme: ThemeRef<'a>,
+    stream: Stream,
+    keymap: Option<KeyMap>,
+    timeout: Option<Duration>,
+    non_interactive: NonInteractive,
+    assume: AssumePolicy,
+}
+
+impl<'a> Dialoguer<'a> {
+    /// Creates a context with the default theme.
+    pub fn new() -> Dialoguer<'static> {
+        Dialoguer::with_theme(get_default_theme())
+    }
+
+    /// Creates a context with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Dialoguer<'a> {
+        Dialoguer::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Dialoguer<'static> {
+        Dialoguer::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Dialoguer<'a> {
+        Dialoguer {
+            theme,
+            stream: Stream::Stderr,
+            keymap: None,
+            timeout: None,
+            non_interactive: NonInteractive::Prompt,
+            assume: AssumePolicy::Interactive,
+        }
+    }
+
+    /// Sets the stream prompts render on.
+    pub fn on(&mut self, stream: Stream) -> &mut Dialoguer<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Sets the key bindings of the list prompts.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut Dialoguer<'a> {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Sets the timeout of the prompts that support one.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Dialoguer<'a> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets what prompts do if they are not connected to a terminal.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut Dialoguer<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Sets whether confirmations and menus answer themselves, e.g. for a
+    /// `--yes` flag.
+    pub fn assume(&mut self, policy: AssumePolicy) -> &mut Dialoguer<'a> {
+        self.assume = policy;
+        self
+    }
+
+    /// Creates a confirmation prompt.
+    pub fn confirm(&self) -> Confirmation<'_> {
+        let mut prompt = Confirmation::with_theme(&*self.theme);
+        prompt
+            .on(self.stream)
+            .non_interactive(self.non_interactive)
+            .assume(self.assume);
+        if let Some(timeout) = self.timeout {
+            prompt.with_timeout(timeout);
+        }
+        prompt
+    }
+
+    /// Asks a series of confirmations and stops at the first one declined.
+    ///
+    /// Every step is a question and its default.  Returns `None` if all
+    /// steps were confirmed, otherwise the index of the declined step; the
+    /// answered steps stay on screen as the usual summary lines.
+    ///
+    /// ```rust,no_run
+    /// # fn test() -> Result<(), Box<std::error::Error>> {
+    /// use dialoguer::Dialoguer;
+    ///
+    /// let ctx = Dialoguer::new();
+    /// match ctx.confirm_all(&[("Stop the service?", false), ("Delete its data?", false)])? {
+    ///     None => println!("Deleting"),
+    ///     Some(step) => println!("Aborted at step {}", step + 1),
+    /// }
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    pub fn confirm_all(&self, steps: &[(&str, bool)]) -> io::Result<Option<usize>> {
+        self.confirm_all_on(&self.stream.term(), steps)
+    }
+
+    /// Like `confirm_all` but allows a specific terminal to be set.
+    pub fn confirm_all_on(
+        &self,
+        term: &dyn Backend,
+        steps: &[(&str, bool)],
+    ) -> io::Result<Option<usize>> {
+        for (idx, &(text, default)) in steps.iter().enumerate() {
+            if !self
+                .confirm()
+                .with_text(text)
+                .default(default)
+                .interact_on(term)?
+            {
+                return Ok(Some(idx));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Creates an input prompt.
+    pub fn input<T>(&self) -> Input<'_, T>
+    where
+        T: Clone + FromStr + Display,
+        T::Err: Display + Debug,
+    {
+        let mut prompt = Input::with_theme(&*self.theme);
+        prompt
+            .on(self.stream)
+            .non_interactive(self.non_interactive)
+            .assume(self.assume);
+        if let Some(timeout) = self.timeout {
+            prompt.with_timeout(timeout);
+        }
+        prompt
+    }
+
+    /// Creates a password prompt.
+    pub fn password(&self) -> PasswordInput<'_> {
+        let mut prompt = PasswordInput::with_theme(&*self.theme);
+        prompt.on(self.stream).non_interactive(self.non_interactive);
+        if let Some(timeout) = self.timeout {
+            prompt.with_timeout(timeout);
+        }
+        prompt
+    }
+
+    /// Creates a selection menu.
+    pub fn select(&self) -> Select<'_> {
+        let mut prompt = Select::with_theme(&*self.theme);
+        prompt
+            .on(self.stream)
+            .non_interactive(self.non_interactive)
+            .assume(self.assume);
+        if let Some(timeout) = self.timeout {
+            prompt.with_timeout(timeout);
+        }
+        if let Some(ref keymap) = self.keymap {
+            prompt.with_keymap(keymap.clone());
+        }
+        prompt
+    }
+
+    /// Creates a checkbox menu.
+    pub fn checkboxes(&self) -> Checkboxes<'_> {
+        let mut prompt = Checkboxes::with_theme(&*self.theme);
+        prompt
+            .on(self.stream)
+            .non_interactive(self.non_interactive)
+            .assume(self.assume);
+        if let Some(timeout) = self.timeout {
+            prompt.with_timeout(timeout);
+        }
+        if let Some(ref keymap) = self.keymap {
+            prompt.with_keymap(keymap.clone());
+        }
+        prompt
+    }
+}
+
+/// Asks a series of confirmations with the default settings and stops at
+/// the first one declined.
+///
+/// See `Dialoguer::confirm_all`.
+pub fn confirm_all(steps: &[(&str, bool)]) -> io::Result<Option<usize>> {
+    Dialoguer::new().confirm_all(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::Key;
+
+    struct Pipe;
+
+    impl Backend for Pipe {
+        fn write_str(&self, _: &str) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn read_key(&self) -> io::Result<Key> {
+            Ok(Key::Enter)
+        }
+
+        fn size(&self) -> (u16, u16) {
+            (24, 80)
+        }
+
+        fn is_term(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_non_interactive() {
+        assert!(NonInteractive::Prompt.answer(&Pipe, Some(1)).is_none());
+        assert_eq!(
+            NonInteractive::UseDefault
+                .answer(&Pipe, Some(1))
+                .unwrap()
+                .unwrap(),
+            1
+        );
+        assert!(NonInteractive::UseDefault
+            .answer::<u8>(&Pipe, None)
+            .unwrap()
+            .is_err());
+
+        let mut ctx = Dialoguer::new();
+        ctx.non_interactive(NonInteractive::UseDefault);
+        assert!(!ctx.confirm().default(false).interact_on(&Pipe).unwrap());
+        let name = ctx
+            .input::<String>()
+            .default("x".into())
+            .interact_on(&Pipe)
+            .unwrap();
+        assert_eq!(name, "x");
+        let steps = [("Stop?", true), ("Delete?", false), ("Restart?", true)];
+        assert_eq!(ctx.confirm_all_on(&Pipe, &steps).unwrap(), Some(1));
+        assert_eq!(ctx.confirm_all_on(&Pipe, &steps[..1]).unwrap(), None);
+        ctx.non_interactive(NonInteractive::Fail);
+        let err = ctx
+            .select()
+            .items(&["a"])
+            .default(0)
+            .interact_on(&Pipe)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+    }
+
+    #[test]
+    fn test_assume() {
+        let mut ctx = Dialoguer::new();
+        ctx.non_interactive(NonInteractive::Fail)
+            .assume(AssumePolicy::AssumeYes);
+        assert!(ctx.confirm().default(false).interact_on(&Pipe).unwrap());
+        assert_eq!(
+            ctx.select()
+                .items(&["a", "b"])
+                .default(1)
+                .interact_on(&Pipe)
+                .unwrap(),
+            1
+        );
+        assert!(ctx.select().items(&["a", "b"]).interact_on(&Pipe).is_err());
+        let name = ctx
+            .input::<String>()
+            .default("guest".into())
+            .interact_on(&Pipe);
+        assert_eq!(name.unwrap(), "guest");
+        assert!(ctx.input::<String>().interact_on(&Pipe).is_err());
+        let checked = ctx.checkboxes().items(&["a", "b"]).interact_on(&Pipe);
+        assert_eq!(checked.unwrap(), Vec::<usize>::new());
+        assert!(ctx
+            .checkboxes()
+            .items(&["a"])
+            .min_selected(1)
+            .interact_on(&Pipe)
+            .is_err());
+        ctx.assume(AssumePolicy::AssumeDefault);
+        assert!(!ctx.confirm().default(false).interact_on(&Pipe).unwrap());
+        assert_eq!(
+            ctx.input::<u16>().default(8080).interact_on(&Pipe).unwrap(),
+            8080
+        );
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(Dialoguer::new());
+        assert_send(Dialoguer::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -5,6 +5,8 @@ use std::io;
 use std::io::{Read, Write};
 use std::process;
 
+#[cfg(feature = "tokio")]
+use rt::{run_detached, PromptFuture};
 use tempfile::NamedTempFileOptions;
 
 /// Launches the default editor edit a string.
@@ -23,6 +25,7 @@ use tempfile::NamedTempFileOptions;
 /// }
 /// # Ok(()) } fn main() { test().unwrap(); }
 /// ```
+#[derive(Clone)]
 pub struct Editor {
     editor: OsString,
     extension: String,
@@ -81,6 +84,14 @@ impl Editor {
         self
     }
 
+    /// Like `edit` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn edit_async(&self, s: &str) -> PromptFuture<'static, io::Result<Option<String>>> {
+        let editor = self.clone();
+        let s = s.to_string();
+        run_detached(move || editor.edit(&s))
+    }
+
     /// Launches the editor to edit a string.
     ///
     /// Returns `None` if the file was not saved or otherwise the
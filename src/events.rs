@@ -0,0 +1,640 @@
+//! Reading of terminal input with support for timeouts and interrupts.
+//!
+//! The prompts read keys through this module; applications building their
+//! own widgets can do the same with `key_events`, which decodes keys the
+//! same way and reports resizes of the terminal.  Such widgets render by
+//! writing to a `Backend` with the `format_*` methods of a `Theme`.
+//!
+//! ```rust,no_run
+//! # extern crate console;
+//! # extern crate dialoguer;
+//! # fn test() -> Result<(), Box<std::error::Error>> {
+//! use console::{Key, Term};
+//! use dialoguer::events::{key_events, KeyEvent};
+//!
+//! let term = Term::stderr();
+//! for event in key_events(&term) {
+//!     match event? {
+//!         KeyEvent::Key(Key::Escape) => break,
+//!         KeyEvent::Key(key) => println!("{:?}", key),
+//!         KeyEvent::Resize(rows, cols) => println!("{}x{}", cols, rows),
+//!     }
+//! }
+//! # Ok(()) } fn main() { test().unwrap(); }
+//! ```
+use std::cell::RefCell;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use console::{measure_text_width, Key};
+use unicode_segmentation::UnicodeSegmentation;
+
+use backend::{cursor_hidden, Backend};
+use bidi::{has_rtl, visual_order};
+use complete::Completer;
+use mouse;
+use theme::alternate_screen_active;
+
+/// An event read by `key_events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// A key was pressed.
+    Key(Key),
+    /// The terminal was resized to the given rows and columns.
+    Resize(u16, u16),
+}
+
+/// Iterates over the events of a terminal, see `key_events`.
+pub struct KeyEvents<'a> {
+    term: &'a dyn Backend,
+}
+
+impl<'a> Iterator for KeyEvents<'a> {
+    type Item = io::Result<KeyEvent>;
+
+    fn next(&mut self) -> Option<io::Result<KeyEvent>> {
+        Some(match wait_for_event(self.term, None, true) {
+            Ok(Event::Resize) => {
+                let (rows, cols) = self.term.size();
+                Ok(KeyEvent::Resize(rows, cols))
+            }
+            Ok(_) => read_key(self.term).map(KeyEvent::Key),
+            Err(err) => Err(err),
+        })
+    }
+}
+
+/// Returns an endless iterator over the keys pressed on `term`.
+///
+/// Like the prompts it reports Ctrl+C as an error of kind `Interrupted`
+/// instead of raising `SIGINT`.  Resizes are only noticed on backends
+/// that implement `Backend::poll_key`.
+pub fn key_events(term: &dyn Backend) -> KeyEvents<'_> {
+    KeyEvents { term }
+}
+
+/// Reads a single key from the terminal.
+///
+/// Unlike `Term::read_key` a Ctrl+C does not raise `SIGINT` but is
+/// reported as an error of kind `Interrupted` so that the prompt can
+/// clean up after itself.  A Ctrl+Z stops the process, see `stop`, and
+/// is reported as `Key::Unknown` once it continues.
+pub(crate) fn read_key(term: &dyn Backend) -> io::Result<Key> {
+    match term.read_key()? {
+        Key::CtrlC => Err(interrupted()),
+        Key::Char('\u{1a}') if cfg!(unix) && term.is_term() => {
+            stop(term)?;
+            Ok(Key::Unknown)
+        }
+        key => Ok(key),
+    }
+}
+
+/// How often the process was stopped with Ctrl+Z and continued.
+static STOPS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The prompt on the line of the cursor, if any.
+    static LINE_PROMPT: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Returns how often the process was stopped with Ctrl+Z so far.
+///
+/// The shell prints below the prompt meanwhile, so renderers start anew
+/// when this changed.
+pub(crate) fn stops() -> usize {
+    STOPS.load(Ordering::SeqCst)
+}
+
+/// Sets the prompt `stop` writes again on the line of the cursor.
+pub(crate) fn set_line_prompt(prompt: &str) {
+    LINE_PROMPT.with(|line_prompt| {
+        let mut line_prompt = line_prompt.borrow_mut();
+        line_prompt.clear();
+        line_prompt.push_str(prompt);
+    });
+}
+
+/// Stops the process with `SIGTSTP` like Ctrl+Z outside of raw mode.
+///
+/// The cursor, mouse reporting and main screen are restored for the
+/// shell and set up again once the process continues, after which the
+/// prompt of the cursor's line is written again.
+fn stop(term: &dyn Backend) -> io::Result<()> {
+    let hidden = cursor_hidden();
+    let captured = mouse::is_captured();
+    let alternate_screen = alternate_screen_active();
+    if captured {
+        term.write_str(mouse::CAPTURE_OFF)?;
+    }
+    if alternate_screen {
+        term.write_str("\x1b[?1049l")?;
+    }
+    term.show_cursor()?;
+    term.write_line("")?;
+    term.flush()?;
+    raise_stop();
+    STOPS.fetch_add(1, Ordering::SeqCst);
+    if alternate_screen {
+        term.write_str("\x1b[?1049h\x1b[H")?;
+    }
+    if hidden {
+        term.hide_cursor()?;
+    }
+    if captured {
+        term.write_str(mouse::CAPTURE_ON)?;
+    }
+    LINE_PROMPT.with(|prompt| term.write_str(&prompt.borrow()))?;
+    term.flush()
+}
+
+#[cfg(unix)]
+fn raise_stop() {
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+}
+
+#[cfg(not(unix))]
+fn raise_stop() {}
+
+/// Reads a single character from the terminal.
+///
+/// Enter is reported as `'\n'`, all other keys are ignored.  The prompt
+/// on the line of the cursor is written again if the terminal is resized
+/// meanwhile.
+pub(crate) fn read_char(term: &dyn Backend) -> io::Result<char> {
+    if !term.is_term() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotConnected,
+            "Not a terminal",
+        ));
+    }
+    loop {
+        wait_for_line_key(term, None, "")?;
+        match read_key(term)? {
+            Key::Char(c) => return Ok(c),
+            Key::Enter => return Ok('\n'),
+            _ => {}
+        }
+    }
+}
+
+/// Reads a line of input, echoing it back to the terminal.
+pub(crate) fn read_line(term: &dyn Backend) -> io::Result<String> {
+    Ok(read_line_impl(term, true, "", None, None, None)?.unwrap_or_default())
+}
+
+/// Like `read_line` but starts out with `initial` as the input and
+/// completes it with `completer` on tab.
+///
+/// Returns `None` if the user pressed the `skip` key.  If no key is
+/// pressed for `timeout` an error of kind `TimedOut` is returned.
+pub(crate) fn read_edited_line(
+    term: &dyn Backend,
+    initial: &str,
+    completer: Option<&dyn Completer>,
+    skip: Option<&Key>,
+    timeout: Option<Duration>,
+) -> io::Result<Option<String>> {
+    read_line_impl(term, true, initial, completer, skip, timeout)
+}
+
+/// Reads a line of input without echoing it.
+///
+/// If no key is pressed for `timeout` an error of kind `TimedOut` is
+/// returned.
+pub(crate) fn read_secure_line(
+    term: &dyn Backend,
+    timeout: Option<Duration>,
+) -> io::Result<String> {
+    Ok(read_line_impl(term, false, "", None, None, timeout)?.unwrap_or_default())
+}
+
+/// Reads a line of input without any cursor movement.
+///
+/// Unlike `read_line` this also works if the terminal is not a tty, in
+/// which case the line is read from stdin.  End of input is reported as an
+/// error of kind `UnexpectedEof`.
+pub(crate) fn read_plain_line(term: &dyn Backend) -> io::Result<String> {
+    if term.is_term() {
+        return read_line(term);
+    }
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "End of input"));
+    }
+    term.write_line("")?;
+    Ok(line.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+/// Like `read_secure_line` but returns `None` if the user pressed the
+/// `skip` key.
+pub(crate) fn read_hidden_line(
+    term: &dyn Backend,
+    skip: Option<&Key>,
+    timeout: Option<Duration>,
+) -> io::Result<Option<String>> {
+    read_line_impl(term, false, "", None, skip, timeout)
+}
+
+fn read_line_impl(
+    term: &dyn Backend,
+    echo: bool,
+    initial: &str,
+    completer: Option<&dyn Completer>,
+    skip: Option<&Key>,
+    timeout: Option<Duration>,
+) -> io::Result<Option<String>> {
+    if !term.is_term() {
+        return Ok(Some("".into()));
+    }
+    let mut input = initial.to_string();
+    if echo && !initial.is_empty() {
+        term.write_str(&visual_order(initial))?;
+    }
+    loop {
+        let shown = if echo {
+            visual_order(&input)
+        } else {
+            "".into()
+        };
+        if !wait_for_line_key(term, timeout, &shown)? {
+            term.write_line("")?;
+            return Err(timed_out());
+        }
+        let stopped = stops();
+        let key = read_key(term)?;
+        if stopped != stops() && echo {
+            term.write_str(&visual_order(&input))?;
+        }
+        if skip == Some(&key) {
+            term.write_line("")?;
+            return Ok(None);
+        }
+        match key {
+            Key::Backspace if echo && has_rtl(&input) => {
+                let width = measure_text_width(&input);
+                pop_grapheme(&mut input);
+                redraw_input(term, width, &input)?;
+            }
+            Key::Backspace => {
+                if let Some(width) = pop_grapheme(&mut input) {
+                    if echo && width > 0 {
+                        term.clear_chars(width)?;
+                    }
+                }
+            }
+            Key::Char(c) => {
+                let width = measure_text_width(&input);
+                input.push(c);
+                if echo && has_rtl(&input) {
+                    redraw_input(term, width, &input)?;
+                } else if echo {
+                    term.write_str(c.encode_utf8(&mut [0; 4]))?;
+                }
+            }
+            Key::Tab if completer.is_some() => {
+                if let Some(completed) = completer.unwrap().complete(&input) {
+                    if echo {
+                        redraw_input(term, measure_text_width(&input), &completed)?;
+                    }
+                    input = completed;
+                }
+            }
+            Key::Enter => {
+                term.write_line("")?;
+                return Ok(Some(input));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replaces the echoed input that took up `width` columns with `input`.
+///
+/// Right-to-left text is reordered as a whole, so it has to be redrawn
+/// on every change.
+fn redraw_input(term: &dyn Backend, width: usize, input: &str) -> io::Result<()> {
+    if width > 0 {
+        term.clear_chars(width)?;
+    }
+    term.write_str(&visual_order(input))
+}
+
+/// Removes the last grapheme cluster, e.g. a letter with its combining
+/// accents or an emoji sequence, from `input`.
+///
+/// Returns the number of columns the removed text took up.
+pub(crate) fn pop_grapheme(input: &mut String) -> Option<usize> {
+    let (idx, width) = input
+        .grapheme_indices(true)
+        .next_back()
+        .map(|(idx, grapheme)| (idx, measure_text_width(grapheme)))?;
+    input.truncate(idx);
+    Some(width)
+}
+
+/// How often the terminal size is checked while waiting for input.
+const RESIZE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The reason `wait_for_event` returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Event {
+    /// A key is ready to be read.
+    Key,
+    /// The terminal was resized.
+    Resize,
+    /// The timeout elapsed without any input.
+    Timeout,
+}
+
+/// Waits until the user presses a key.
+///
+/// Returns `false` if the timeout elapsed without any input.  Without a
+/// timeout, or when reading from something other than a terminal, this
+/// returns `true` right away.
+pub(crate) fn wait_for_key(term: &dyn Backend, timeout: Option<Duration>) -> io::Result<bool> {
+    Ok(wait_for_event(term, timeout, false)? != Event::Timeout)
+}
+
+/// Waits until the user presses a key, the timeout elapses or, if
+/// `watch_resize` is set, the terminal changes its size.
+pub(crate) fn wait_for_event(
+    term: &dyn Backend,
+    timeout: Option<Duration>,
+    watch_resize: bool,
+) -> io::Result<Event> {
+    if !term.is_term() || (timeout.is_none() && !watch_resize) {
+        return Ok(Event::Key);
+    }
+    let _session = PollSession::new();
+    let size = term.size();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if watch_resize && term.size() != size {
+            return Ok(Event::Resize);
+        }
+        let slice = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(Event::Timeout);
+                }
+                let remaining = deadline - now;
+                if watch_resize {
+                    remaining.min(RESIZE_POLL_INTERVAL)
+                } else {
+                    remaining
+                }
+            }
+            None => RESIZE_POLL_INTERVAL,
+        };
+        if term.poll_key(slice)? {
+            return Ok(Event::Key);
+        }
+    }
+}
+
+/// Like `wait_for_key` but writes the prompt on the line of the cursor and
+/// the `shown` input after it again whenever the terminal is resized, as
+/// the old rendering may have wrapped differently.
+pub(crate) fn wait_for_line_key(
+    term: &dyn Backend,
+    timeout: Option<Duration>,
+    shown: &str,
+) -> io::Result<bool> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        match wait_for_event(term, remaining, true)? {
+            Event::Key => return Ok(true),
+            Event::Timeout => return Ok(false),
+            Event::Resize => {
+                let line = LINE_PROMPT.with(|prompt| {
+                    let prompt = prompt.borrow();
+                    format!("{}{}", prompt.rsplit('\n').next().unwrap_or(""), shown)
+                });
+                let width = term.size().1 as usize;
+                let rows = measure_text_width(&line).div_ceil(width.max(1)).max(1);
+                term.clear_line()?;
+                term.clear_last_lines(rows - 1)?;
+                term.write_str(&line)?;
+                term.flush()?;
+            }
+        }
+    }
+}
+
+/// Creates the error returned by prompts without a default that timed out.
+pub(crate) fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "prompt timed out")
+}
+
+/// Creates the error returned when the user hit Ctrl+C.
+pub(crate) fn interrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "prompt interrupted")
+}
+
+/// Keeps the terminal set up for polling while it is held.
+///
+/// Without a session `poll_input` opens the terminal and switches its mode
+/// for every call.  Within one this happens on the first call only and is
+/// undone when the outermost session ends, so waiting in short slices does
+/// not reconfigure the terminal every time.  Backends that do not poll the
+/// terminal never touch it.
+pub(crate) struct PollSession(());
+
+#[cfg(unix)]
+thread_local! {
+    static POLL_SESSIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    static POLL_MODE: RefCell<Option<unix_poll::PollMode>> = const { RefCell::new(None) };
+}
+
+impl PollSession {
+    pub fn new() -> PollSession {
+        #[cfg(unix)]
+        POLL_SESSIONS.with(|sessions| sessions.set(sessions.get() + 1));
+        PollSession(())
+    }
+}
+
+impl Drop for PollSession {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            let left = POLL_SESSIONS.with(|sessions| {
+                sessions.set(sessions.get() - 1);
+                sessions.get()
+            });
+            if left == 0 {
+                POLL_MODE.with(|mode| mode.borrow_mut().take());
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+mod unix_poll {
+    use std::fs;
+    use std::io;
+    use std::mem;
+    use std::os::unix::io::AsRawFd;
+
+    pub fn check(rv: libc::c_int) -> io::Result<libc::c_int> {
+        if rv < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(rv)
+        }
+    }
+
+    /// The terminal switched to the mode used for polling, restored on drop.
+    pub struct PollMode {
+        pub fd: libc::c_int,
+        original: libc::termios,
+        _tty: Option<fs::File>,
+    }
+
+    impl PollMode {
+        pub fn new() -> io::Result<PollMode> {
+            // console reads keys from stdin if it is a terminal and from the
+            // controlling terminal otherwise, so poll the same source.
+            let (fd, tty) = if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+                (libc::STDIN_FILENO, None)
+            } else {
+                let tty = fs::File::open("/dev/tty")?;
+                (tty.as_raw_fd(), Some(tty))
+            };
+
+            // In canonical mode the terminal only reports input once a full
+            // line was entered, so switch that off while waiting.  Pending
+            // input is left in the queue for the actual read, including a
+            // Ctrl+C which would otherwise raise a signal.
+            let mut termios: libc::termios = unsafe { mem::zeroed() };
+            check(unsafe { libc::tcgetattr(fd, &mut termios) })?;
+            let original = termios;
+            termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            termios.c_cc[libc::VMIN] = 1;
+            termios.c_cc[libc::VTIME] = 0;
+            check(unsafe { libc::tcsetattr(fd, libc::TCSANOW, &termios) })?;
+            Ok(PollMode {
+                fd,
+                original,
+                _tty: tty,
+            })
+        }
+    }
+
+    impl Drop for PollMode {
+        fn drop(&mut self) {
+            unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.original) };
+        }
+    }
+}
+
+/// Polls stdin or the controlling terminal for input in slices.
+///
+/// `next_slice` is invoked before each slice and returns how long to wait
+/// for, or `None` to give up.  Returns `true` if input is available.
+#[cfg(unix)]
+pub(crate) fn poll_input<F: FnMut() -> Option<Duration>>(mut next_slice: F) -> io::Result<bool> {
+    use self::unix_poll::{check, PollMode};
+
+    let _session = PollSession::new();
+    let fd = POLL_MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        if let Some(ref mode) = *mode {
+            return Ok(mode.fd);
+        }
+        let new = PollMode::new()?;
+        let fd = new.fd;
+        *mode = Some(new);
+        Ok::<_, io::Error>(fd)
+    })?;
+
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    loop {
+        let slice = match next_slice() {
+            Some(slice) => slice,
+            None => return Ok(false),
+        };
+        let millis = slice.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        match check(unsafe { libc::poll(&mut pollfd, 1, millis) }) {
+            Ok(0) => continue,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            rv => return rv.map(|_| true),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn poll_input<F: FnMut() -> Option<Duration>>(_next_slice: F) -> io::Result<bool> {
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::{Cell, RefCell};
+
+    /// A terminal that narrows to 10 columns once it was polled.
+    #[derive(Default)]
+    struct Narrowing {
+        polled: Cell<bool>,
+        output: RefCell<String>,
+    }
+
+    impl Backend for Narrowing {
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            self.output.borrow_mut().push_str(s);
+            Ok(())
+        }
+
+        fn read_key(&self) -> io::Result<Key> {
+            Ok(Key::Enter)
+        }
+
+        fn size(&self) -> (u16, u16) {
+            (5, if self.polled.get() { 10 } else { 80 })
+        }
+
+        fn is_term(&self) -> bool {
+            true
+        }
+
+        fn poll_key(&self, _timeout: Duration) -> io::Result<bool> {
+            Ok(self.polled.replace(true))
+        }
+    }
+
+    #[test]
+    fn test_resize_redraws_line() {
+        let term = Narrowing::default();
+        set_line_prompt("Hostname: ");
+        assert!(wait_for_line_key(&term, None, "db-1").unwrap());
+        set_line_prompt("");
+        assert_eq!(
+            *term.output.borrow(),
+            "\r\x1b[2K\x1b[1A\r\x1b[0JHostname: db-1"
+        );
+    }
+
+    #[test]
+    fn test_pop_grapheme() {
+        let mut input = "ae\u{301}日👍🏽".to_string();
+        assert_eq!(pop_grapheme(&mut input), Some(2));
+        assert_eq!(pop_grapheme(&mut input), Some(2));
+        assert_eq!(input, "ae\u{301}");
+        assert_eq!(pop_grapheme(&mut input), Some(1));
+        assert_eq!(input, "a");
+        pop_grapheme(&mut input);
+        assert_eq!(pop_grapheme(&mut input), None);
+    }
+}
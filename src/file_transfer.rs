@@ -0,0 +1,628 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use console::{measure_text_width, truncate_str, Key, Style};
+
+use backend::{Backend, HiddenCursor, TermGuard};
+use events::{read_key, read_plain_line, wait_for_event, Event};
+use keymap::{Action, KeyMap};
+use messages::{fill, messages};
+use observer::observe;
+use paging::Paging;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{
+    get_default_theme, SelectionStyle, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef,
+};
+use tree_select::TreeSelect;
+
+/// One side of a file transfer prompt: a directory and its entries.
+struct Pane {
+    dir: PathBuf,
+    /// The names of the entries and whether they are directories.
+    entries: Vec<(String, bool)>,
+    sel: usize,
+    dirs_only: bool,
+    paging: Paging,
+}
+
+impl Pane {
+    fn open(dir: PathBuf, dirs_only: bool, hidden: bool, paged: bool) -> io::Result<Pane> {
+        let entries = list_dir(&dir, dirs_only, hidden)?;
+        Ok(Pane {
+            paging: Paging::new(entries.len(), paged),
+            dir,
+            entries,
+            sel: 0,
+            dirs_only,
+        })
+    }
+
+    /// Returns the path of the highlighted entry.
+    fn selected(&self) -> Option<PathBuf> {
+        self.entries
+            .get(self.sel)
+            .map(|(name, _)| self.dir.join(name))
+    }
+
+    /// Shows the highlighted directory in place of the current one.
+    fn enter(&mut self, hidden: bool) {
+        let dir = match self.entries.get(self.sel) {
+            Some(&(ref name, true)) => self.dir.join(name),
+            _ => return,
+        };
+        if let Ok(entries) = list_dir(&dir, self.dirs_only, hidden) {
+            self.dir = dir;
+            self.entries = entries;
+            self.sel = 0;
+        }
+    }
+
+    /// Shows the parent of the current directory with the current one
+    /// highlighted.
+    fn leave(&mut self, hidden: bool) {
+        let dir = match self.dir.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => return,
+        };
+        if let Ok(entries) = list_dir(&dir, self.dirs_only, hidden) {
+            let name = self.dir.file_name().map(|name| name.to_string_lossy());
+            self.sel = entries
+                .iter()
+                .position(|entry| Some(entry.0.as_str()) == name.as_deref())
+                .unwrap_or(0);
+            self.dir = dir;
+            self.entries = entries;
+        }
+    }
+}
+
+/// Lists the entries of `dir` sorted by name with directories first.
+fn list_dir(dir: &Path, dirs_only: bool, hidden: bool) -> io::Result<Vec<(String, bool)>> {
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let is_dir = entry.file_type()?.is_dir();
+        if (!hidden && name.starts_with('.')) || (dirs_only && !is_dir) {
+            continue;
+        }
+        entries.push((!is_dir, name));
+    }
+    entries.sort();
+    Ok(entries
+        .into_iter()
+        .map(|(file, name)| (name, !file))
+        .collect())
+}
+
+/// Renders a source and a destination directory side by side to pick a
+/// file and the directory to copy or move it to.
+///
+/// The source pane lists files and directories, the destination pane
+/// only directories.  Tab moves the focus to the other pane, the right
+/// arrow key opens the highlighted directory and the left one goes up to
+/// the parent.  Confirming returns the highlighted source entry and the
+/// directory the destination pane shows.
+///
+/// Entries are sorted by name with directories first.  Directories that
+/// cannot be read are not opened.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::FileTransferPrompt;
+///
+/// let (source, destination) = FileTransferPrompt::new("downloads", "backup")
+///     .with_prompt("Copy")
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct FileTransferPrompt<'a> {
+    source: PathBuf,
+    destination: PathBuf,
+    prompt: Option<String>,
+    hidden: bool,
+    clear: bool,
+    theme: ThemeRef<'a>,
+    paged: bool,
+    keymap: KeyMap,
+    stream: Stream,
+}
+
+impl<'a> FileTransferPrompt<'a> {
+    /// Creates a prompt whose panes start out in the given directories.
+    pub fn new<S, D>(source: S, destination: D) -> FileTransferPrompt<'static>
+    where
+        S: Into<PathBuf>,
+        D: Into<PathBuf>,
+    {
+        FileTransferPrompt::with_theme(source, destination, get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme<S, D>(
+        source: S,
+        destination: D,
+        theme: &'a (dyn Theme + Sync),
+    ) -> FileTransferPrompt<'a>
+    where
+        S: Into<PathBuf>,
+        D: Into<PathBuf>,
+    {
+        FileTransferPrompt::with_theme_ref(
+            source.into(),
+            destination.into(),
+            ThemeRef::Borrowed(theme),
+        )
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<S, D, T>(
+        source: S,
+        destination: D,
+        theme: T,
+    ) -> FileTransferPrompt<'static>
+    where
+        S: Into<PathBuf>,
+        D: Into<PathBuf>,
+        T: Theme + Send + Sync + 'static,
+    {
+        FileTransferPrompt::with_theme_ref(
+            source.into(),
+            destination.into(),
+            ThemeRef::Owned(Arc::new(theme)),
+        )
+    }
+
+    fn with_theme_ref(
+        source: PathBuf,
+        destination: PathBuf,
+        theme: ThemeRef<'a>,
+    ) -> FileTransferPrompt<'a> {
+        FileTransferPrompt {
+            source,
+            destination,
+            prompt: None,
+            hidden: false,
+            clear: true,
+            theme,
+            paged: false,
+            keymap: FileTransferPrompt::default_keymap(),
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Returns the default key bindings of the prompt.
+    ///
+    /// These are the bindings of `TreeSelect::default_keymap` with tab
+    /// switching between the panes.
+    pub fn default_keymap() -> KeyMap {
+        let mut keymap = TreeSelect::default_keymap();
+        keymap.bind(Key::Tab, Action::SwitchPane);
+        keymap
+    }
+
+    /// Prefaces the panes with a prompt.
+    ///
+    /// When a prompt is set the system also prints out a confirmation after
+    /// the selection.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut FileTransferPrompt<'a> {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut FileTransferPrompt<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted entry for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut FileTransferPrompt<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets whether entries whose name starts with a dot are listed.
+    ///
+    /// The default is `false`.
+    pub fn hidden(&mut self, val: bool) -> &mut FileTransferPrompt<'a> {
+        self.hidden = val;
+        self
+    }
+
+    /// Enables or disables paging
+    pub fn paged(&mut self, val: bool) -> &mut FileTransferPrompt<'a> {
+        self.paged = val;
+        self
+    }
+
+    /// Sets the clear behavior of the panes.
+    ///
+    /// The default is to clear the panes.
+    pub fn clear(&mut self, val: bool) -> &mut FileTransferPrompt<'a> {
+        self.clear = val;
+        self
+    }
+
+    /// Sets the key bindings of the prompt.
+    ///
+    /// Start from `FileTransferPrompt::default_keymap` to keep the bindings
+    /// for switching panes and opening directories.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut FileTransferPrompt<'a> {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut FileTransferPrompt<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the source and destination.
+    ///
+    /// The dialog is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<(PathBuf, PathBuf)> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns `None` if the user cancelled with Esc or 'q'.
+    pub fn interact_opt(&self) -> io::Result<Option<(PathBuf, PathBuf)>> {
+        self._interact_on(&self.stream.term(), true)
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<(PathBuf, PathBuf)>>
+    where
+        'a: 'static,
+    {
+        let empty = FileTransferPrompt::new(PathBuf::new(), PathBuf::new());
+        run_detached_mut(self, empty, |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<(PathBuf, PathBuf)> {
+        self._interact_on(term, false)?
+            .ok_or_else(|| io::Error::other("Quit not allowed in this case"))
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<(PathBuf, PathBuf)>> {
+        self._interact_on(term, true)
+    }
+
+    fn _interact_on(
+        &self,
+        term: &dyn Backend,
+        allow_quit: bool,
+    ) -> io::Result<Option<(PathBuf, PathBuf)>> {
+        let prompt = self.prompt.as_deref().unwrap_or("");
+        observe(
+            prompt,
+            || self.read_answer(term, allow_quit),
+            |rv| {
+                rv.as_ref()
+                    .map(|(source, destination)| transfer_label(source, destination))
+            },
+        )
+    }
+
+    fn read_answer(
+        &self,
+        term: &dyn Backend,
+        allow_quit: bool,
+    ) -> io::Result<Option<(PathBuf, PathBuf)>> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render, allow_quit);
+        }
+        let mut panes = [
+            Pane::open(self.source.clone(), false, self.hidden, self.paged)?,
+            Pane::open(self.destination.clone(), true, self.hidden, self.paged)?,
+        ];
+        let mut focus = 0;
+        let _cursor = HiddenCursor::new(term)?;
+        let mut show_keys = false;
+        let mut actions = vec![
+            Action::Up,
+            Action::Down,
+            Action::Expand,
+            Action::Collapse,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::SwitchPane,
+            Action::Confirm,
+        ];
+        if allow_quit {
+            actions.push(Action::Cancel);
+        }
+        actions.push(Action::Help);
+        let keys = self.keymap.help_lines(&actions);
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        loop {
+            let width = (term.size().1 as usize / 2).max(2);
+            // the titles of the panes take one row
+            let reserved = render.prompt_rows() + 1 + if show_keys { keys.len() } else { 0 };
+            for pane in &mut panes {
+                pane.paging.set_items(pane.entries.len());
+                pane.paging.update(term, reserved, pane.sel);
+            }
+            render.begin_frame();
+            let messages = messages();
+            let mut cells = vec![];
+            for (label, pane) in [&messages.source, &messages.destination].iter().zip(&panes) {
+                // the header marker and the gap between the panes take three
+                // columns, so the path gets what is left after the label
+                let room = width.saturating_sub(measure_text_width(label) + 5);
+                let dir = truncate_start(&pane.dir.to_string_lossy(), room);
+                let mut buf = String::new();
+                self.theme
+                    .format_table_header(&mut buf, &format!("{}: {}", label, dir))
+                    .map_err(io::Error::other)?;
+                cells.push(truncate_str(&buf, width - 1, "…").into_owned());
+            }
+            render.grid_row(&cells, width)?;
+            let rows = panes[0].paging.capacity().max(panes[1].paging.capacity());
+            for row in 0..rows {
+                let mut cells = vec![];
+                for (idx, pane) in panes.iter().enumerate() {
+                    let entry = match pane.entries.get(pane.paging.offset() + row) {
+                        Some(entry) if row < pane.paging.capacity() => entry,
+                        _ => {
+                            cells.push(String::new());
+                            continue;
+                        }
+                    };
+                    let label = if entry.1 {
+                        format!("{}/", entry.0)
+                    } else {
+                        entry.0.clone()
+                    };
+                    let style = if idx == focus && pane.paging.offset() + row == pane.sel {
+                        SelectionStyle::MenuSelected
+                    } else {
+                        SelectionStyle::MenuUnselected
+                    };
+                    let mut buf = String::new();
+                    self.theme
+                        .format_selection(&mut buf, &label, style)
+                        .map_err(io::Error::other)?;
+                    cells.push(truncate_str(&buf, width - 1, "…").into_owned());
+                }
+                render.grid_row(&cells, width)?;
+            }
+            if let Some(indicator) = panes[focus].paging.indicator(panes[focus].sel) {
+                render.hint(&indicator)?;
+            }
+            if show_keys {
+                for key in &keys {
+                    render.hint(key)?;
+                }
+            }
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                if let Some(ref prompt) = self.prompt {
+                    render.prompt(prompt)?;
+                }
+                continue;
+            }
+            let action = self
+                .keymap
+                .action(&render.clear_interrupted(read_key(term))?);
+            let pane = &mut panes[focus];
+            if let Some(sel) = action.and_then(|action| pane.paging.handle_action(action, pane.sel))
+            {
+                pane.sel = sel;
+                continue;
+            }
+            let count = pane.entries.len().max(1);
+            match action {
+                Some(Action::Down) => pane.sel = (pane.sel + 1) % count,
+                Some(Action::Up) => pane.sel = (pane.sel + count - 1) % count,
+                Some(Action::Expand) => pane.enter(self.hidden),
+                Some(Action::Collapse) => pane.leave(self.hidden),
+                Some(Action::SwitchPane) => focus = 1 - focus,
+                Some(Action::Confirm) => {
+                    if let Some(source) = panes[0].selected() {
+                        let destination = panes[1].dir.clone();
+                        if self.clear {
+                            render.clear()?;
+                        }
+                        self.report(&mut render, &source, &destination)?;
+                        return Ok(Some((source, destination)));
+                    }
+                }
+                Some(Action::Cancel) if allow_quit => {
+                    if self.clear {
+                        render.clear_preserve_prompt()?;
+                    }
+                    return Ok(None);
+                }
+                Some(Action::Help) => show_keys = !show_keys,
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the source and the destination as paths relative to the
+    /// directories the panes would start out in.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        allow_quit: bool,
+    ) -> io::Result<Option<(PathBuf, PathBuf)>> {
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        let source = match self.read_path(term, render, &self.source, false, allow_quit)? {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+        let destination = match self.read_path(term, render, &self.destination, true, allow_quit)? {
+            Some(destination) => destination,
+            None => return Ok(None),
+        };
+        self.report(render, &source, &destination)?;
+        Ok(Some((source, destination)))
+    }
+
+    /// Reads a path below `dir` until one exists, and is a directory if
+    /// `dir_only` is set.  An empty answer picks `dir` for directories.
+    fn read_path(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        dir: &Path,
+        dir_only: bool,
+        allow_quit: bool,
+    ) -> io::Result<Option<PathBuf>> {
+        let messages = messages();
+        let (label, default) = if dir_only {
+            (&messages.destination, Some(dir.to_string_lossy()))
+        } else {
+            (&messages.source, None)
+        };
+        loop {
+            render.input_prompt(label, default.as_deref())?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let input = input.trim();
+            if allow_quit && input == "q" {
+                return Ok(None);
+            }
+            if input.is_empty() && !dir_only {
+                continue;
+            }
+            let path = dir.join(input);
+            if !path.exists() {
+                render.error(&fill(&messages.missing_path, &[("path", &input)]))?;
+            } else if dir_only && !path.is_dir() {
+                render.error(&fill(&messages.not_a_directory, &[("path", &input)]))?;
+            } else {
+                return Ok(Some(path));
+            }
+        }
+    }
+
+    fn report(
+        &self,
+        render: &mut TermThemeRenderer,
+        source: &Path,
+        destination: &Path,
+    ) -> io::Result<()> {
+        match self.prompt {
+            Some(ref prompt) => {
+                render.single_prompt_selection(prompt, &transfer_label(source, destination))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Cuts off the start of `text` so that it fits into `width` columns.
+fn truncate_start(text: &str, width: usize) -> String {
+    if measure_text_width(text) <= width {
+        return text.to_string();
+    }
+    text.char_indices()
+        .map(|(idx, _)| &text[idx..])
+        .find(|rest| measure_text_width(rest) < width)
+        .map_or_else(String::new, |rest| format!("…{}", rest))
+}
+
+/// Describes a transfer for the report line.
+fn transfer_label(source: &Path, destination: &Path) -> String {
+    format!("{} -> {}", source.display(), destination.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::process;
+
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_file_transfer() {
+        let dir = env::temp_dir().join(format!("dialoguer-file-transfer-{}", process::id()));
+        fs::create_dir_all(dir.join("src/docs")).unwrap();
+        fs::create_dir_all(dir.join("dst/old")).unwrap();
+        for file in &["src/a.txt", "src/docs/b.txt", "dst/notes.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[
+            Key::ArrowRight,
+            Key::Tab,
+            Key::ArrowRight,
+            Key::Tab,
+            Key::Enter,
+        ]);
+        let (source, destination) = FileTransferPrompt::new(dir.join("src"), dir.join("dst"))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(source, dir.join("src/docs/b.txt"));
+        assert_eq!(destination, dir.join("dst/old"));
+        let rows = |frame: &str| frame.lines().skip(1).map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            rows(&term.frames()[0]),
+            vec!["> docs/               old/", "  a.txt"]
+        );
+        assert_eq!(rows(&term.frames()[2]), vec!["  b.txt             > old/"]);
+        assert!(term.frames()[3].starts_with("  Source: …src/docs   Destination: …old"));
+
+        let mut pane = Pane::open(dir.join("src/docs"), false, false, false).unwrap();
+        pane.leave(false);
+        assert_eq!(pane.selected(), Some(dir.join("src/docs")));
+        assert_eq!(
+            pane.entries,
+            vec![("docs".to_string(), true), ("a.txt".to_string(), false)]
+        );
+        let mut pane = Pane::open(dir.join("dst"), true, false, false).unwrap();
+        assert_eq!(pane.entries, vec![("old".to_string(), true)]);
+        pane.enter(false);
+        assert_eq!(pane.dir, dir.join("dst/old"));
+        assert_eq!(pane.selected(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(FileTransferPrompt::new("a", "b"));
+        assert_send(FileTransferPrompt::with_owned_theme(
+            "a",
+            "b",
+            ColorfulTheme::default(),
+        ));
+    }
+}
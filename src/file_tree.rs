@@ -0,0 +1,600 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use console::Style;
+
+use backend::{Backend, HiddenCursor, TermGuard};
+use events::{read_key, read_plain_line, wait_for_event, Event};
+use keymap::{Action, KeyMap};
+use messages::{fill, messages};
+use observer::observe;
+use paging::Paging;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, CheckState, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+use tree_select::TreeSelect;
+
+/// A file or directory below the root of a file tree.
+struct Entry {
+    path: PathBuf,
+    name: String,
+    depth: usize,
+    dir: bool,
+}
+
+/// The entries of a directory and their check marks.
+struct Tree {
+    entries: Vec<Entry>,
+    /// The index after the last descendant of each entry.
+    ends: Vec<usize>,
+    checked: Vec<bool>,
+}
+
+impl Tree {
+    fn new(entries: Vec<Entry>) -> Tree {
+        let mut ends = vec![entries.len(); entries.len()];
+        let mut open: Vec<usize> = vec![];
+        for (idx, entry) in entries.iter().enumerate() {
+            while let Some(&last) = open.last() {
+                if entries[last].depth < entry.depth {
+                    break;
+                }
+                ends[last] = idx;
+                open.pop();
+            }
+            open.push(idx);
+        }
+        let checked = vec![false; entries.len()];
+        Tree {
+            entries,
+            ends,
+            checked,
+        }
+    }
+
+    /// Returns `true` for files and for directories without listed
+    /// entries, which are what can be selected.
+    fn is_leaf(&self, idx: usize) -> bool {
+        self.ends[idx] == idx + 1
+    }
+
+    /// Returns whether an entry is checked, for directories derived from
+    /// the leaves below them.
+    fn state(&self, idx: usize) -> CheckState {
+        let leaves: Vec<bool> = (idx..self.ends[idx])
+            .filter(|&leaf| self.is_leaf(leaf))
+            .map(|leaf| self.checked[leaf])
+            .collect();
+        if leaves.iter().all(|&checked| checked) {
+            CheckState::Checked
+        } else if leaves.iter().any(|&checked| checked) {
+            CheckState::Partial
+        } else {
+            CheckState::Unchecked
+        }
+    }
+
+    /// Checks an entry and everything below it, or unchecks them all if
+    /// they are all checked.
+    fn toggle(&mut self, idx: usize) {
+        let check = self.state(idx) != CheckState::Checked;
+        for checked in &mut self.checked[idx..self.ends[idx]] {
+            *checked = check;
+        }
+    }
+
+    fn toggle_all(&mut self) {
+        let check = !self.checked.iter().all(|&checked| checked);
+        for checked in &mut self.checked {
+            *checked = check;
+        }
+    }
+
+    /// Returns the checked leaves.
+    fn selections(&self) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|&idx| self.is_leaf(idx) && self.checked[idx])
+            .collect()
+    }
+
+    /// Returns the indices of the entries not hidden in collapsed
+    /// directories.
+    fn visible(&self, expanded: &[bool]) -> Vec<usize> {
+        let mut visible = vec![];
+        let mut idx = 0;
+        while idx < self.entries.len() {
+            visible.push(idx);
+            idx = if expanded[idx] {
+                idx + 1
+            } else {
+                self.ends[idx]
+            };
+        }
+        visible
+    }
+
+    /// Returns the directory an entry is in, unless that is the root.
+    fn parent(&self, idx: usize) -> Option<usize> {
+        (0..idx).rev().find(|&parent| self.ends[parent] > idx)
+    }
+}
+
+/// Renders the files below a directory as a checkbox tree.
+///
+/// Directories are expanded and collapsed like in a `TreeSelect`.  Space
+/// checks an entry; checking a directory checks everything below it, and
+/// directories of which only some entries are checked show a partial
+/// check mark.  The result is the paths of the checked files, in the
+/// order they are listed, plus those of checked empty directories.
+///
+/// Entries are sorted by name with directories first.  Symbolic links
+/// are listed but never followed.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::FileTree;
+///
+/// let paths = FileTree::new("assets")
+///     .with_prompt("Files to upload")
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct FileTree<'a> {
+    root: PathBuf,
+    prompt: Option<String>,
+    hidden: bool,
+    max_depth: Option<usize>,
+    clear: bool,
+    theme: ThemeRef<'a>,
+    paged: bool,
+    keymap: KeyMap,
+    stream: Stream,
+}
+
+impl<'a> FileTree<'a> {
+    /// Creates a file tree of the directory `root`.
+    pub fn new<P: Into<PathBuf>>(root: P) -> FileTree<'static> {
+        FileTree::with_theme(root, get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme<P: Into<PathBuf>>(root: P, theme: &'a (dyn Theme + Sync)) -> FileTree<'a> {
+        FileTree::with_theme_ref(root.into(), ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<P: Into<PathBuf>, T: Theme + Send + Sync + 'static>(
+        root: P,
+        theme: T,
+    ) -> FileTree<'static> {
+        FileTree::with_theme_ref(root.into(), ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(root: PathBuf, theme: ThemeRef<'a>) -> FileTree<'a> {
+        FileTree {
+            root,
+            prompt: None,
+            hidden: false,
+            max_depth: None,
+            clear: true,
+            theme,
+            paged: false,
+            keymap: TreeSelect::default_keymap(),
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Prefaces the tree with a prompt.
+    ///
+    /// When a prompt is set the system also prints out a confirmation after
+    /// the selection.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut FileTree<'a> {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut FileTree<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted item for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut FileTree<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets whether entries whose name starts with a dot are listed.
+    ///
+    /// The default is `false`.
+    pub fn hidden(&mut self, val: bool) -> &mut FileTree<'a> {
+        self.hidden = val;
+        self
+    }
+
+    /// Limits how many levels of directories are listed; 1 lists only the
+    /// entries of the root.
+    ///
+    /// Directories at the last level are selected as a whole.
+    pub fn max_depth(&mut self, val: usize) -> &mut FileTree<'a> {
+        self.max_depth = Some(val.max(1));
+        self
+    }
+
+    /// Enables or disables paging
+    pub fn paged(&mut self, val: bool) -> &mut FileTree<'a> {
+        self.paged = val;
+        self
+    }
+
+    /// Sets the clear behavior of the tree.
+    ///
+    /// The default is to clear the tree.
+    pub fn clear(&mut self, val: bool) -> &mut FileTree<'a> {
+        self.clear = val;
+        self
+    }
+
+    /// Sets the key bindings of the tree.
+    ///
+    /// Start from `TreeSelect::default_keymap` to keep the bindings for
+    /// expanding and collapsing directories.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut FileTree<'a> {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut FileTree<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the checked paths.
+    ///
+    /// The dialog is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<Vec<PathBuf>> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns `None` if the user cancelled with Esc or 'q'.
+    pub fn interact_opt(&self) -> io::Result<Option<Vec<PathBuf>>> {
+        self._interact_on(&self.stream.term(), true)
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<Vec<PathBuf>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, FileTree::new(PathBuf::new()), |prompt| {
+            prompt.interact()
+        })
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<Vec<PathBuf>> {
+        self._interact_on(term, false)?
+            .ok_or_else(|| io::Error::other("Quit not allowed in this case"))
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<Vec<PathBuf>>> {
+        self._interact_on(term, true)
+    }
+
+    fn _interact_on(
+        &self,
+        term: &dyn Backend,
+        allow_quit: bool,
+    ) -> io::Result<Option<Vec<PathBuf>>> {
+        let prompt = self.prompt.as_deref().unwrap_or("");
+        observe(
+            prompt,
+            || self.read_answer(term, allow_quit),
+            |rv| {
+                rv.as_ref().map(|paths| {
+                    let labels: Vec<String> = paths.iter().map(|path| self.display(path)).collect();
+                    labels.join(", ")
+                })
+            },
+        )
+    }
+
+    fn read_answer(
+        &self,
+        term: &dyn Backend,
+        allow_quit: bool,
+    ) -> io::Result<Option<Vec<PathBuf>>> {
+        let _guard = TermGuard::new(term);
+        let mut entries = vec![];
+        self.read_dir(&self.root, 0, &mut entries)?;
+        if entries.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "directory has no entries",
+            ));
+        }
+        let mut tree = Tree::new(entries);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render, &mut tree, allow_quit);
+        }
+        let mut expanded = vec![false; tree.entries.len()];
+        let _cursor = HiddenCursor::new(term)?;
+        let mut paging = Paging::new(tree.entries.len(), self.paged);
+        let mut sel = 0;
+        let mut show_keys = false;
+        let mut actions = vec![
+            Action::Up,
+            Action::Down,
+            Action::Expand,
+            Action::Collapse,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Toggle,
+            Action::ToggleAll,
+            Action::Confirm,
+        ];
+        if allow_quit {
+            actions.push(Action::Cancel);
+        }
+        actions.push(Action::Help);
+        let keys = self.keymap.help_lines(&actions);
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        loop {
+            let visible = tree.visible(&expanded);
+            let pos = visible.iter().position(|&idx| idx == sel).unwrap_or(0);
+            let reserved = render.prompt_rows() + if show_keys { keys.len() } else { 0 };
+            paging.set_items(visible.len());
+            paging.update(term, reserved, pos);
+            render.begin_frame();
+            for &idx in visible.iter().skip(paging.offset()).take(paging.capacity()) {
+                let entry = &tree.entries[idx];
+                let label = if entry.dir {
+                    format!("{}/", entry.name)
+                } else {
+                    entry.name.clone()
+                };
+                render.checkbox_tree_item(
+                    &label,
+                    entry.depth,
+                    if tree.is_leaf(idx) {
+                        None
+                    } else {
+                        Some(expanded[idx])
+                    },
+                    tree.state(idx),
+                    idx == sel,
+                )?;
+            }
+            if let Some(indicator) = paging.indicator(pos) {
+                render.hint(&indicator)?;
+            }
+            if show_keys {
+                for key in &keys {
+                    render.hint(key)?;
+                }
+            }
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                if let Some(ref prompt) = self.prompt {
+                    render.prompt(prompt)?;
+                }
+                continue;
+            }
+            let action = self
+                .keymap
+                .action(&render.clear_interrupted(read_key(term))?);
+            if let Some(new_pos) = action.and_then(|action| paging.handle_action(action, pos)) {
+                sel = visible[new_pos];
+                continue;
+            }
+            let group = !tree.is_leaf(sel);
+            match action {
+                Some(Action::Down) => sel = visible[(pos + 1) % visible.len()],
+                Some(Action::Up) => sel = visible[(pos + visible.len() - 1) % visible.len()],
+                Some(Action::Expand) if group => expanded[sel] = true,
+                Some(Action::Collapse) if group && expanded[sel] => expanded[sel] = false,
+                Some(Action::Collapse) => {
+                    if let Some(parent) = tree.parent(sel) {
+                        sel = parent;
+                    }
+                }
+                Some(Action::Toggle) => tree.toggle(sel),
+                Some(Action::ToggleAll) => tree.toggle_all(),
+                Some(Action::Confirm) => return self.finish(&mut render, &tree).map(Some),
+                Some(Action::Cancel) if allow_quit => {
+                    if self.clear {
+                        render.clear_preserve_prompt()?;
+                    }
+                    return Ok(None);
+                }
+                Some(Action::Help) => show_keys = !show_keys,
+                _ => {}
+            }
+        }
+    }
+
+    /// Prints all entries as a numbered list and reads the numbers of the
+    /// entries to check.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        tree: &mut Tree,
+        allow_quit: bool,
+    ) -> io::Result<Option<Vec<PathBuf>>> {
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        for (idx, entry) in tree.entries.iter().enumerate() {
+            render.numbered_item(idx + 1, &self.display(&entry.path))?;
+        }
+        loop {
+            render.input_prompt(&messages().enter_numbers, None)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            if allow_quit && input.trim() == "q" {
+                return Ok(None);
+            }
+            let mut numbers = vec![];
+            for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+                if part.is_empty() {
+                    continue;
+                }
+                match part.parse::<usize>() {
+                    Ok(number) if number >= 1 && number <= tree.entries.len() => {
+                        numbers.push(number - 1);
+                    }
+                    _ => {
+                        numbers.clear();
+                        render.error(&fill(&messages().invalid_numbers, &[("input", &part)]))?;
+                        break;
+                    }
+                }
+            }
+            if numbers.is_empty() && !input.trim().is_empty() {
+                continue;
+            }
+            for idx in numbers {
+                if tree.state(idx) != CheckState::Checked {
+                    tree.toggle(idx);
+                }
+            }
+            return self.finish(render, tree).map(Some);
+        }
+    }
+
+    /// Clears the tree and reports the checked paths.
+    fn finish(&self, render: &mut TermThemeRenderer, tree: &Tree) -> io::Result<Vec<PathBuf>> {
+        if self.clear {
+            render.clear()?;
+        }
+        let paths: Vec<PathBuf> = tree
+            .selections()
+            .into_iter()
+            .map(|idx| tree.entries[idx].path.clone())
+            .collect();
+        if let Some(ref prompt) = self.prompt {
+            let labels: Vec<String> = paths.iter().map(|path| self.display(path)).collect();
+            let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+            render.checkboxes_report(prompt, &labels)?;
+        }
+        Ok(paths)
+    }
+
+    /// Lists the entries of `dir` and, recursively, of its directories.
+    fn read_dir(&self, dir: &Path, depth: usize, entries: &mut Vec<Entry>) -> io::Result<()> {
+        let mut children = vec![];
+        for child in fs::read_dir(dir)? {
+            let child = child?;
+            let name = child.file_name().to_string_lossy().into_owned();
+            if !self.hidden && name.starts_with('.') {
+                continue;
+            }
+            children.push((!child.file_type()?.is_dir(), name, child.path()));
+        }
+        children.sort();
+        for (file, name, path) in children {
+            let descend = !file && self.max_depth != Some(depth + 1);
+            entries.push(Entry {
+                path: path.clone(),
+                name,
+                depth,
+                dir: !file,
+            });
+            if descend {
+                self.read_dir(&path, depth + 1, entries)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a path relative to the root for display.
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::process;
+
+    use console::Key;
+
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_file_tree() {
+        let dir = env::temp_dir().join(format!("dialoguer-file-tree-{}", process::id()));
+        fs::create_dir_all(dir.join("src/bin")).unwrap();
+        fs::create_dir(dir.join("empty")).unwrap();
+        for file in &["src/lib.rs", "src/bin/main.rs", "README", ".git"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[
+            Key::ArrowDown,
+            Key::ArrowRight,
+            Key::ArrowDown,
+            Key::Char(' '),
+            Key::Enter,
+        ]);
+        let paths = FileTree::new(&dir).interact_on(&term).unwrap();
+        assert_eq!(
+            term.frames()[4],
+            "  [ ] empty/\n  [-] - src/\n> [x]   + bin/\n  [ ]   lib.rs\n  [ ] README"
+        );
+        assert_eq!(paths, vec![dir.join("src/bin/main.rs")]);
+
+        let mut entries = vec![];
+        FileTree::new(&dir)
+            .max_depth(1)
+            .read_dir(&dir, 0, &mut entries)
+            .unwrap();
+        let mut tree = Tree::new(entries);
+        assert_eq!(tree.ends, vec![1, 2, 3]);
+        tree.toggle(1);
+        assert_eq!(tree.selections(), vec![1]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(FileTree::new("."));
+        assert_send(FileTree::with_owned_theme(".", ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,392 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use console::{measure_text_width, pad_str, Alignment, Style};
+
+use backend::{Backend, TermGuard};
+use messages::messages;
+use prompts::{Confirmation, Input};
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use select::Select;
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders a sequence of prompts whose answers can be edited afterwards.
+///
+/// Every answer is kept as text under the key of its field: inputs keep
+/// the entered text, confirmations `true` or `false` and selections the
+/// selected item.  `edit` re-opens a field pre-filled with its answer in
+/// place of its report line and `review` lets the user go over all
+/// answers before confirming them.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::Form;
+///
+/// let mut form = Form::new();
+/// form.input("name", "Name")
+///     .input("email", "Email")
+///     .select("plan", "Plan", &["free", "pro"]);
+/// let answers = form.interact()?;
+/// if !answers["email"].contains('@') {
+///     form.edit("email")?;
+/// }
+/// let answers = form.review()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Form<'a> {
+    fields: Vec<Field>,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+struct Field {
+    key: String,
+    prompt: String,
+    kind: FieldKind,
+    answer: Option<String>,
+}
+
+enum FieldKind {
+    Input,
+    Confirm,
+    Select(Vec<String>),
+}
+
+impl Field {
+    /// Returns the answer the way the report line shows it.
+    fn shown_answer(&self) -> Option<Cow<'_, str>> {
+        match (&self.kind, self.answer.as_ref()) {
+            (FieldKind::Confirm, Some(answer)) => {
+                Some(messages().yes_no(answer == "true").to_string().into())
+            }
+            (_, answer) => answer.map(|answer| answer.as_str().into()),
+        }
+    }
+
+    fn report(&self, render: &mut TermThemeRenderer) -> io::Result<()> {
+        let answer = match self.answer {
+            Some(ref answer) => answer,
+            None => return Ok(()),
+        };
+        match self.kind {
+            FieldKind::Input => render.single_prompt_selection(&self.prompt, answer),
+            FieldKind::Confirm => {
+                render.confirmation_prompt_selection(&self.prompt, answer == "true")
+            }
+            FieldKind::Select(_) => render.select_report(&self.prompt, answer),
+        }
+    }
+}
+
+impl<'a> Form<'a> {
+    /// Creates a new form without fields.
+    pub fn new() -> Form<'static> {
+        Form::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Form<'a> {
+        Form::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Form<'static> {
+        Form::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Form<'a> {
+        Form {
+            fields: vec![],
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Adds a text input.
+    pub fn input(&mut self, key: &str, prompt: &str) -> &mut Form<'a> {
+        self.field(key, prompt, FieldKind::Input)
+    }
+
+    /// Adds a yes/no confirmation answered with `true` or `false`.
+    pub fn confirm(&mut self, key: &str, prompt: &str) -> &mut Form<'a> {
+        self.field(key, prompt, FieldKind::Confirm)
+    }
+
+    /// Adds a selection menu answered with the selected item.
+    pub fn select<T: ToString>(&mut self, key: &str, prompt: &str, items: &[T]) -> &mut Form<'a> {
+        let items = items.iter().map(|item| item.to_string()).collect();
+        self.field(key, prompt, FieldKind::Select(items))
+    }
+
+    fn field(&mut self, key: &str, prompt: &str, kind: FieldKind) -> &mut Form<'a> {
+        self.fields.push(Field {
+            key: key.into(),
+            prompt: prompt.into(),
+            kind,
+            answer: None,
+        });
+        self
+    }
+
+    /// Sets the style of the prompt texts for this form alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Form<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompts on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Form<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Returns the answers given so far by key.
+    pub fn answers(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .answer
+                    .as_ref()
+                    .map(|answer| (field.key.clone(), answer.clone()))
+            })
+            .collect()
+    }
+
+    /// Asks every field in turn and returns the answers by key.
+    ///
+    /// Fields that were answered before start out with their answer.
+    pub fn interact(&mut self) -> io::Result<HashMap<String, String>> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<HashMap<String, String>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Form::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&mut self, term: &dyn Backend) -> io::Result<HashMap<String, String>> {
+        let _guard = TermGuard::new(term);
+        for idx in 0..self.fields.len() {
+            let answer = self.ask(term, idx)?;
+            self.fields[idx].answer = Some(answer);
+        }
+        Ok(self.answers())
+    }
+
+    /// Re-opens the field with the given key pre-filled with its answer.
+    ///
+    /// The report line of the field is replaced in place, which requires
+    /// the report lines of the form to be the last lines on the terminal.
+    /// Returns the new answer.
+    pub fn edit(&mut self, key: &str) -> io::Result<String> {
+        let term = self.stream.term();
+        self.edit_on(&term, key)
+    }
+
+    /// Like `edit` but allows a specific terminal to be set.
+    pub fn edit_on(&mut self, term: &dyn Backend, key: &str) -> io::Result<String> {
+        let _guard = TermGuard::new(term);
+        let idx = self
+            .fields
+            .iter()
+            .position(|field| field.key == key)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("No field named {}", key),
+                )
+            })?;
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        let in_place = term.is_term() && !render.is_accessible();
+        if in_place {
+            let mut rows = 0;
+            for field in &self.fields[idx..] {
+                if let Some(answer) = field.shown_answer() {
+                    rows += render.report_rows(&field.prompt, &answer)?;
+                }
+            }
+            term.clear_last_lines(rows)?;
+        }
+        let answer = self.ask(term, idx)?;
+        self.fields[idx].answer = Some(answer.clone());
+        if in_place {
+            for field in &self.fields[idx + 1..] {
+                field.report(&mut render)?;
+            }
+        }
+        Ok(answer)
+    }
+
+    /// Shows a summary of all answers and returns them once confirmed.
+    ///
+    /// The summary is a menu of the answers followed by a confirm entry.
+    /// Picking an answer re-opens its field with `edit`, picking the
+    /// confirm entry returns the answers.  Fields without an answer are
+    /// asked before the summary is shown.
+    pub fn review(&mut self) -> io::Result<HashMap<String, String>> {
+        let term = self.stream.term();
+        self.review_on(&term)
+    }
+
+    /// Like `review` but allows a specific terminal to be set.
+    pub fn review_on(&mut self, term: &dyn Backend) -> io::Result<HashMap<String, String>> {
+        let _guard = TermGuard::new(term);
+        for idx in 0..self.fields.len() {
+            if self.fields[idx].answer.is_none() {
+                let answer = self.ask(term, idx)?;
+                self.fields[idx].answer = Some(answer);
+            }
+        }
+        let mut sel = self.fields.len();
+        loop {
+            let width = self
+                .fields
+                .iter()
+                .map(|field| measure_text_width(&field.prompt))
+                .max()
+                .unwrap_or(0);
+            let mut items: Vec<String> = self
+                .fields
+                .iter()
+                .map(|field| {
+                    let prompt = pad_str(&field.prompt, width, Alignment::Left, None);
+                    format!("{}  {}", prompt, field.shown_answer().unwrap_or_default())
+                })
+                .collect();
+            let messages = messages();
+            items.push(messages.review_confirm.clone());
+            sel = Select::with_theme(&*self.theme)
+                .with_prompt(&messages.review)
+                .items(&items)
+                .default(sel)
+                .report(false)
+                .interact_on(term)?;
+            if sel == self.fields.len() {
+                return Ok(self.answers());
+            }
+            let key = self.fields[sel].key.clone();
+            self.edit_on(term, &key)?;
+        }
+    }
+
+    /// Runs the prompt of a field, starting out with its previous answer.
+    fn ask(&self, term: &dyn Backend, idx: usize) -> io::Result<String> {
+        let field = &self.fields[idx];
+        let prev = field.answer.as_deref();
+        match field.kind {
+            FieldKind::Input => {
+                let mut input = Input::<String>::with_theme(&*self.theme);
+                input.with_prompt(&field.prompt);
+                if let Some(prev) = prev {
+                    input.with_initial_text(prev);
+                }
+                input.interact_on(term)
+            }
+            FieldKind::Confirm => Confirmation::with_theme(&*self.theme)
+                .with_text(&field.prompt)
+                .default(prev != Some("false"))
+                .interact_on(term)
+                .map(|answer| answer.to_string()),
+            FieldKind::Select(ref items) => {
+                let mut select = Select::with_theme(&*self.theme);
+                select.with_prompt(&field.prompt).items(items);
+                if let Some(prev) = prev {
+                    if let Some(pos) = items.iter().position(|item| item == prev) {
+                        select.default(pos);
+                    }
+                }
+                Ok(items[select.interact_on(term)?].clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use console::Key;
+
+    struct Script {
+        keys: RefCell<Vec<Key>>,
+        output: RefCell<String>,
+    }
+
+    impl Backend for Script {
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            self.output.borrow_mut().push_str(s);
+            Ok(())
+        }
+
+        fn read_key(&self) -> io::Result<Key> {
+            Ok(self.keys.borrow_mut().remove(0))
+        }
+
+        fn size(&self) -> (u16, u16) {
+            (24, 80)
+        }
+    }
+
+    #[test]
+    fn test_edit_and_review() {
+        let script = Script {
+            keys: RefCell::new(vec![Key::Char('a'), Key::Enter, Key::Char('n')]),
+            output: RefCell::new(String::new()),
+        };
+        let mut form = Form::new();
+        form.input("name", "Name").confirm("admin", "Admin?");
+        let answers = form.interact_on(&script).unwrap();
+        assert_eq!(answers["name"], "a");
+        assert_eq!(answers["admin"], "false");
+
+        script.output.borrow_mut().clear();
+        script
+            .keys
+            .borrow_mut()
+            .extend(vec![Key::Char('b'), Key::Enter]);
+        assert_eq!(form.edit_on(&script, "name").unwrap(), "ab");
+        assert_eq!(form.answers()["name"], "ab");
+        assert!(script.output.borrow().starts_with("\x1b[2A"));
+        assert!(script.output.borrow().ends_with("Admin? no\n"));
+        assert!(form.edit_on(&script, "email").is_err());
+
+        let keys = vec![
+            Key::ArrowUp,
+            Key::Enter,
+            Key::Char('y'),
+            Key::ArrowDown,
+            Key::Enter,
+        ];
+        script.keys.borrow_mut().extend(keys);
+        let answers = form.review_on(&script).unwrap();
+        assert_eq!(answers["admin"], "true");
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(Form::new());
+        assert_send(Form::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,417 @@
+//! Changes to an open prompt from other threads.
+use std::io;
+use std::mem;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use backend::Backend;
+use events::{wait_for_event, Event, PollSession};
+use mouse::MouseCapture;
+use theme::TermThemeRenderer;
+
+/// How often a prompt with a handle looks for updates.
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A change sent through a `PromptHandle`.
+pub(crate) enum Update {
+    AddItem(String),
+    SetItems(Vec<String>),
+    SetPrompt(String),
+    SetStatus(Option<String>),
+    ReserveRows(usize),
+    SetRow(usize, Row),
+    ClearRows,
+    Suspend(Suspension),
+}
+
+/// A request of `PromptHandle::suspend` to pause the prompt.
+///
+/// Dropping it without calling `wait` lets `suspend` go on right away.
+pub(crate) struct Suspension {
+    paused: Sender<()>,
+    resumed: Receiver<()>,
+}
+
+impl Suspension {
+    /// Tells `suspend` that the terminal is free and blocks until its
+    /// closure returned.
+    pub fn wait(self) {
+        if self.paused.send(()).is_ok() {
+            let _ = self.resumed.recv();
+        }
+    }
+}
+
+/// A row of the region below a menu.
+#[derive(Clone)]
+pub(crate) enum Row {
+    Blank,
+    Text(String),
+    Progress(String, u64, u64),
+}
+
+/// Changes a `Select` or `Checkboxes` while it is shown.
+///
+/// The handle is attached to a prompt with `with_handle` and can be
+/// cloned and sent to other threads.  Besides the items and the prompt it
+/// controls a region below the menu: a status line and any number of
+/// rows with text or progress bars.  The prompt applies the changes and
+/// renders again while it waits for keys; changes sent before it opens
+/// are applied when it does.  With `suspend` the prompt can also be put
+/// aside to print other output.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::thread;
+/// use dialoguer::{PromptHandle, Select};
+///
+/// let handle = PromptHandle::new();
+/// let scanner = handle.clone();
+/// thread::spawn(move || {
+///     for host in &["10.0.0.2", "10.0.0.7"] {
+///         scanner.add_item(host);
+///     }
+///     scanner.set_status("Scan complete");
+/// });
+/// let host = Select::new()
+///     .with_prompt("Host")
+///     .with_handle(&handle)
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+#[derive(Clone, Default)]
+pub struct PromptHandle {
+    queue: Arc<Mutex<Queue>>,
+}
+
+/// The changes not applied yet and whether a prompt is open.
+#[derive(Default)]
+struct Queue {
+    updates: Vec<Update>,
+    open: bool,
+}
+
+impl PromptHandle {
+    /// Creates a handle that is not attached to any prompt yet.
+    pub fn new() -> PromptHandle {
+        PromptHandle::default()
+    }
+
+    /// Appends an item to the menu.
+    pub fn add_item(&self, item: &str) {
+        self.send(Update::AddItem(item.into()));
+    }
+
+    /// Replaces all items of the menu.
+    ///
+    /// Checked items of checkboxes are unchecked.
+    pub fn set_items<T: ToString>(&self, items: &[T]) {
+        self.send(Update::SetItems(
+            items.iter().map(|item| item.to_string()).collect(),
+        ));
+    }
+
+    /// Changes the prompt text.
+    pub fn set_prompt(&self, prompt: &str) {
+        self.send(Update::SetPrompt(prompt.into()));
+    }
+
+    /// Shows a status line below the menu, e.g. the progress of a search.
+    pub fn set_status(&self, status: &str) {
+        self.send(Update::SetStatus(Some(status.into())));
+    }
+
+    /// Removes the status line.
+    pub fn clear_status(&self) {
+        self.send(Update::SetStatus(None));
+    }
+
+    /// Reserves rows below the menu for `set_row` and `set_progress_row`.
+    ///
+    /// Reserved rows stay blank until they are set, so the menu does not
+    /// move when they are filled in later.
+    pub fn reserve_rows(&self, count: usize) {
+        self.send(Update::ReserveRows(count));
+    }
+
+    /// Sets the text of a row below the menu, e.g. `3 files scanned`.
+    ///
+    /// Rows are counted from 0 below the status line; rows before `index`
+    /// that are not set yet stay blank.
+    pub fn set_row(&self, index: usize, text: &str) {
+        self.send(Update::SetRow(index, Row::Text(text.into())));
+    }
+
+    /// Shows a progress bar at `pos` out of `len` steps in a row below the
+    /// menu, drawn like a `ProgressBar`.
+    pub fn set_progress_row(&self, index: usize, message: &str, pos: u64, len: u64) {
+        self.send(Update::SetRow(
+            index,
+            Row::Progress(message.into(), pos, len),
+        ));
+    }
+
+    /// Removes all rows set or reserved below the menu.
+    pub fn clear_rows(&self) {
+        self.send(Update::ClearRows);
+    }
+
+    /// Clears the prompt and restores the terminal, runs `f` and renders
+    /// the prompt again as it was.
+    ///
+    /// Meanwhile `f` can write to the terminal freely, e.g. print log
+    /// messages or run a command in the foreground; keys pressed are
+    /// left to the prompt.  This blocks until the prompt paused, which it
+    /// does while it waits for keys.  If no prompt is open `f` is run
+    /// right away.
+    ///
+    /// ```rust,no_run
+    /// # fn test() -> Result<(), Box<std::error::Error>> {
+    /// use std::process::Command;
+    /// use std::thread;
+    /// use dialoguer::{PromptHandle, Select};
+    ///
+    /// let handle = PromptHandle::new();
+    /// let builder = handle.clone();
+    /// thread::spawn(move || {
+    ///     builder.suspend(|| Command::new("make").status())
+    /// });
+    /// let target = Select::new()
+    ///     .with_prompt("Deploy to")
+    ///     .items(&["staging", "production"])
+    ///     .with_handle(&handle)
+    ///     .interact()?;
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    pub fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        let (paused_tx, paused) = channel();
+        let (resumed, resumed_rx) = channel();
+        {
+            let mut queue = self.queue();
+            if !queue.open {
+                drop(queue);
+                return f();
+            }
+            queue.updates.push(Update::Suspend(Suspension {
+                paused: paused_tx,
+                resumed: resumed_rx,
+            }));
+        }
+        let _ = paused.recv();
+        let rv = f();
+        let _ = resumed.send(());
+        rv
+    }
+
+    fn send(&self, update: Update) {
+        self.queue().updates.push(update);
+    }
+
+    fn has_updates(&self) -> bool {
+        !self.queue().updates.is_empty()
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Takes the changes sent since the last call.
+    pub(crate) fn take_updates(&self) -> Vec<Update> {
+        self.queue().updates.drain(..).collect()
+    }
+
+    /// Marks a prompt as open until the returned guard is dropped.
+    ///
+    /// Suspensions still pending then are let go, since no prompt is left
+    /// to pause.
+    pub(crate) fn open(&self) -> OpenPrompt<'_> {
+        self.queue().open = true;
+        OpenPrompt { handle: self }
+    }
+}
+
+/// Keeps a prompt marked as open, see `PromptHandle::open`.
+pub(crate) struct OpenPrompt<'a> {
+    handle: &'a PromptHandle,
+}
+
+impl<'a> Drop for OpenPrompt<'a> {
+    fn drop(&mut self) {
+        let mut queue = self.handle.queue();
+        queue.open = false;
+        let updates = mem::take(&mut queue.updates);
+        queue.updates = updates
+            .into_iter()
+            .filter(|update| !matches!(update, Update::Suspend(_)))
+            .collect();
+    }
+}
+
+/// Pauses a prompt for `suspension`.
+///
+/// The prompt is cleared and the cursor, mouse and main screen restored;
+/// once `suspend` returned they are set up again and the caller has to
+/// render the prompt again.
+pub(crate) fn suspend_prompt(
+    render: &mut TermThemeRenderer,
+    mouse: &MouseCapture,
+    alternate_screen: bool,
+    suspension: Suspension,
+) -> io::Result<()> {
+    let term = render.term();
+    render.clear()?;
+    render.leave_alternate_screen()?;
+    mouse.pause()?;
+    if term.is_term() {
+        term.show_cursor()?;
+    }
+    term.flush()?;
+    suspension.wait();
+    if alternate_screen {
+        render.enter_alternate_screen()?;
+    }
+    if term.is_term() {
+        term.hide_cursor()?;
+    }
+    mouse.resume()?;
+    term.flush()
+}
+
+/// The status line and rows a `PromptHandle` shows below a menu.
+#[derive(Default)]
+pub(crate) struct Region {
+    status: Option<String>,
+    rows: Vec<Row>,
+}
+
+impl Region {
+    /// Applies an update of the status line or the rows and ignores all
+    /// others.
+    pub fn apply(&mut self, update: Update) {
+        match update {
+            Update::SetStatus(status) => self.status = status,
+            Update::ReserveRows(count) if count > self.rows.len() => {
+                self.rows.resize(count, Row::Blank);
+            }
+            Update::SetRow(index, row) => {
+                if index >= self.rows.len() {
+                    self.rows.resize(index + 1, Row::Blank);
+                }
+                self.rows[index] = row;
+            }
+            Update::ClearRows => self.rows.clear(),
+            _ => {}
+        }
+    }
+
+    /// Returns the number of lines `render` renders.
+    pub fn height(&self) -> usize {
+        self.status.is_some() as usize + self.rows.len()
+    }
+
+    pub fn render(&self, render: &mut TermThemeRenderer) -> io::Result<()> {
+        if let Some(ref status) = self.status {
+            render.hint(status)?;
+        }
+        for row in &self.rows {
+            match *row {
+                Row::Blank => render.hint("")?,
+                Row::Text(ref text) => render.hint(text)?,
+                Row::Progress(ref message, pos, len) => render.progress_bar(message, pos, len)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Like `wait_for_event` with `watch_resize` but returns `None` as soon as
+/// `handle` has updates.
+pub(crate) fn wait_for_event_or_update(
+    term: &dyn Backend,
+    timeout: Option<Duration>,
+    handle: Option<&PromptHandle>,
+) -> io::Result<Option<Event>> {
+    let handle = match handle {
+        Some(handle) if term.is_term() => handle,
+        _ => return wait_for_event(term, timeout, true).map(Some),
+    };
+    let _session = PollSession::new();
+    let size = term.size();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if handle.has_updates() {
+            return Ok(None);
+        }
+        let slice = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(Some(Event::Timeout));
+                }
+                (deadline - now).min(UPDATE_POLL_INTERVAL)
+            }
+            None => UPDATE_POLL_INTERVAL,
+        };
+        match wait_for_event(term, Some(slice), true)? {
+            Event::Timeout if term.size() != size => return Ok(Some(Event::Resize)),
+            Event::Timeout => {}
+            event => return Ok(Some(event)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    #[test]
+    fn test_region() {
+        let handle = PromptHandle::new();
+        handle.reserve_rows(2);
+        handle.set_row(3, "3 files scanned");
+        handle.set_status("Scanning");
+        let mut region = Region::default();
+        for update in handle.take_updates() {
+            region.apply(update);
+        }
+        assert_eq!(region.height(), 5);
+        handle.reserve_rows(1);
+        handle.clear_status();
+        for update in handle.take_updates() {
+            region.apply(update);
+        }
+        assert_eq!(region.height(), 4);
+        assert!(!handle.has_updates());
+    }
+
+    #[test]
+    fn test_suspend() {
+        let handle = PromptHandle::new();
+        assert_eq!(handle.suspend(|| 1), 1);
+        assert!(!handle.has_updates());
+
+        let open = handle.open();
+        let suspender = handle.clone();
+        let thread = thread::spawn(move || suspender.suspend(|| 2));
+        loop {
+            if let Some(Update::Suspend(suspension)) = handle.take_updates().pop() {
+                suspension.wait();
+                break;
+            }
+            thread::yield_now();
+        }
+        assert_eq!(thread.join().unwrap(), 2);
+
+        let suspender = handle.clone();
+        let thread = thread::spawn(move || suspender.suspend(|| 3));
+        while !handle.has_updates() {
+            thread::yield_now();
+        }
+        drop(open);
+        assert_eq!(thread.join().unwrap(), 3);
+        assert!(!handle.has_updates());
+    }
+}
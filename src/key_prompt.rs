@@ -0,0 +1,268 @@
+use std::io;
+use std::sync::Arc;
+
+use console::Style;
+
+use backend::{Backend, TermGuard};
+use context::NonInteractive;
+use events::{read_char, read_plain_line};
+use messages::{fill, messages};
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders a choice answered with a single key press.
+///
+/// The choices are shown as `[a]pply [s]kip [q]uit` and the first key
+/// matching one of them, regardless of case, answers the prompt.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::KeyPrompt;
+///
+/// let key = KeyPrompt::new()
+///     .with_prompt("Apply this hunk?")
+///     .choice('a', "apply")
+///     .choice('s', "skip")
+///     .choice('q', "quit")
+///     .interact()?;
+/// if key == 'q' {
+///     return Ok(());
+/// }
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct KeyPrompt<'a> {
+    prompt: String,
+    choices: Vec<(char, String)>,
+    default: Option<char>,
+    theme: ThemeRef<'a>,
+    non_interactive: NonInteractive,
+    answer: Option<char>,
+    stream: Stream,
+}
+
+impl<'a> KeyPrompt<'a> {
+    /// Creates a new key prompt without choices.
+    pub fn new() -> KeyPrompt<'static> {
+        KeyPrompt::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> KeyPrompt<'a> {
+        KeyPrompt::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> KeyPrompt<'static> {
+        KeyPrompt::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> KeyPrompt<'a> {
+        KeyPrompt {
+            prompt: "".into(),
+            choices: vec![],
+            default: None,
+            theme,
+            non_interactive: NonInteractive::Prompt,
+            answer: None,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut KeyPrompt<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut KeyPrompt<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Adds a choice answered with `key`.
+    ///
+    /// If the label starts with the key it is shown as `[k]label`,
+    /// otherwise as `[k] label`.
+    pub fn choice(&mut self, key: char, label: &str) -> &mut KeyPrompt<'a> {
+        self.choices.push((key, label.into()));
+        self
+    }
+
+    /// Sets the key enter picks.
+    ///
+    /// By default enter does not answer the prompt.
+    pub fn default(&mut self, key: char) -> &mut KeyPrompt<'a> {
+        self.default = Some(key);
+        self
+    }
+
+    /// Sets what happens if the terminal is not interactive.
+    ///
+    /// With `NonInteractive::UseDefault` the key set with `default` is
+    /// returned.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut KeyPrompt<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Answers the prompt with the choice of `key` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, key: char) -> &mut KeyPrompt<'a> {
+        self.answer = Some(key);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut KeyPrompt<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the key of the picked choice.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<char> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<char>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, KeyPrompt::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<char> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term),
+            |&key| Some(self.label(key).into()),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<char> {
+        let _guard = TermGuard::new(term);
+        if let Some(rv) = self.non_interactive.answer(term, self.default) {
+            return rv;
+        }
+        if self.choices.is_empty() {
+            return Err(io::Error::other("KeyPrompt has no choices"));
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if let Some(answer) = self.answer {
+            let key = self.find(answer).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("No choice {}", answer))
+            })?;
+            render.single_prompt_selection(&self.prompt, self.label(key))?;
+            return Ok(key);
+        }
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+        let choices = self.choice_refs();
+        render.key_prompt(&self.prompt, &choices, self.default)?;
+        loop {
+            let key = match render.clear_interrupted(read_char(term))? {
+                '\n' | '\r' => match self.default {
+                    Some(key) => key,
+                    None => continue,
+                },
+                c => match self.find(c) {
+                    Some(key) => key,
+                    None => continue,
+                },
+            };
+            term.clear_line()?;
+            render.single_prompt_selection(&self.prompt, self.label(key))?;
+            return Ok(key);
+        }
+    }
+
+    /// Reads the key or label of a choice as a typed line for the
+    /// accessible render mode.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<char> {
+        let choices = self.choice_refs();
+        loop {
+            render.key_prompt(&self.prompt, &choices, self.default)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let input = input.trim();
+            let mut chars = input.chars();
+            let key = match (chars.next(), chars.next()) {
+                (None, _) => self.default,
+                (Some(c), None) => self.find(c),
+                _ => self
+                    .choices
+                    .iter()
+                    .find(|choice| choice.1.eq_ignore_ascii_case(input))
+                    .map(|choice| choice.0),
+            };
+            match key {
+                Some(key) => {
+                    render.single_prompt_selection(&self.prompt, self.label(key))?;
+                    return Ok(key);
+                }
+                None => {
+                    let keys: Vec<String> = self.choices.iter().map(|c| c.0.to_string()).collect();
+                    render.error(&fill(&messages().answer_key, &[("keys", &keys.join(", "))]))?;
+                }
+            }
+        }
+    }
+
+    fn choice_refs(&self) -> Vec<(char, &str)> {
+        self.choices
+            .iter()
+            .map(|&(key, ref label)| (key, label.as_str()))
+            .collect()
+    }
+
+    /// Returns the key of the choice `c` answers.
+    fn find(&self, c: char) -> Option<char> {
+        self.choices
+            .iter()
+            .map(|choice| choice.0)
+            .find(|key| key.to_lowercase().eq(c.to_lowercase()))
+    }
+
+    fn label(&self, key: char) -> &str {
+        self.choices
+            .iter()
+            .find(|choice| choice.0 == key)
+            .map_or("", |choice| choice.1.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(KeyPrompt::new());
+        assert_send(KeyPrompt::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,249 @@
+//! Key bindings of the list prompts.
+use console::Key;
+
+use messages::messages;
+
+/// An action of a list prompt that keys can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Moves to the previous item.
+    Up,
+    /// Moves to the next item.
+    Down,
+    /// Moves one page up.
+    PageUp,
+    /// Moves one page down.
+    PageDown,
+    /// Moves to the first item.
+    First,
+    /// Moves to the last item.
+    Last,
+    /// Moves to the previous column of a grid.
+    Left,
+    /// Moves to the next column of a grid.
+    Right,
+    /// Expands the current group of a tree.
+    Expand,
+    /// Collapses the current group of a tree or moves to its parent.
+    Collapse,
+    /// Toggles the current checkbox or, in a `Select`, picks the item.
+    Toggle,
+    /// Checks all checkboxes or unchecks them if all are checked.
+    ToggleAll,
+    /// Inverts all checkboxes.
+    Invert,
+    /// Confirms the selection.
+    Confirm,
+    /// Cancels the prompt.
+    Cancel,
+    /// Starts searching the items.
+    Search,
+    /// Reloads items fetched when the prompt opened.
+    Refresh,
+    /// Moves the focus to the other pane of a two-pane prompt.
+    SwitchPane,
+    /// Toggles the list of key bindings.
+    Help,
+}
+
+impl Action {
+    /// All actions in the order of the key help.
+    pub(crate) const ALL: [Action; 19] = [
+        Action::Up,
+        Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::First,
+        Action::Last,
+        Action::Left,
+        Action::Right,
+        Action::Expand,
+        Action::Collapse,
+        Action::Toggle,
+        Action::ToggleAll,
+        Action::Invert,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Search,
+        Action::Refresh,
+        Action::SwitchPane,
+        Action::Help,
+    ];
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Action::Up => "move up",
+            Action::Down => "move down",
+            Action::PageUp => "previous page",
+            Action::PageDown => "next page",
+            Action::First => "first item",
+            Action::Last => "last item",
+            Action::Left => "previous column",
+            Action::Right => "next column",
+            Action::Expand => "expand group",
+            Action::Collapse => "collapse group",
+            Action::Toggle => "toggle item",
+            Action::ToggleAll => "toggle all items",
+            Action::Invert => "invert selection",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Search => "search",
+            Action::Refresh => "reload items",
+            Action::SwitchPane => "switch pane",
+            Action::Help => "toggle this help",
+        }
+    }
+}
+
+/// Maps keys to the actions of list prompts.
+///
+/// The default map offers arrow keys as well as vim style navigation:
+///
+/// ```rust,no_run
+/// # extern crate console;
+/// # extern crate dialoguer;
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use console::Key;
+/// use dialoguer::{Action, KeyMap, Select};
+///
+/// let mut keymap = KeyMap::default();
+/// keymap.bind(Key::Char('x'), Action::Confirm).unbind(&Key::Char('q'));
+///
+/// let selection = Select::new()
+///     .items(&["a", "b"])
+///     .with_keymap(keymap)
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl KeyMap {
+    /// Creates a key map without any bindings.
+    pub fn new() -> KeyMap {
+        KeyMap { bindings: vec![] }
+    }
+
+    /// Binds a key to an action, replacing a previous binding of the key.
+    pub fn bind(&mut self, key: Key, action: Action) -> &mut KeyMap {
+        self.unbind(&key);
+        self.bindings.push((key, action));
+        self
+    }
+
+    /// Removes the binding of a key.
+    pub fn unbind(&mut self, key: &Key) -> &mut KeyMap {
+        self.bindings.retain(|binding| binding.0 != *key);
+        self
+    }
+
+    /// Returns the action a key is bound to.
+    pub fn action(&self, key: &Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.0 == *key)
+            .map(|binding| binding.1)
+    }
+
+    /// Describes the bindings of the given actions, one line per action.
+    pub(crate) fn help_lines(&self, actions: &[Action]) -> Vec<String> {
+        let messages = messages();
+        actions
+            .iter()
+            .filter_map(|&action| {
+                let keys: Vec<_> = self
+                    .bindings
+                    .iter()
+                    .filter(|binding| binding.1 == action)
+                    .map(|binding| key_name(&binding.0))
+                    .collect();
+                if keys.is_empty() {
+                    None
+                } else {
+                    Some(format!("{:<12}{}", keys.join(" "), messages.action(action)))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> KeyMap {
+        let mut keymap = KeyMap::new();
+        for &(ref key, action) in &[
+            (Key::ArrowUp, Action::Up),
+            (Key::Char('k'), Action::Up),
+            (Key::ArrowDown, Action::Down),
+            (Key::Char('j'), Action::Down),
+            (Key::PageUp, Action::PageUp),
+            (Key::ArrowLeft, Action::PageUp),
+            (Key::Char('h'), Action::PageUp),
+            (Key::PageDown, Action::PageDown),
+            (Key::ArrowRight, Action::PageDown),
+            (Key::Char('l'), Action::PageDown),
+            (Key::Home, Action::First),
+            (Key::Char('g'), Action::First),
+            (Key::End, Action::Last),
+            (Key::Char('G'), Action::Last),
+            (Key::Char(' '), Action::Toggle),
+            (Key::Char('a'), Action::ToggleAll),
+            (Key::Char('i'), Action::Invert),
+            (Key::Enter, Action::Confirm),
+            (Key::Escape, Action::Cancel),
+            (Key::Char('q'), Action::Cancel),
+            (Key::Char('/'), Action::Search),
+            (Key::Char('?'), Action::Help),
+            (Key::Char('\x12'), Action::Refresh),
+        ] {
+            keymap.bind(key.clone(), action);
+        }
+        keymap
+    }
+}
+
+fn key_name(key: &Key) -> String {
+    match *key {
+        Key::ArrowUp => "↑".into(),
+        Key::ArrowDown => "↓".into(),
+        Key::ArrowLeft => "←".into(),
+        Key::ArrowRight => "→".into(),
+        Key::PageUp => "pgup".into(),
+        Key::PageDown => "pgdn".into(),
+        Key::Home => "home".into(),
+        Key::End => "end".into(),
+        Key::Enter => "enter".into(),
+        Key::Escape => "esc".into(),
+        Key::Backspace => "backspace".into(),
+        Key::Tab => "tab".into(),
+        Key::Char(' ') => "space".into(),
+        Key::Char(c) if c < ' ' => format!("ctrl-{}", (c as u8 + b'a' - 1) as char),
+        Key::Char(c) => c.to_string(),
+        ref key => format!("{:?}", key).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebind() {
+        let mut keymap = KeyMap::default();
+        assert_eq!(keymap.action(&Key::Char('G')), Some(Action::Last));
+        keymap
+            .bind(Key::Char('q'), Action::Confirm)
+            .unbind(&Key::Escape);
+        assert_eq!(keymap.action(&Key::Char('q')), Some(Action::Confirm));
+        assert_eq!(keymap.action(&Key::Escape), None);
+        assert_eq!(
+            keymap.help_lines(&[Action::First, Action::Cancel]),
+            vec!["home g      first item".to_string()]
+        );
+        assert_eq!(
+            keymap.help_lines(&[Action::Refresh]),
+            vec!["ctrl-r      reload items".to_string()]
+        );
+    }
+}
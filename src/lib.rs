@@ -11,20 +11,246 @@
 //! # Crate Contents
 //!
 //! * Confirmation prompts
+//! * Toggles between two options
+//! * Single key choices
+//! * Rating scales
 //! * Input prompts (regular and password)
+//! * Passwords from environment variables, file descriptors or the keyring
 //! * Input validation
+//! * Duration and byte size inputs
+//! * IP address and CIDR inputs (with the `net` feature)
+//! * Input completion from history
+//! * Autocomplete inputs with suggestions
+//! * Tag and key-value list inputs
 //! * Menu selections
 //! * Checkboxes
-//! * Editor launching
+//! * Tree selections
+//! * Checkbox trees of files and directories
+//! * Table selections with aligned columns
+//! * Forms with editable answers
+//! * Editor launching (with the default `editor` feature)
+//! * Progress bars and spinners
+//! * Shell quoting of answers
+//!
+//! # Minimal Builds
+//!
+//! `Editor` is behind the `editor` feature, which is enabled by default
+//! and pulls in `tempfile`.  Applications that only need the prompts can
+//! turn off the default features.
+//!
+//! # Shared Settings
+//!
+//! A `Dialoguer` context carries the theme, stream, key bindings, timeout
+//! and non-interactive policy of an application and creates prompts with
+//! them, so they do not have to be passed to every prompt.
+//!
+//! # Interrupts
+//!
+//! When the user hits Ctrl+C while a prompt is active the prompt is
+//! cleared from the terminal and `interact` returns an `io::Error` of
+//! kind `Interrupted` instead of the process being killed.
+//!
+//! Ctrl+Z stops the process as usual on Unix.  The cursor and screen are
+//! restored for the shell meanwhile, and once the process is continued
+//! the prompt is rendered again below whatever the shell printed.
+//!
+//! # Plain Output
+//!
+//! Prompts are rendered without ANSI styling if `NO_COLOR` is set or the
+//! terminal does not support colors.  This can be overridden with
+//! `theme::set_plain_mode`.  `theme::color_level` tells how many colors
+//! the terminal displays.
+//!
+//! On the Linux console, legacy Windows consoles and with a locale other
+//! than UTF-8 themes fall back to ASCII glyphs, e.g. `>` instead of `❯`.
+//! This can be overridden with `theme::set_ascii_mode`.
+//!
+//! Control characters and escape sequences other than styles are
+//! stripped from prompts and items, so text from other programs cannot
+//! move the cursor or break the layout.  `theme::set_sanitize_mode` shows
+//! them escaped instead or passes them through.
+//!
+//! # Accessibility
+//!
+//! For screen readers and dumb terminals prompts can render without any
+//! cursor movement: menus become numbered lists answered by typing a
+//! number.  This is picked automatically if `TERM` is `dumb` or the
+//! prompt runs inside Emacs, and can be forced with
+//! `theme::set_accessible_mode`.
+//!
+//! # Async Support
+//!
+//! With the `tokio` feature enabled every prompt gains `interact_async`
+//! methods which can be awaited from within a tokio runtime.  The prompt
+//! runs on a thread of its own, so other tasks keep running while the
+//! user is typing.  Prompts awaited this way may not borrow anything,
+//! which `with_owned_theme` helps with.
+//!
+//! # Theme Configuration
+//!
+//! With the `serde` feature enabled `ColorfulTheme::from_toml` and
+//! `ColorfulTheme::from_json` load a theme from a configuration file so
+//! that end users can restyle prompts without recompiling.
+//! `ColorfulTheme::overlay` lays `ThemeOverrides` over a theme to change
+//! a few glyphs or styles and keep the rest.  For a single prompt that
+//! stands out, `prompt_style` and `highlight_style` change the styles of
+//! that prompt alone.
+//!
+//! # Persistent State
+//!
+//! With the `serde` feature enabled `set_state_store` keeps the answers
+//! prompts remember, such as the last pick of a `Select` or `Input` with
+//! a `remember_key` and the values of a `HistoryCompleter::from_state`,
+//! in a `StateStore` file so they last across runs.
+//!
+//! # Localization
+//!
+//! The texts prompts show on their own, such as the `[y/n]` hint of a
+//! confirmation and the errors of the accessible mode, come from a
+//! `Messages` table that `set_messages` replaces for all prompts.
+//!
+//! # Instrumentation
+//!
+//! A `PromptObserver` set with `set_observer` is told when prompts open,
+//! are answered, are cancelled or reject an answer, e.g. to log answers
+//! for an audit or to time how long users spend on each question.
+//!
+//! With the `log` feature enabled every prompt also logs how it closed as
+//! a debug event through the `log` crate, with the prompt, the answer and
+//! the time it took as fields.  Passwords and hidden input are logged as
+//! `<redacted>`.
+//!
+//! # Recording Sessions
+//!
+//! A `Recorder` wraps a backend and records the keys prompts read, plus
+//! their answers through its observer, as a `Session`.  A `Replay` feeds
+//! a saved session back to the prompts, instantly or at the recorded
+//! pace, for demos, bug reports and tests.
+//!
+//! # Snapshot Tests
+//!
+//! A `VirtualTerm` runs prompts on a terminal of a fixed size in memory
+//! with a script of keys, so tests can assert the exact screens users see
+//! for a theme and input, frame by frame.
+//!
+//! # Mouse Support
+//!
+//! With the `mouse` feature enabled menus and checkboxes take mouse
+//! input: a click highlights an item, a double click picks or toggles it
+//! and the wheel scrolls by pages.
+//!
+//! # Right-to-Left Text
+//!
+//! With the `bidi` feature enabled prompts, items and input in Arabic,
+//! Hebrew and other right-to-left scripts are reordered for display, as
+//! most terminals print characters strictly left to right.
+//!
+//! # Web Terminals
+//!
+//! With the `wasm` feature enabled `WebTerm` renders prompts to an
+//! xterm.js compatible stream and takes key presses from JS, so web based
+//! terminals can run the same prompts through `interact_on`.
 extern crate console;
+#[cfg(feature = "net")]
+extern crate ipnet;
+#[cfg(feature = "keyring")]
+extern crate keyring;
+#[cfg(unix)]
+extern crate libc;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(any(feature = "editor", feature = "serde", test))]
 extern crate tempfile;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "serde")]
+extern crate toml;
+#[cfg(feature = "bidi")]
+extern crate unicode_bidi;
+extern crate unicode_segmentation;
+pub use autocomplete::Autocomplete;
+pub use backend::Backend;
+pub use complete::{Completer, Delimiters, HistoryCompleter, WordSplitter};
+pub use context::{confirm_all, AssumePolicy, Dialoguer, NonInteractive};
+#[cfg(feature = "editor")]
 pub use edit::Editor;
+pub use file_transfer::FileTransferPrompt;
+pub use file_tree::FileTree;
+pub use form::Form;
+pub use handle::PromptHandle;
+pub use key_prompt::KeyPrompt;
+pub use keymap::{Action, KeyMap};
+pub use messages::{set_messages, Messages};
+#[cfg(feature = "net")]
+pub use net::IpInput;
+pub use observer::{set_observer, PromptObserver};
+pub use progress::{ProgressBar, Spinner};
 pub use prompts::{Confirmation, Input, PasswordInput};
-pub use select::{Checkboxes, Select};
-pub use validate::Validator;
+pub use rating::Rating;
+pub use replay::{Recorder, Replay, Session};
+#[cfg(feature = "tokio")]
+pub use rt::PromptFuture;
+#[cfg(unix)]
+pub use secret::FdSecret;
+pub use secret::SecretSource;
+pub use select::{Checkboxes, Select, SelectValues};
+#[cfg(feature = "serde")]
+pub use state::{set_state_store, StateStore};
+pub use stream::Stream;
+pub use table_select::TableSelect;
+pub use tag_input::TagInput;
+pub use toggle::Toggle;
+pub use tree_select::TreeSelect;
+pub use units::{ByteSizeInput, DurationInput};
+pub use validate::{TooManyAttempts, Validation, Validator, Warn};
+pub use virtual_term::VirtualTerm;
+#[cfg(feature = "wasm")]
+pub use web::{WebInput, WebTerm};
 
+mod autocomplete;
+mod backend;
+mod bidi;
+mod complete;
+mod context;
+#[cfg(feature = "editor")]
 mod edit;
+pub mod events;
+mod file_transfer;
+mod file_tree;
+mod form;
+mod handle;
+mod key_prompt;
+mod keymap;
+mod messages;
+mod mouse;
+#[cfg(feature = "net")]
+mod net;
+mod observer;
+mod paging;
+mod progress;
 mod prompts;
+pub mod quote;
+mod rating;
+mod replay;
+#[cfg(feature = "tokio")]
+mod rt;
+mod secret;
 mod select;
+mod state;
+mod stream;
+mod table_select;
+mod tag_input;
 pub mod theme;
+#[cfg(feature = "serde")]
+mod theme_config;
+mod toggle;
+mod tree_select;
+mod units;
 mod validate;
+mod virtual_term;
+#[cfg(feature = "wasm")]
+mod web;
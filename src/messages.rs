@@ -0,0 +1,297 @@
+//! Texts the prompts show on their own, e.g. hints and error messages.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use keymap::Action;
+
+static MESSAGES: RwLock<Option<Arc<Messages>>> = RwLock::new(None);
+
+/// The table of built-in texts.
+///
+/// The default holds the English texts.  An application replaces the
+/// table as a whole with `set_messages`, usually starting out from the
+/// default.  Words in braces such as `{count}` are placeholders filled in
+/// by the prompt.
+///
+/// ```rust,no_run
+/// use dialoguer::{set_messages, Messages};
+///
+/// set_messages(Messages {
+///     yes: "ja".into(),
+///     no: "nein".into(),
+///     answer_yes_no: "Bitte mit ja oder nein antworten".into(),
+///     ..Messages::default()
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Messages {
+    /// The answer "yes".  Its first letter answers a confirmation.
+    pub yes: String,
+    /// The answer "no".  Its first letter answers a confirmation.
+    pub no: String,
+    /// The word whose first letter edits the default of an input.
+    pub edit: String,
+    /// The question whether to use the default of an input.
+    pub use_default: String,
+    /// The error for an answer to a confirmation that is neither yes nor no.
+    pub answer_yes_no: String,
+    /// The error for an answer to a toggle that is neither option, with
+    /// the placeholders `{on}` and `{off}`.
+    pub answer_either: String,
+    /// The error for an answer to a key prompt that is none of its keys,
+    /// with the placeholder `{keys}`.
+    pub answer_key: String,
+    /// Shown in place of a password.
+    pub hidden: String,
+    /// Shown in place of a password taken from a `SecretSource`, with the
+    /// placeholders `{hidden}` and `{source}`.
+    pub secret_from: String,
+    /// Shown in place of the answer to a skipped prompt.
+    pub skipped: String,
+    /// Shown while the items of a menu are loading.
+    pub loading: String,
+    /// Prefixes errors in the default theme.
+    pub error: String,
+    /// Prefixes warnings in the default theme.
+    pub warning: String,
+    /// Follows a validation warning, telling how to use the value anyway.
+    pub confirm_warning: String,
+    /// The count of checked items, with the placeholder `{count}`.
+    pub selected_count: String,
+    /// Asks for the number of an item.
+    pub enter_number: String,
+    /// Asks for the numbers of several items.
+    pub enter_numbers: String,
+    /// The error for a number that is not the number of an item.
+    pub invalid_number: String,
+    /// The error for one of several numbers, with the placeholder `{input}`.
+    pub invalid_numbers: String,
+    /// The error for a number outside of a rating, with the placeholder
+    /// `{max}`.
+    pub number_range: String,
+    /// The error for text that is not a duration.
+    pub invalid_duration: String,
+    /// The error for text that is not a byte size.
+    pub invalid_byte_size: String,
+    /// The error for a value below the minimum, with the placeholder
+    /// `{min}`.
+    pub value_at_least: String,
+    /// The error for a value above the maximum, with the placeholder
+    /// `{max}`.
+    pub value_at_most: String,
+    /// The error for a character that cannot be part of an address, with
+    /// the placeholders `{char}` and `{position}`.
+    pub address_unexpected: String,
+    /// The error for a number too large for its place in an address, with
+    /// the placeholder `{position}`.
+    pub address_too_large: String,
+    /// The error for an address that is not complete.
+    pub address_incomplete: String,
+    /// Too few checked items, with the placeholders `{count}` and `{items}`.
+    pub select_at_least: String,
+    /// Too many checked items, with the placeholders `{count}` and `{items}`.
+    pub select_at_most: String,
+    /// The word `{items}` stands for if the count is one.
+    pub item: String,
+    /// The word `{items}` stands for otherwise.
+    pub items: String,
+    /// Explains the accessible mode of a tag input.
+    pub tag_hint: String,
+    /// The error for a tag that is not a key-value pair.
+    pub expected_pair: String,
+    /// The prompt of the summary of a form.
+    pub review: String,
+    /// The entry confirming the summary of a form.
+    pub review_confirm: String,
+    /// Describes picking an item by its number in the key help.
+    pub pick_by_number: String,
+    /// The title of the pane a file is transferred from.
+    pub source: String,
+    /// The title of the pane a file is transferred to.
+    pub destination: String,
+    /// The error for a path that does not exist, with the placeholder
+    /// `{path}`.
+    pub missing_path: String,
+    /// The error for a path that is not a directory, with the placeholder
+    /// `{path}`.
+    pub not_a_directory: String,
+    /// Describes the actions in the key help.
+    pub actions: HashMap<Action, String>,
+}
+
+impl Default for Messages {
+    fn default() -> Messages {
+        Messages {
+            yes: "yes".into(),
+            no: "no".into(),
+            edit: "edit".into(),
+            use_default: "use this?".into(),
+            answer_yes_no: "Please answer yes or no".into(),
+            answer_either: "Please answer {on} or {off}".into(),
+            answer_key: "Please answer with one of {keys}".into(),
+            hidden: "[hidden]".into(),
+            secret_from: "{hidden} from {source}".into(),
+            skipped: "(skipped)".into(),
+            loading: "Loading…".into(),
+            error: "error".into(),
+            warning: "warning".into(),
+            confirm_warning: "submit it again to use it anyway".into(),
+            selected_count: "({count} selected)".into(),
+            enter_number: "Enter a number".into(),
+            enter_numbers: "Enter numbers separated by commas".into(),
+            invalid_number: "Please enter the number of a selectable item".into(),
+            invalid_numbers: "'{input}' is not the number of a selectable item".into(),
+            number_range: "Please enter a number from 1 to {max}".into(),
+            invalid_duration: "Please enter a duration such as 90s, 5m or 1h30m".into(),
+            invalid_byte_size: "Please enter a size such as 512MB or 1.5GiB".into(),
+            value_at_least: "Please enter at least {min}".into(),
+            value_at_most: "Please enter at most {max}".into(),
+            address_unexpected: "Unexpected '{char}' at position {position}".into(),
+            address_too_large: "Number too large at position {position}".into(),
+            address_incomplete: "Please enter a complete address".into(),
+            select_at_least: "Please select at least {count} {items}".into(),
+            select_at_most: "Please select at most {count} {items}".into(),
+            item: "item".into(),
+            items: "items".into(),
+            tag_hint: "Enter one value per line, an empty line to finish".into(),
+            expected_pair: "Expected key=value".into(),
+            review: "Review your answers".into(),
+            review_confirm: "Confirm".into(),
+            pick_by_number: "pick by number".into(),
+            source: "Source".into(),
+            destination: "Destination".into(),
+            missing_path: "'{path}' does not exist".into(),
+            not_a_directory: "'{path}' is not a directory".into(),
+            actions: Action::ALL
+                .iter()
+                .map(|&action| (action, action.description().into()))
+                .collect(),
+        }
+    }
+}
+
+impl Messages {
+    /// Returns the word for yes or no.
+    pub(crate) fn yes_no(&self, value: bool) -> &str {
+        if value {
+            &self.yes
+        } else {
+            &self.no
+        }
+    }
+
+    /// Returns whether `c` is the key answering with `value`.
+    pub(crate) fn is_yes_no_key(&self, c: char, value: bool) -> bool {
+        is_initial(c, self.yes_no(value))
+    }
+
+    /// Returns whether `c` is the key editing the default of an input.
+    pub(crate) fn is_edit_key(&self, c: char) -> bool {
+        is_initial(c, &self.edit)
+    }
+
+    /// Parses a typed answer to a confirmation.
+    pub(crate) fn parse_yes_no(&self, input: &str) -> Option<bool> {
+        let input = input.trim().to_lowercase();
+        [true, false].iter().cloned().find(|&value| {
+            let word = self.yes_no(value).to_lowercase();
+            input == word || input == initial(&word)
+        })
+    }
+
+    /// Returns the hint of a confirmation, e.g. `[Y/n]`.
+    pub(crate) fn yes_no_hint(&self, default: bool) -> String {
+        let (yes, no) = (initial(&self.yes), initial(&self.no));
+        if default {
+            format!("[{}/{}]", yes.to_uppercase(), no.to_lowercase())
+        } else {
+            format!("[{}/{}]", yes.to_lowercase(), no.to_uppercase())
+        }
+    }
+
+    /// Returns the question whether to use a default, e.g.
+    /// `use this? [Y/n/e]`.
+    pub(crate) fn use_default_hint(&self) -> String {
+        format!(
+            "{} [{}/{}/{}]",
+            self.use_default,
+            initial(&self.yes).to_uppercase(),
+            initial(&self.no).to_lowercase(),
+            initial(&self.edit).to_lowercase()
+        )
+    }
+
+    /// Returns `items` or `item` depending on the count.
+    pub(crate) fn items(&self, count: usize) -> &str {
+        if count == 1 {
+            &self.item
+        } else {
+            &self.items
+        }
+    }
+
+    /// Returns the description of an action in the key help.
+    pub(crate) fn action(&self, action: Action) -> &str {
+        self.actions
+            .get(&action)
+            .map_or(action.description(), |text| text.as_str())
+    }
+}
+
+/// Replaces the built-in texts of all prompts.
+pub fn set_messages(messages: Messages) {
+    *MESSAGES.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(messages));
+}
+
+/// Returns the current table of built-in texts.
+pub(crate) fn messages() -> Arc<Messages> {
+    MESSAGES
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Fills the `{name}` placeholders of a text.
+pub(crate) fn fill(text: &str, args: &[(&str, &dyn ToString)]) -> String {
+    args.iter().fold(text.to_string(), |text, &(name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+fn initial(word: &str) -> String {
+    word.chars().next().map(String::from).unwrap_or_default()
+}
+
+fn is_initial(c: char, word: &str) -> bool {
+    word.chars()
+        .next()
+        .is_some_and(|first| first.to_lowercase().eq(c.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let messages = Messages {
+            yes: "ja".into(),
+            no: "nein".into(),
+            edit: "ändern".into(),
+            ..Messages::default()
+        };
+        assert_eq!(messages.yes_no_hint(false), "[j/N]");
+        assert_eq!(messages.use_default_hint(), "use this? [J/n/ä]");
+        assert!(messages.is_yes_no_key('J', true));
+        assert!(!messages.is_yes_no_key('y', true));
+        assert_eq!(messages.parse_yes_no(" Nein "), Some(false));
+        assert_eq!(messages.parse_yes_no("yes"), None);
+        let text = fill(
+            &messages.select_at_least,
+            &[("count", &2), ("items", &"items")],
+        );
+        assert_eq!(text, "Please select at least 2 items");
+        assert_eq!(messages.action(Action::Up), "move up");
+    }
+}
@@ -0,0 +1,187 @@
+//! Mouse input of the list prompts.
+//!
+//! With the `mouse` feature enabled list prompts turn on the SGR mouse
+//! reporting of the terminal.  The terminal then sends clicks and wheel
+//! turns as escape sequences, which arrive as `Key::UnknownEscSeq`
+//! followed by the rest of the sequence as single characters.
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use console::Key;
+
+use backend::Backend;
+
+/// The longest time between the clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Turns on click and wheel reporting in the SGR format.
+pub(crate) const CAPTURE_ON: &str = "\x1b[?1000h\x1b[?1006h";
+/// Turns mouse reporting off again.
+pub(crate) const CAPTURE_OFF: &str = "\x1b[?1006l\x1b[?1000l";
+
+/// The number of `MouseCapture`s alive on terminals.
+static CAPTURES: AtomicUsize = AtomicUsize::new(0);
+
+/// A mouse event reported by the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mouse {
+    /// The left button was pressed at the given screen row and column,
+    /// both starting at 0.
+    Click { row: usize, col: usize },
+    /// The wheel was turned up.
+    ScrollUp,
+    /// The wheel was turned down.
+    ScrollDown,
+}
+
+/// Turns on mouse reporting for as long as it is alive.
+pub(crate) struct MouseCapture<'a> {
+    term: Option<&'a dyn Backend>,
+}
+
+impl<'a> MouseCapture<'a> {
+    #[cfg(feature = "mouse")]
+    pub fn new(term: &'a dyn Backend) -> io::Result<MouseCapture<'a>> {
+        if !term.is_term() {
+            return Ok(MouseCapture { term: None });
+        }
+        let capture = MouseCapture { term: Some(term) };
+        capture.resume()?;
+        CAPTURES.fetch_add(1, Ordering::SeqCst);
+        Ok(capture)
+    }
+
+    #[cfg(not(feature = "mouse"))]
+    pub fn new(_term: &'a dyn Backend) -> io::Result<MouseCapture<'a>> {
+        Ok(MouseCapture { term: None })
+    }
+
+    /// Stops reporting mouse events until `resume` is called.
+    pub fn pause(&self) -> io::Result<()> {
+        if let Some(term) = self.term {
+            term.write_str(CAPTURE_OFF)?;
+            term.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn resume(&self) -> io::Result<()> {
+        if let Some(term) = self.term {
+            term.write_str(CAPTURE_ON)?;
+            term.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for MouseCapture<'a> {
+    fn drop(&mut self) {
+        if self.term.is_some() {
+            CAPTURES.fetch_sub(1, Ordering::SeqCst);
+        }
+        let _ = self.pause();
+    }
+}
+
+/// Returns `true` while a prompt has mouse reporting turned on.
+pub(crate) fn is_captured() -> bool {
+    CAPTURES.load(Ordering::SeqCst) > 0
+}
+
+/// Reads the mouse event `key` starts, if it starts one.
+///
+/// Without the `mouse` feature the terminal sends no such events.
+pub(crate) fn read_mouse(term: &dyn Backend, key: &Key) -> io::Result<Option<Mouse>> {
+    match *key {
+        Key::UnknownEscSeq(ref seq) if seq.starts_with(&['[', '<']) => {
+            Ok(parse_mouse(&read_csi(term, seq)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Asks the terminal for the row of the cursor, starting at 0.
+pub(crate) fn cursor_row(term: &dyn Backend) -> io::Result<Option<usize>> {
+    term.write_str("\x1b[6n")?;
+    term.flush()?;
+    let seq = match term.read_key()? {
+        Key::UnknownEscSeq(ref seq) if seq.first() == Some(&'[') => read_csi(term, seq)?,
+        _ => return Ok(None),
+    };
+    Ok(seq
+        .strip_suffix('R')
+        .and_then(|pos| pos.split(';').next())
+        .and_then(|row| row.parse::<usize>().ok())
+        .and_then(|row| row.checked_sub(1)))
+}
+
+/// Reads the rest of a control sequence whose start console already
+/// consumed and returns it without the leading `[`.
+fn read_csi(term: &dyn Backend, seq: &[char]) -> io::Result<String> {
+    let mut rv: String = seq[1..].iter().collect();
+    while !rv.ends_with(|c| ('@'..='~').contains(&c)) {
+        match term.read_key()? {
+            Key::Char(c) => rv.push(c),
+            _ => break,
+        }
+    }
+    Ok(rv)
+}
+
+/// Parses an SGR mouse report such as `<0;12;3M`.
+fn parse_mouse(seq: &str) -> Option<Mouse> {
+    let params = seq.strip_prefix('<')?.strip_suffix('M')?;
+    let mut params = params.split(';').map(|param| param.parse::<usize>().ok());
+    let (button, col, row) = (params.next()??, params.next()??, params.next()??);
+    match button {
+        0 => Some(Mouse::Click {
+            row: row.checked_sub(1)?,
+            col: col.checked_sub(1)?,
+        }),
+        64 => Some(Mouse::ScrollUp),
+        65 => Some(Mouse::ScrollDown),
+        _ => None,
+    }
+}
+
+/// Tells double clicks from single clicks.
+#[derive(Default)]
+pub(crate) struct Clicks {
+    last: Option<(usize, Instant)>,
+}
+
+impl Clicks {
+    /// Records a click on an item and returns whether it was the second
+    /// click of a double click.
+    pub fn click(&mut self, idx: usize) -> bool {
+        let now = Instant::now();
+        let double = match self.last {
+            Some((last, at)) => last == idx && now - at <= DOUBLE_CLICK,
+            None => false,
+        };
+        self.last = if double { None } else { Some((idx, now)) };
+        double
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mouse() {
+        assert_eq!(
+            parse_mouse("<0;12;3M"),
+            Some(Mouse::Click { row: 2, col: 11 })
+        );
+        assert_eq!(parse_mouse("<0;12;3m"), None);
+        assert_eq!(parse_mouse("<65;1;1M"), Some(Mouse::ScrollDown));
+        assert_eq!(parse_mouse("<2;1;1M"), None);
+        let mut clicks = Clicks::default();
+        assert!(!clicks.click(1));
+        assert!(clicks.click(1));
+        assert!(!clicks.click(2));
+        assert!(!clicks.click(3));
+    }
+}
@@ -0,0 +1,405 @@
+use std::io;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use console::{Key, Style};
+use ipnet::IpNet;
+
+use backend::{Backend, TermGuard};
+use events::{pop_grapheme, read_key, read_plain_line, wait_for_event, Event};
+use messages::{fill, messages};
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders an input prompt for an IPv4 or IPv6 address.
+///
+/// The input is checked as it is typed: a character that cannot be part
+/// of an address, or a number too large for its place, is reported with
+/// its position right away.  An address that is merely incomplete is only
+/// rejected on enter.
+///
+/// `interact` returns an `IpAddr`.  `interact_net` also takes a CIDR
+/// prefix such as `/24` and returns an `ipnet::IpNet`; an address without
+/// a prefix becomes a network of that one host.  The report line shows
+/// the value normalized, e.g. IPv6 addresses in their shortest form.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::IpInput;
+///
+/// let gateway = IpInput::new().with_prompt("Gateway").interact()?;
+/// let subnet = IpInput::new().with_prompt("Subnet").interact_net()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct IpInput<'a> {
+    prompt: String,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a> IpInput<'a> {
+    /// Creates a new address input.
+    pub fn new() -> IpInput<'static> {
+        IpInput::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> IpInput<'a> {
+        IpInput::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> IpInput<'static> {
+        IpInput::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> IpInput<'a> {
+        IpInput {
+            prompt: "".into(),
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the input prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut IpInput<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut IpInput<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut IpInput<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the address.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<IpAddr> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<IpAddr>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, IpInput::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<IpAddr> {
+        self.read_value(term, false)
+    }
+
+    /// Like `interact` but takes an optional CIDR prefix and returns the
+    /// network.
+    pub fn interact_net(&self) -> io::Result<IpNet> {
+        self.interact_net_on(&self.stream.term())
+    }
+
+    /// Like `interact_net` but allows a specific terminal to be set.
+    pub fn interact_net_on(&self, term: &dyn Backend) -> io::Result<IpNet> {
+        self.read_value(term, true)
+    }
+
+    fn read_value<V: Address>(&self, term: &dyn Backend, net: bool) -> io::Result<V> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term, net),
+            |value: &V| Some(value.to_string()),
+        )
+    }
+
+    fn read_answer<V: Address>(&self, term: &dyn Backend, net: bool) -> io::Result<V> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.read_answer_accessible(term, &mut render, net);
+        }
+        let mut input = String::new();
+        let mut error: Option<String> = None;
+        loop {
+            render.begin_frame();
+            render.input_line(&self.prompt, &input)?;
+            if let Some(ref error) = error {
+                render.error(error)?;
+            }
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                continue;
+            }
+            match render.clear_interrupted(read_key(term))? {
+                Key::Enter => match parse::<V>(&input, net) {
+                    Ok(value) => {
+                        render.clear()?;
+                        render.single_prompt_selection(&self.prompt, &value.to_string())?;
+                        return Ok(value);
+                    }
+                    Err(err) => {
+                        error = Some(err);
+                        continue;
+                    }
+                },
+                Key::Backspace => {
+                    pop_grapheme(&mut input);
+                }
+                Key::Char(c) if !c.is_control() => input.push(c),
+                _ => continue,
+            }
+            error = check(&input, net).err().map(AddressError::message);
+        }
+    }
+
+    /// Reads the address as a plain line for the accessible render mode.
+    fn read_answer_accessible<V: Address>(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        net: bool,
+    ) -> io::Result<V> {
+        loop {
+            render.input_prompt(&self.prompt, None)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            match parse::<V>(input.trim(), net) {
+                Ok(value) => {
+                    render.single_prompt_selection(&self.prompt, &value.to_string())?;
+                    return Ok(value);
+                }
+                Err(err) => render.error(&err)?,
+            }
+        }
+    }
+}
+
+/// The values an `IpInput` returns.
+trait Address: ToString + Sized {
+    fn from_input(input: &str) -> Option<Self>;
+}
+
+impl Address for IpAddr {
+    fn from_input(input: &str) -> Option<IpAddr> {
+        IpAddr::from_str(input).ok()
+    }
+}
+
+impl Address for IpNet {
+    fn from_input(input: &str) -> Option<IpNet> {
+        if input.contains('/') {
+            IpNet::from_str(input).ok()
+        } else {
+            IpAddr::from_str(input).ok().map(IpNet::from)
+        }
+    }
+}
+
+/// Parses an address, or returns the message of why it is rejected.
+fn parse<V: Address>(input: &str, net: bool) -> Result<V, String> {
+    check(input, net).map_err(AddressError::message)?;
+    V::from_input(input).ok_or_else(|| messages().address_incomplete.clone())
+}
+
+/// Why typed text is not an address, with positions counted in characters
+/// from zero.
+#[derive(Debug, PartialEq)]
+enum AddressError {
+    /// A character that cannot follow the text before it.
+    Unexpected(usize, char),
+    /// A number that starts at the position and is too large.
+    TooLarge(usize),
+}
+
+impl AddressError {
+    /// Returns the error shown, with positions counted from one.
+    fn message(self) -> String {
+        match self {
+            AddressError::Unexpected(pos, c) => fill(
+                &messages().address_unexpected,
+                &[("char", &c), ("position", &(pos + 1))],
+            ),
+            AddressError::TooLarge(pos) => {
+                fill(&messages().address_too_large, &[("position", &(pos + 1))])
+            }
+        }
+    }
+}
+
+/// Checks that text can still become an address, and with `net` an
+/// address with a prefix.
+///
+/// Everything before an error is ASCII, so byte offsets are character
+/// positions.
+fn check(input: &str, net: bool) -> Result<(), AddressError> {
+    let (addr, prefix) = match input.find('/') {
+        Some(idx) if net => (&input[..idx], Some(idx + 1)),
+        Some(idx) => (&input[..idx], None),
+        None => (input, None),
+    };
+    let v6 = addr.contains(':');
+    if v6 {
+        check_v6(addr)?;
+    } else {
+        check_v4(addr, 0)?;
+    }
+    if addr.len() < input.len() && prefix.is_none() {
+        return Err(AddressError::Unexpected(addr.len(), '/'));
+    }
+    if let Some(start) = prefix {
+        if let Some((idx, c)) = input[start..]
+            .char_indices()
+            .find(|&(_, c)| !c.is_ascii_digit())
+        {
+            return Err(AddressError::Unexpected(start + idx, c));
+        }
+        let max = if v6 { 128 } else { 32 };
+        if input.len() > start && input[start..].parse().map_or(true, |len: u32| len > max) {
+            return Err(AddressError::TooLarge(start));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the dotted part of an IPv4 address that starts at `offset`.
+fn check_v4(addr: &str, offset: usize) -> Result<(), AddressError> {
+    let mut start = 0;
+    for (idx, c) in addr.char_indices() {
+        match c {
+            '0'..='9' if idx - start < 3 => {}
+            '0'..='9' => return Err(AddressError::TooLarge(offset + start)),
+            '.' if idx > start && addr[..idx].matches('.').count() < 3 => start = idx + 1,
+            c => return Err(AddressError::Unexpected(offset + idx, c)),
+        }
+        if addr[start..=idx]
+            .parse()
+            .is_ok_and(|octet: u32| octet > 255)
+        {
+            return Err(AddressError::TooLarge(offset + start));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the colon separated groups of an IPv6 address.
+fn check_v6(addr: &str) -> Result<(), AddressError> {
+    let mut groups = 0;
+    let mut start = 0;
+    let mut colons = 0;
+    let mut compressed = false;
+    for (idx, c) in addr.char_indices() {
+        match c {
+            ':' => {
+                colons += 1;
+                if colons > 2 || colons == 2 && compressed {
+                    return Err(AddressError::Unexpected(idx, c));
+                }
+                compressed |= colons == 2;
+            }
+            '.' if groups > 0 && colons == 0 => return check_v4(&addr[start..], start),
+            c if c.is_ascii_hexdigit() => {
+                if idx == 1 && colons == 1 {
+                    return Err(AddressError::Unexpected(idx, c));
+                }
+                if colons > 0 || idx == 0 {
+                    groups += 1;
+                    start = idx;
+                    let max = if compressed { 7 } else { 8 };
+                    if groups > max {
+                        return Err(AddressError::Unexpected(idx.saturating_sub(1), ':'));
+                    }
+                }
+                colons = 0;
+                if idx - start >= 4 {
+                    return Err(AddressError::TooLarge(start));
+                }
+            }
+            c => return Err(AddressError::Unexpected(idx, c)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_check() {
+        use self::AddressError::*;
+
+        assert_eq!(check("192.168.", false), Ok(()));
+        assert_eq!(check("192.168.1.256", false), Err(TooLarge(10)));
+        assert_eq!(check("192..", false), Err(Unexpected(4, '.')));
+        assert_eq!(check("1.2.3.4.", false), Err(Unexpected(7, '.')));
+        assert_eq!(check("10.0.0.0/8", false), Err(Unexpected(8, '/')));
+        assert_eq!(check("10.0.0.0/33", true), Err(TooLarge(9)));
+        assert_eq!(check("10.0.0.0/2x", true), Err(Unexpected(10, 'x')));
+        assert_eq!(check("fe80::1/64", true), Ok(()));
+        assert_eq!(check("fe80:::", false), Err(Unexpected(6, ':')));
+        assert_eq!(check("::1::", false), Err(Unexpected(4, ':')));
+        assert_eq!(check(":1", false), Err(Unexpected(1, '1')));
+        assert_eq!(check("12345", false), Err(TooLarge(0)));
+        assert_eq!(check("abcde:", false), Err(TooLarge(0)));
+        assert_eq!(check("1:2:3:4:5:6:7:8:9", false), Err(Unexpected(15, ':')));
+        assert_eq!(check("::ffff:10.0.0.300", false), Err(TooLarge(14)));
+        assert_eq!(check("fe80::g", false), Err(Unexpected(6, 'g')));
+    }
+
+    #[test]
+    fn test_ip_input() {
+        let mut term = VirtualTerm::new(10, 60);
+        term.text("10.0.0.0/33");
+        term.keys(&[Key::Backspace, Key::Backspace]);
+        term.text("8\n");
+        let net = IpInput::new()
+            .with_prompt("Subnet")
+            .interact_net_on(&term)
+            .unwrap();
+        assert_eq!(net, "10.0.0.0/8".parse().unwrap());
+        assert_eq!(
+            term.frames()[11],
+            "Subnet: 10.0.0.0/33\nerror: Number too large at position 10"
+        );
+        assert_eq!(term.screen(), "Subnet: 10.0.0.0/8");
+
+        let mut term = VirtualTerm::new(10, 60);
+        term.text("fe80:0:0::1\n");
+        let addr = IpInput::new()
+            .with_prompt("Address")
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(addr, "fe80::1".parse::<IpAddr>().unwrap());
+        assert_eq!(term.screen(), "Address: fe80::1");
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(IpInput::new());
+        assert_send(IpInput::with_owned_theme(ColorfulTheme::default()));
+    }
+}
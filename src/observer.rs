@@ -0,0 +1,239 @@
+//! Hooks that let applications watch prompts, e.g. to log answers or to
+//! time how long users spend on each question.
+use std::cell::RefCell;
+use std::io;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+static OBSERVER: RwLock<Option<Arc<dyn PromptObserver>>> = RwLock::new(None);
+
+thread_local! {
+    /// The prompts open on this thread, the innermost one last.
+    static OPEN: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Receives the lifecycle events of all prompts.
+///
+/// All methods do nothing by default, so an observer only implements the
+/// events it cares about.  Prompts pass their prompt text to identify
+/// themselves.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::time::Duration;
+/// use dialoguer::{set_observer, Input, PromptObserver};
+///
+/// struct Timer;
+///
+/// impl PromptObserver for Timer {
+///     fn on_submit(&self, prompt: &str, answer: &str, elapsed: Duration) {
+///         eprintln!("{:?} answered {:?} in {:?}", prompt, answer, elapsed);
+///     }
+/// }
+///
+/// set_observer(Some(Box::new(Timer)));
+/// let name: String = Input::new().with_prompt("Name").interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub trait PromptObserver: Send + Sync {
+    /// Called when a prompt opens.
+    fn on_start(&self, _prompt: &str) {}
+
+    /// Called when a prompt was answered, with the answer as the prompt
+    /// reports it and the time the prompt was open.
+    ///
+    /// Passwords and hidden input are passed as the `hidden` message.
+    fn on_submit(&self, _prompt: &str, _answer: &str, _elapsed: Duration) {}
+
+    /// Called when a prompt closed without an answer, e.g. because the
+    /// user hit Ctrl+C or skipped it, or because it failed.
+    fn on_cancel(&self, _prompt: &str, _elapsed: Duration) {}
+
+    /// Called when a prompt rejected an answer, with the error it shows.
+    fn on_invalid(&self, _prompt: &str, _error: &str) {}
+}
+
+/// Sets the observer all prompts report to, or `None` to remove it.
+pub fn set_observer(observer: Option<Box<dyn PromptObserver>>) {
+    *OBSERVER.write().unwrap_or_else(|err| err.into_inner()) = observer.map(Arc::from);
+}
+
+fn observer() -> Option<Arc<dyn PromptObserver>> {
+    OBSERVER
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+/// Runs a prompt and reports it to the observer.
+///
+/// `answer` turns the result into the answer passed to `on_submit`, or
+/// `None` if the prompt was skipped.
+pub(crate) fn observe<T, F, A>(prompt: &str, f: F, answer: A) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T>,
+    A: FnOnce(&T) -> Option<String>,
+{
+    observe_secret(prompt, false, f, answer)
+}
+
+/// Like `observe` but for prompts whose answer may be `secret`, which is
+/// logged as `<redacted>`.
+pub(crate) fn observe_secret<T, F, A>(prompt: &str, secret: bool, f: F, answer: A) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T>,
+    A: FnOnce(&T) -> Option<String>,
+{
+    let observer = observer();
+    if observer.is_none() && !logging() {
+        return f();
+    }
+    if let Some(ref observer) = observer {
+        observer.on_start(prompt);
+    }
+    OPEN.with(|open| open.borrow_mut().push(prompt.into()));
+    let started = Instant::now();
+    let rv = f();
+    OPEN.with(|open| open.borrow_mut().pop());
+    let elapsed = started.elapsed();
+    let answer = rv.as_ref().ok().and_then(answer);
+    let logged = answer
+        .as_deref()
+        .map(|answer| if secret { "<redacted>" } else { answer });
+    log_answer(prompt, logged, elapsed);
+    match (observer, answer) {
+        (Some(observer), Some(answer)) => observer.on_submit(prompt, &answer, elapsed),
+        (Some(observer), None) => observer.on_cancel(prompt, elapsed),
+        (None, _) => {}
+    }
+    rv
+}
+
+/// Returns whether answers are logged.
+#[cfg(feature = "log")]
+fn logging() -> bool {
+    log::log_enabled!(target: "dialoguer", log::Level::Debug)
+}
+
+#[cfg(not(feature = "log"))]
+fn logging() -> bool {
+    false
+}
+
+/// Logs how a prompt closed as a debug event of the `dialoguer` target
+/// with the fields `prompt`, `answer` and `elapsed`.
+#[cfg(feature = "log")]
+fn log_answer(prompt: &str, answer: Option<&str>, elapsed: Duration) {
+    match answer {
+        Some(answer) => log::debug!(
+            target: "dialoguer", prompt, answer, elapsed:?;
+            "{:?} answered with {:?} after {:?}", prompt, answer, elapsed
+        ),
+        None => log::debug!(
+            target: "dialoguer", prompt, elapsed:?;
+            "{:?} closed without an answer after {:?}", prompt, elapsed
+        ),
+    }
+}
+
+#[cfg(not(feature = "log"))]
+fn log_answer(_prompt: &str, _answer: Option<&str>, _elapsed: Duration) {}
+
+/// Returns the labels of the picked items of a menu as an answer.
+///
+/// Items beyond `items`, e.g. ones added while the menu was open, are
+/// given by their index.
+pub(crate) fn labels(items: &[String], picked: &[usize]) -> String {
+    let labels: Vec<String> = picked
+        .iter()
+        .map(|&idx| items.get(idx).cloned().unwrap_or_else(|| idx.to_string()))
+        .collect();
+    labels.join(", ")
+}
+
+/// Reports an error shown by the innermost open prompt.
+pub(crate) fn invalid(error: &str) {
+    let prompt = match OPEN.with(|open| open.borrow().last().cloned()) {
+        Some(prompt) => prompt,
+        None => return,
+    };
+    if let Some(observer) = observer() {
+        observer.on_invalid(&prompt, error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl PromptObserver for Arc<Recorder> {
+        fn on_start(&self, prompt: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {}", prompt));
+        }
+
+        fn on_submit(&self, prompt: &str, answer: &str, _elapsed: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("submit {} {}", prompt, answer));
+        }
+
+        fn on_cancel(&self, prompt: &str, _elapsed: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("cancel {}", prompt));
+        }
+
+        fn on_invalid(&self, prompt: &str, error: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("invalid {} {}", prompt, error));
+        }
+    }
+
+    #[test]
+    fn test_observe() {
+        let recorder = Arc::new(Recorder::default());
+        set_observer(Some(Box::new(recorder.clone())));
+        let port = || {
+            invalid("not a number");
+            Ok(8080)
+        };
+        assert_eq!(
+            observe("Observed port", port, |port| Some(port.to_string())).unwrap(),
+            8080
+        );
+        let name = || -> io::Result<String> { Err(io::ErrorKind::Interrupted.into()) };
+        assert!(observe("Observed name", name, |name| Some(name.clone())).is_err());
+        invalid("outside of a prompt");
+        set_observer(None);
+        // other tests may run prompts at the same time
+        let events = recorder.events.lock().unwrap();
+        let events: Vec<&str> = events
+            .iter()
+            .map(|event| event.as_str())
+            .filter(|event| event.contains("Observed") || event.contains("outside"))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                "start Observed port",
+                "invalid Observed port not a number",
+                "submit Observed port 8080",
+                "start Observed name",
+                "cancel Observed name",
+            ]
+        );
+    }
+}
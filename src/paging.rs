@@ -0,0 +1,126 @@
+//! Scrolling of menus that are taller than the terminal.
+use backend::Backend;
+use keymap::Action;
+
+/// Keeps track of the visible window of a menu.
+pub(crate) struct Paging {
+    items: usize,
+    force: bool,
+    capacity: usize,
+    offset: usize,
+    active: bool,
+}
+
+impl Paging {
+    /// Creates the paging state for a menu with the given number of items.
+    ///
+    /// With `force` the position indicator is shown even if all items fit.
+    pub fn new(items: usize, force: bool) -> Paging {
+        Paging {
+            items,
+            force,
+            capacity: items,
+            offset: 0,
+            active: force,
+        }
+    }
+
+    /// Changes the number of items, e.g. after filtering.
+    pub fn set_items(&mut self, items: usize) {
+        if items != self.items {
+            self.items = items;
+            self.offset = 0;
+        }
+    }
+
+    /// Fits the window into the terminal and scrolls `sel` into view.
+    ///
+    /// `reserved` is the number of rows needed for everything but the
+    /// items, e.g. the prompt and help text.
+    pub fn update(&mut self, term: &dyn Backend, reserved: usize, sel: usize) {
+        // one row is kept free for the cursor below the menu
+        self.fit((term.size().0 as usize).saturating_sub(reserved + 1));
+        self.scroll_to(sel);
+    }
+
+    fn fit(&mut self, rows: usize) {
+        self.active = self.force || self.items > rows;
+        self.capacity = if self.active {
+            rows.saturating_sub(1).max(1)
+        } else {
+            self.items
+        };
+    }
+
+    fn scroll_to(&mut self, sel: usize) {
+        if sel >= self.items {
+            return;
+        }
+        if sel < self.offset {
+            self.offset = sel;
+        } else if sel >= self.offset + self.capacity {
+            self.offset = sel + 1 - self.capacity;
+        }
+        self.offset = self.offset.min(self.items.saturating_sub(self.capacity));
+    }
+
+    /// The index of the first visible item.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The number of visible items.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the position indicator (`3/120`) if the menu is paged.
+    pub fn indicator(&self, sel: usize) -> Option<String> {
+        if !self.active {
+            return None;
+        }
+        let pos = if sel < self.items { sel + 1 } else { 0 };
+        Some(format!("{}/{}", pos, self.items))
+    }
+
+    /// Returns the new selection if `action` is a paging action.
+    pub fn handle_action(&self, action: Action, sel: usize) -> Option<usize> {
+        if self.items == 0 {
+            return None;
+        }
+        let sel = if sel < self.items { sel } else { 0 };
+        let last = self.items - 1;
+        match action {
+            Action::PageUp => Some(sel.saturating_sub(self.capacity)),
+            Action::PageDown => Some((sel + self.capacity).min(last)),
+            Action::First => Some(0),
+            Action::Last => Some(last),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_window() {
+        let mut paging = Paging::new(120, false);
+        paging.fit(11);
+        assert_eq!(paging.capacity(), 10);
+        paging.scroll_to(2);
+        assert_eq!(paging.offset(), 0);
+        assert_eq!(paging.indicator(2).unwrap(), "3/120");
+        paging.scroll_to(15);
+        assert_eq!(paging.offset(), 6);
+        assert_eq!(paging.handle_action(Action::PageDown, 15), Some(25));
+        assert_eq!(paging.handle_action(Action::PageUp, 5), Some(0));
+        assert_eq!(paging.handle_action(Action::Last, 5), Some(119));
+
+        let mut paging = Paging::new(5, false);
+        paging.fit(11);
+        assert_eq!(paging.capacity(), 5);
+        assert_eq!(paging.indicator(0), None);
+    }
+}
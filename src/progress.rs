@@ -0,0 +1,335 @@
+//! Themed progress bars and spinners.
+use std::io;
+use std::panic;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use console::Term;
+
+use stream::Stream;
+use theme::{get_default_theme, is_accessible_mode, TermThemeRenderer, Theme, ThemeRef};
+
+/// How often a spinner advances while it waits for work to complete.
+pub(crate) const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// The status line shared by progress bars and spinners.
+struct StatusLine<'a> {
+    term: Term,
+    theme: ThemeRef<'a>,
+    message: String,
+    drawn: bool,
+}
+
+impl<'a> StatusLine<'a> {
+    fn new(theme: ThemeRef<'a>) -> StatusLine<'a> {
+        StatusLine {
+            term: Term::stderr(),
+            theme,
+            message: String::new(),
+            drawn: false,
+        }
+    }
+
+    /// Redraws the line unless the terminal cannot update it in place.
+    fn draw<F: FnOnce(&mut TermThemeRenderer) -> io::Result<()>>(
+        &mut self,
+        f: F,
+    ) -> io::Result<()> {
+        if !self.term.is_term() || is_accessible_mode() {
+            return Ok(());
+        }
+        self.clear()?;
+        f(&mut TermThemeRenderer::new(&self.term, &*self.theme))?;
+        self.drawn = true;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        if self.drawn {
+            self.term.clear_last_lines(1)?;
+            self.drawn = false;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, message: Option<&str>) -> io::Result<()> {
+        self.clear()?;
+        match message {
+            Some(message) => {
+                TermThemeRenderer::new(&self.term, &*self.theme).progress_done(message)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Renders a progress bar with the theme of the prompts.
+///
+/// The bar is drawn on stderr by default and redrawn in place on every
+/// update.  If the stream is not a terminal or the accessible mode is
+/// active only the final message is printed.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::{Confirmation, ProgressBar};
+///
+/// let mut bar = ProgressBar::new(3);
+/// bar.with_message("Downloading");
+/// for _ in 0..3 {
+///     bar.inc(1)?;
+/// }
+/// let keep = bar.suspend(|| Confirmation::new().with_text("Keep the file?").interact())??;
+/// bar.finish_with_message(if keep { "Downloaded" } else { "Discarded" })?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct ProgressBar<'a> {
+    line: StatusLine<'a>,
+    pos: u64,
+    len: u64,
+}
+
+impl<'a> ProgressBar<'a> {
+    /// Creates a progress bar for `len` steps.
+    pub fn new(len: u64) -> ProgressBar<'static> {
+        ProgressBar::with_theme(get_default_theme(), len)
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync), len: u64) -> ProgressBar<'a> {
+        ProgressBar {
+            line: StatusLine::new(ThemeRef::Borrowed(theme)),
+            pos: 0,
+            len,
+        }
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T>(theme: T, len: u64) -> ProgressBar<'static>
+    where
+        T: Theme + Send + Sync + 'static,
+    {
+        ProgressBar {
+            line: StatusLine::new(ThemeRef::Owned(Arc::new(theme))),
+            pos: 0,
+            len,
+        }
+    }
+
+    /// Sets the stream the bar is rendered on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut ProgressBar<'a> {
+        self.line.term = stream.term();
+        self
+    }
+
+    /// Sets the message shown in front of the bar.
+    pub fn with_message(&mut self, message: &str) -> &mut ProgressBar<'a> {
+        self.line.message = message.to_string();
+        self
+    }
+
+    /// The current position.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Advances the bar by `delta` steps and redraws it.
+    pub fn inc(&mut self, delta: u64) -> io::Result<()> {
+        let pos = self.pos.saturating_add(delta);
+        self.set_position(pos)
+    }
+
+    /// Moves the bar to `pos` and redraws it.
+    pub fn set_position(&mut self, pos: u64) -> io::Result<()> {
+        self.pos = pos.min(self.len);
+        self.draw()
+    }
+
+    /// Changes the message and redraws the bar.
+    pub fn set_message(&mut self, message: &str) -> io::Result<()> {
+        self.line.message = message.to_string();
+        self.draw()
+    }
+
+    /// Hides the bar while `f` runs, e.g. to show a prompt, and redraws it
+    /// afterwards.
+    pub fn suspend<R, F: FnOnce() -> R>(&mut self, f: F) -> io::Result<R> {
+        self.line.clear()?;
+        let rv = f();
+        self.draw()?;
+        Ok(rv)
+    }
+
+    /// Removes the bar from the terminal.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.line.finish(None)
+    }
+
+    /// Replaces the bar with a final message.
+    pub fn finish_with_message(&mut self, message: &str) -> io::Result<()> {
+        self.line.finish(Some(message))
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let (pos, len) = (self.pos, self.len);
+        let message = self.line.message.clone();
+        self.line
+            .draw(|render| render.progress_bar(&message, pos, len))
+    }
+}
+
+/// Renders a spinner with the theme of the prompts.
+///
+/// The spinner advances on every `tick`.  Like `ProgressBar` it only
+/// prints the final message if its stream is not a terminal.
+pub struct Spinner<'a> {
+    line: StatusLine<'a>,
+    tick: usize,
+}
+
+impl<'a> Spinner<'a> {
+    /// Creates a spinner.
+    pub fn new() -> Spinner<'static> {
+        Spinner::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Spinner<'a> {
+        Spinner {
+            line: StatusLine::new(ThemeRef::Borrowed(theme)),
+            tick: 0,
+        }
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Spinner<'static> {
+        Spinner {
+            line: StatusLine::new(ThemeRef::Owned(Arc::new(theme))),
+            tick: 0,
+        }
+    }
+
+    /// Sets the stream the spinner is rendered on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Spinner<'a> {
+        self.line.term = stream.term();
+        self
+    }
+
+    /// Sets the message shown next to the spinner.
+    pub fn with_message(&mut self, message: &str) -> &mut Spinner<'a> {
+        self.line.message = message.to_string();
+        self
+    }
+
+    /// Advances the spinner and redraws it.
+    pub fn tick(&mut self) -> io::Result<()> {
+        self.tick = self.tick.wrapping_add(1);
+        self.draw()
+    }
+
+    /// Changes the message and redraws the spinner.
+    pub fn set_message(&mut self, message: &str) -> io::Result<()> {
+        self.line.message = message.to_string();
+        self.draw()
+    }
+
+    /// Hides the spinner while `f` runs, e.g. to show a prompt, and
+    /// redraws it afterwards.
+    pub fn suspend<R, F: FnOnce() -> R>(&mut self, f: F) -> io::Result<R> {
+        self.line.clear()?;
+        let rv = f();
+        self.draw()?;
+        Ok(rv)
+    }
+
+    /// Runs `f` on another thread while the spinner advances, then removes
+    /// the spinner and returns the result of `f`.
+    ///
+    /// This keeps the terminal lively while the items of a prompt are
+    /// computed; the prompt then appears in place of the spinner.
+    ///
+    /// ```rust,no_run
+    /// # fn test() -> Result<(), Box<std::error::Error>> {
+    /// use dialoguer::{Select, Spinner};
+    ///
+    /// # fn list_branches() -> Vec<String> { vec![] }
+    /// let branches = Spinner::new()
+    ///     .with_message("Fetching branches")
+    ///     .run(|| list_branches())?;
+    /// let branch = Select::new().items(&branches).interact()?;
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    pub fn run<R: Send, F: FnOnce() -> R + Send>(&mut self, f: F) -> io::Result<R> {
+        let rv = spin_while(f, |_| self.tick())?;
+        self.line.clear()?;
+        Ok(rv)
+    }
+
+    /// Removes the spinner from the terminal.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.line.finish(None)
+    }
+
+    /// Replaces the spinner with a final message.
+    pub fn finish_with_message(&mut self, message: &str) -> io::Result<()> {
+        self.line.finish(Some(message))
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let tick = self.tick;
+        let message = self.line.message.clone();
+        self.line.draw(|render| render.spinner(&message, tick))
+    }
+}
+
+/// Runs `f` on another thread and calls `tick` with an increasing count
+/// every `SPINNER_INTERVAL` until it completes.
+///
+/// A panic of `f` is resumed on the calling thread.
+pub(crate) fn spin_while<R, F, T>(f: F, mut tick: T) -> io::Result<R>
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+    T: FnMut(usize) -> io::Result<()>,
+{
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        let task = scope.spawn(move || {
+            let _ = tx.send(f());
+        });
+        let mut count = 0;
+        loop {
+            tick(count)?;
+            match rx.recv_timeout(SPINNER_INTERVAL) {
+                Ok(rv) => return Ok(rv),
+                Err(RecvTimeoutError::Timeout) => count += 1,
+                Err(RecvTimeoutError::Disconnected) => match task.join() {
+                    Err(payload) => panic::resume_unwind(payload),
+                    Ok(()) => unreachable!("the task sends its result before it ends"),
+                },
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(ProgressBar::new(10));
+        assert_send(ProgressBar::with_owned_theme(ColorfulTheme::default(), 10));
+        assert_send(Spinner::new());
+        assert_send(Spinner::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -1,10 +1,37 @@
+use std::borrow::Cow;
+use std::env;
 use std::fmt::{Debug, Display};
+#[cfg(feature = "tokio")]
+use std::future::{poll_fn, Future};
 use std::io;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
 use std::str::FromStr;
+#[cfg(feature = "tokio")]
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
 
-use console::Term;
-use theme::{get_default_theme, TermThemeRenderer, Theme};
-use validate::Validator;
+use console::{Key, Style};
+
+use backend::{Backend, TermGuard};
+use complete::{Completer, WordCompleter, WordSplitter};
+use context::{AssumePolicy, NonInteractive};
+use events::{
+    read_char, read_edited_line, read_hidden_line, read_plain_line, read_secure_line, timed_out,
+    wait_for_key, wait_for_line_key,
+};
+use messages::{fill, messages};
+use observer::{observe, observe_secret};
+#[cfg(feature = "tokio")]
+use progress::SPINNER_INTERVAL;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, spawn_future, PromptFuture};
+use secret::SecretSource;
+use state;
+use stream::Stream;
+use theme::{get_default_theme, ReportFn, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+use validate::{count_attempt, Validation, Validator};
 
 /// Renders a simple confirmation prompt.
 ///
@@ -25,9 +52,31 @@ pub struct Confirmation<'a> {
     text: String,
     default: bool,
     show_default: bool,
-    theme: &'a Theme,
+    theme: ThemeRef<'a>,
+    reporter: Option<ReportFn<'a>>,
+    timeout: Option<Duration>,
+    non_interactive: NonInteractive,
+    assume: AssumePolicy,
+    answer: Option<bool>,
+    stream: Stream,
 }
 
+/// Turns the raw input and its parse error into the message shown.
+type ParseErrorMsg<'a, E> = Box<dyn Fn(&str, &E) -> String + Send + 'a>;
+
+/// The validators of an input chained into one function.
+type ValidatorFn<'a> = Box<dyn Fn(&str) -> Validation<String> + Send + 'a>;
+
+/// The post processing steps of an input chained into one function.
+type PostProcessFn<'a> = Box<dyn Fn(&str) -> String + Send + 'a>;
+
+/// An asynchronous validator with its error turned into the message shown.
+#[cfg(feature = "tokio")]
+type AsyncValidatorFn = Box<dyn Fn(String) -> ValidationFuture + Send>;
+
+#[cfg(feature = "tokio")]
+type ValidationFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
 /// Renders a simple input prompt.
 ///
 /// ## Example usage
@@ -40,13 +89,32 @@ pub struct Confirmation<'a> {
 /// println!("Name: {}", name);
 /// # Ok(()) } fn main() { test().unwrap(); }
 /// ```
-pub struct Input<'a, T> {
+pub struct Input<'a, T: FromStr> {
     prompt: String,
     default: Option<T>,
+    remember_key: Option<String>,
     show_default: bool,
-    theme: &'a Theme,
+    theme: ThemeRef<'a>,
+    reporter: Option<ReportFn<'a>>,
     permit_empty: bool,
-    validator: Option<Box<Fn(&str) -> Option<String>>>,
+    post_process: Option<PostProcessFn<'a>>,
+    validator: Option<ValidatorFn<'a>>,
+    #[cfg(feature = "tokio")]
+    async_validators: Vec<AsyncValidatorFn>,
+    parse_error_msg: Option<ParseErrorMsg<'a, T::Err>>,
+    completer: Option<Box<dyn Completer + Send + 'a>>,
+    word_splitter: Option<Box<dyn WordSplitter + Send + 'a>>,
+    confirm_default: bool,
+    initial_text: Option<String>,
+    hide_input: bool,
+    optional: bool,
+    skip_key: Key,
+    max_invalid_attempts: Option<usize>,
+    timeout: Option<Duration>,
+    non_interactive: NonInteractive,
+    assume: AssumePolicy,
+    answer: Option<T>,
+    stream: Stream,
 }
 /// Renders a password input prompt.
 ///
@@ -64,9 +132,16 @@ pub struct Input<'a, T> {
 /// ```
 pub struct PasswordInput<'a> {
     prompt: String,
-    theme: &'a Theme,
+    theme: ThemeRef<'a>,
+    reporter: Option<ReportFn<'a>>,
     allow_empty_password: bool,
     confirmation_prompt: Option<(String, String)>,
+    max_invalid_attempts: Option<usize>,
+    timeout: Option<Duration>,
+    non_interactive: NonInteractive,
+    answer: Option<String>,
+    sources: Vec<SecretSource>,
+    stream: Stream,
 }
 
 impl<'a> Confirmation<'a> {
@@ -76,12 +151,30 @@ impl<'a> Confirmation<'a> {
     }
 
     /// Sets a theme other than the default one.
-    pub fn with_theme(theme: &'a Theme) -> Confirmation<'a> {
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Confirmation<'a> {
+        Confirmation::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    ///
+    /// The resulting prompt does not borrow anything and can be stored or
+    /// returned from functions freely.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Confirmation<'static> {
+        Confirmation::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Confirmation<'a> {
         Confirmation {
             text: "".into(),
             default: true,
             show_default: true,
             theme,
+            reporter: None,
+            timeout: None,
+            non_interactive: NonInteractive::Prompt,
+            assume: AssumePolicy::Interactive,
+            answer: None,
+            stream: Stream::Stderr,
         }
     }
 
@@ -91,12 +184,46 @@ impl<'a> Confirmation<'a> {
         self
     }
 
+    /// Sets a function that rewrites the answer shown on the report line.
+    ///
+    /// The function is given the answer as shown, e.g. `yes`.  Returning
+    /// `None` leaves the report line out.
+    pub fn with_report<F>(&mut self, f: F) -> &mut Confirmation<'a>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'a,
+    {
+        self.reporter = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Confirmation<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
     /// Overrides the default.
     pub fn default(&mut self, val: bool) -> &mut Confirmation<'a> {
         self.default = val;
         self
     }
 
+    /// Takes the default from an environment variable if it is set.
+    ///
+    /// `y`, `yes`, `true` and `1` mean yes, `n`, `no`, `false` and `0`
+    /// mean no.  Other values are ignored.
+    pub fn default_from_env(&mut self, var: &str) -> &mut Confirmation<'a> {
+        if let Some(val) = env::var(var).ok().and_then(|val| parse_bool(&val)) {
+            self.default = val;
+        }
+        self
+    }
+
     /// Disables or enables the default value display.
     ///
     /// The default is to append `[y/n]` to the prompt to tell the
@@ -107,32 +234,117 @@ impl<'a> Confirmation<'a> {
         self
     }
 
+    /// Sets a timeout after which the default is selected.
+    ///
+    /// The timeout restarts with every keypress.  Timeouts are currently
+    /// only supported on unix systems.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Confirmation<'a> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets what the prompt does if it is not connected to a terminal.
+    ///
+    /// The default is to prompt anyway.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut Confirmation<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Sets whether the prompt answers itself, e.g. for a `--yes` flag.
+    ///
+    /// The default is to ask.
+    pub fn assume(&mut self, policy: AssumePolicy) -> &mut Confirmation<'a> {
+        self.assume = policy;
+        self
+    }
+
+    /// Answers the prompt with `value` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, value: bool) -> &mut Confirmation<'a> {
+        self.answer = Some(value);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Confirmation<'a> {
+        self.stream = stream;
+        self
+    }
+
     /// Enables user interaction and returns the result.
     ///
     /// If the user confirms the result is `true`, `false` otherwise.
-    /// The dialog is rendered on stderr.
+    /// The dialog is rendered on stderr unless changed with `on`.
     pub fn interact(&self) -> io::Result<bool> {
-        self.interact_on(&Term::stderr())
+        self.interact_on(&self.stream.term())
     }
 
-    /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on(&self, term: &Term) -> io::Result<bool> {
-        let mut render = TermThemeRenderer::new(term, self.theme);
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    ///
+    /// The prompt runs on a thread of its own, so other tasks keep running
+    /// while the user is typing.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<bool>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Confirmation::new(), |prompt| prompt.interact())
+    }
 
-        render.confirmation_prompt(
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<bool> {
+        observe(
             &self.text,
-            if self.show_default {
-                Some(self.default)
-            } else {
-                None
+            || self.read_answer(term),
+            |&rv| {
+                let messages = messages();
+                Some(if rv {
+                    messages.yes.clone()
+                } else {
+                    messages.no.clone()
+                })
             },
-        )?;
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<bool> {
+        let _guard = TermGuard::new(term);
+        if let Some(answer) = self
+            .answer
+            .or_else(|| self.assume.confirmation(self.default))
+        {
+            let mut render = TermThemeRenderer::new(term, &*self.theme);
+            render.set_report(self.reporter.as_ref());
+            render.confirmation_prompt_selection(&self.text, answer)?;
+            return Ok(answer);
+        }
+        if let Some(rv) = self.non_interactive.answer(term, Some(self.default)) {
+            return rv;
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        render.set_report(self.reporter.as_ref());
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+
+        render.confirmation_prompt(&self.text, self.shown_default())?;
         loop {
-            let input = term.read_char()?;
+            if !wait_for_line_key(term, self.timeout, "")? {
+                term.clear_line()?;
+                render.confirmation_prompt_selection(&self.text, self.default)?;
+                return Ok(self.default);
+            }
+            let input = render.clear_interrupted(read_char(term))?;
+            let messages = messages();
             let rv = match input {
-                'y' | 'Y' => true,
-                'n' | 'N' => false,
                 '\n' | '\r' => self.default,
+                c if messages.is_yes_no_key(c, true) => true,
+                c if messages.is_yes_no_key(c, false) => false,
                 _ => {
                     continue;
                 }
@@ -142,6 +354,50 @@ impl<'a> Confirmation<'a> {
             return Ok(rv);
         }
     }
+
+    /// Reads the answer as a typed line for the accessible render mode.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<bool> {
+        loop {
+            render.confirmation_prompt(&self.text, self.shown_default())?;
+            if !wait_for_key(term, self.timeout)? {
+                term.write_line("")?;
+                render.confirmation_prompt_selection(&self.text, self.default)?;
+                return Ok(self.default);
+            }
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let messages = messages();
+            let rv = match messages.parse_yes_no(&input) {
+                Some(rv) => rv,
+                None if input.trim().is_empty() => self.default,
+                None => {
+                    render.error(&messages.answer_yes_no)?;
+                    continue;
+                }
+            };
+            render.confirmation_prompt_selection(&self.text, rv)?;
+            return Ok(rv);
+        }
+    }
+
+    fn shown_default(&self) -> Option<bool> {
+        if self.show_default {
+            Some(self.default)
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_bool(val: &str) -> Option<bool> {
+    match val.trim().to_lowercase().as_str() {
+        "y" | "yes" | "true" | "1" => Some(true),
+        "n" | "no" | "false" | "0" => Some(false),
+        _ => None,
+    }
 }
 
 impl<'a, T> Input<'a, T>
@@ -155,14 +411,45 @@ where
     }
 
     /// Creates an input with a specific theme.
-    pub fn with_theme(theme: &'a Theme) -> Input<'a, T> {
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Input<'a, T> {
+        Input::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    ///
+    /// The resulting prompt does not borrow anything and can be stored or
+    /// returned from functions freely.
+    pub fn with_owned_theme<TH: Theme + Send + Sync + 'static>(theme: TH) -> Input<'static, T> {
+        Input::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    pub(crate) fn with_theme_ref(theme: ThemeRef<'a>) -> Input<'a, T> {
         Input {
             prompt: "".into(),
             default: None,
+            remember_key: None,
             show_default: true,
             theme,
+            reporter: None,
             permit_empty: false,
+            post_process: None,
             validator: None,
+            #[cfg(feature = "tokio")]
+            async_validators: vec![],
+            parse_error_msg: None,
+            completer: None,
+            word_splitter: None,
+            confirm_default: false,
+            initial_text: None,
+            hide_input: false,
+            optional: false,
+            skip_key: Key::Escape,
+            max_invalid_attempts: None,
+            timeout: None,
+            non_interactive: NonInteractive::Prompt,
+            assume: AssumePolicy::Interactive,
+            answer: None,
+            stream: Stream::Stderr,
         }
     }
     /// Sets the input prompt.
@@ -171,6 +458,29 @@ where
         self
     }
 
+    /// Sets a function that rewrites the answer shown on the report line.
+    ///
+    /// This can e.g. mask a sensitive value or append a unit.  Returning
+    /// `None` leaves the report line out.
+    pub fn with_report<F>(&mut self, f: F) -> &mut Input<'a, T>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'a,
+    {
+        self.reporter = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Input<'a, T> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
     /// Sets a default.
     ///
     /// Out of the box the prompt does not have a default and will continue
@@ -180,6 +490,64 @@ where
         self.default = Some(value);
         self
     }
+
+    /// Takes the default from an environment variable if it is set.
+    ///
+    /// Values that do not parse into `T` are ignored.
+    pub fn default_from_env(&mut self, var: &str) -> &mut Input<'a, T> {
+        if let Some(val) = env::var(var).ok().and_then(|val| val.parse().ok()) {
+            self.default = Some(val);
+        }
+        self
+    }
+
+    /// Remembers the answer under a key.
+    ///
+    /// Later inputs with the same key take the answer given last as their
+    /// default, if it still parses.  Hidden inputs are never remembered.
+    pub fn remember_key(&mut self, key: &str) -> &mut Input<'a, T> {
+        self.remember_key = Some(key.into());
+        self
+    }
+
+    /// Returns the default, preferring the remembered answer.
+    fn default_value(&self) -> Option<T> {
+        self.remember_key
+            .as_ref()
+            .filter(|_| !self.hide_input)
+            .and_then(|key| state::recall(key))
+            .and_then(|value| value.parse().ok())
+            .or_else(|| self.default.clone())
+    }
+
+    /// Asks whether to use the default before reading any input.
+    ///
+    /// The default is shown with `[Y/n/e]`: yes takes it right away, no
+    /// reads a new value and `e` lets the user edit the default text.
+    pub fn confirm_default(&mut self, val: bool) -> &mut Input<'a, T> {
+        self.confirm_default = val;
+        self
+    }
+
+    /// Pre-fills the input with text the user can edit.
+    ///
+    /// Unlike a default the text is not shown next to the prompt but put
+    /// into the line being edited.
+    pub fn with_initial_text(&mut self, text: &str) -> &mut Input<'a, T> {
+        self.initial_text = Some(text.into());
+        self
+    }
+
+    /// Hides the typed text like a password input does.
+    ///
+    /// Unlike `PasswordInput` the input is still parsed into `T`, e.g. for
+    /// a numeric PIN.  Completion and initial text do not apply, and the
+    /// answer is reported as hidden.
+    pub fn hide_input(&mut self, val: bool) -> &mut Input<'a, T> {
+        self.hide_input = val;
+        self
+    }
+
     /// Enables or disables an empty input
     ///
     /// By default, if there is no default value set for the input, the user must input a non-empty string.
@@ -196,36 +564,302 @@ where
         self
     }
 
+    /// Sets a timeout for the user to type.
+    ///
+    /// The timeout restarts with every keypress.  When it elapses the
+    /// default is used, even if the user started typing.  If there is no
+    /// default an error of kind `TimedOut` is returned instead.  Timeouts
+    /// are currently only supported on unix systems.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Input<'a, T> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the message shown when the input cannot be parsed into `T`.
+    ///
+    /// The function gets the raw input and the parse error.  By default
+    /// the error itself is shown.
+    pub fn with_parse_error_msg<F>(&mut self, f: F) -> &mut Input<'a, T>
+    where
+        F: Fn(&str, &T::Err) -> String + Send + 'a,
+    {
+        self.parse_error_msg = Some(Box::new(f));
+        self
+    }
+
+    /// Gives up after the given number of rejected inputs.
+    ///
+    /// Inputs that fail validation or parsing count as rejected.  Once the
+    /// limit is reached `interact` fails with a `TooManyAttempts` error.
+    pub fn max_invalid_attempts(&mut self, n: usize) -> &mut Input<'a, T> {
+        self.max_invalid_attempts = Some(n);
+        self
+    }
+
+    /// Transforms the typed text before it is validated and parsed, e.g.
+    /// with `|s| s.trim().to_lowercase()`.
+    ///
+    /// The transformed text is what the report line shows.  Text that
+    /// becomes empty counts as empty input, so the default is used.
+    /// Calling this again applies the new function after the previous.
+    pub fn with_post_process<F>(&mut self, f: F) -> &mut Input<'a, T>
+    where
+        F: Fn(&str) -> String + Send + 'a,
+    {
+        self.post_process = Some(match self.post_process.take() {
+            Some(prev) => Box::new(move |input: &str| f(&prev(input))),
+            None => Box::new(f),
+        });
+        self
+    }
+
     /// Registers a validator.
-    pub fn validate_with<V: Validator + 'static>(&mut self, validator: V) -> &mut Input<'a, T> {
+    ///
+    /// Errors reject the input.  Warnings, e.g. from validators wrapped in
+    /// `Warn`, are shown without blocking: submitting the same input again
+    /// uses it.
+    pub fn validate_with<V: Validator + Send + 'a>(&mut self, validator: V) -> &mut Input<'a, T> {
         let old_validator_func = self.validator.take();
-        self.validator = Some(Box::new(move |value: &str| -> Option<String> {
-            if let Some(old) = old_validator_func.as_ref() {
-                if let Some(err) = old(value) {
-                    return Some(err);
-                }
-            }
-            match validator.validate(value) {
-                Ok(()) => None,
-                Err(err) => Some(err.to_string()),
+        self.validator = Some(Box::new(move |value: &str| -> Validation<String> {
+            let old = old_validator_func
+                .as_ref()
+                .map_or(Validation::Valid, |old| old(value));
+            match (old, validator.check(value)) {
+                (Validation::Error(err), _) => Validation::Error(err),
+                (_, Validation::Error(err)) => Validation::Error(err.to_string()),
+                (Validation::Warning(warning), _) => Validation::Warning(warning),
+                (_, Validation::Warning(warning)) => Validation::Warning(warning.to_string()),
+                _ => Validation::Valid,
             }
         }));
         self
     }
 
+    /// Registers a validator that runs asynchronously, e.g. to ask a server
+    /// whether a name is taken.
+    ///
+    /// It runs once the other validators accepted the input while a spinner
+    /// is shown on the prompt line.  Outside of a multi-threaded tokio
+    /// runtime the validator runs on a runtime of its own.
+    ///
+    /// ```rust,no_run,edition2018
+    /// # fn test() -> Result<(), Box<dyn std::error::Error>> {
+    /// use dialoguer::Input;
+    /// # async fn is_taken(name: &str) -> bool { false }
+    ///
+    /// let name = Input::<String>::new()
+    ///     .with_prompt("Username")
+    ///     .validate_async_with(|name: String| async move {
+    ///         if is_taken(&name).await {
+    ///             Err("this name is taken")
+    ///         } else {
+    ///             Ok(())
+    ///         }
+    ///     })
+    ///     .interact()?;
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn validate_async_with<F, Fut, E>(&mut self, validator: F) -> &mut Input<'a, T>
+    where
+        F: Fn(String) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.async_validators
+            .push(Box::new(move |value: String| -> ValidationFuture {
+                let mut fut = Box::pin(validator(value));
+                Box::pin(poll_fn(move |cx| {
+                    fut.as_mut()
+                        .poll(cx)
+                        .map(|rv| rv.map_err(|err| err.to_string()))
+                }))
+            }));
+        self
+    }
+
+    /// Registers a completer which fills in the input on tab.
+    ///
+    /// Submitted values are passed to the completer's `remember`.
+    pub fn with_completer<C: Completer + Send + 'a>(&mut self, completer: C) -> &mut Input<'a, T> {
+        self.completer = Some(Box::new(completer));
+        self
+    }
+
+    /// Sets which part of the input the completer completes.
+    ///
+    /// Without a splitter the whole input is completed.
+    pub fn with_word_splitter<S: WordSplitter + Send + 'a>(
+        &mut self,
+        splitter: S,
+    ) -> &mut Input<'a, T> {
+        self.word_splitter = Some(Box::new(splitter));
+        self
+    }
+
+    /// Sets what the prompt does if it is not connected to a terminal.
+    ///
+    /// The default is to prompt anyway.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut Input<'a, T> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Sets whether the input answers itself with its default, e.g. for a
+    /// `--yes` flag.
+    ///
+    /// The default is to ask.  An input without a default always asks.
+    pub fn assume(&mut self, policy: AssumePolicy) -> &mut Input<'a, T> {
+        self.assume = policy;
+        self
+    }
+
+    /// Answers the prompt with `value` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, value: T) -> &mut Input<'a, T> {
+        self.answer = Some(value);
+        self
+    }
+
+    /// Lets the user skip the prompt with Esc.
+    ///
+    /// A skipped prompt is reported as "(skipped)" and makes `interact_opt`
+    /// return `None`.  In the accessible mode an empty line skips the
+    /// prompt unless there is a default or empty input is allowed.
+    pub fn optional(&mut self, val: bool) -> &mut Input<'a, T> {
+        self.optional = val;
+        self
+    }
+
+    /// Sets the key that skips an optional prompt.
+    ///
+    /// The default is Esc.
+    pub fn with_skip_key(&mut self, key: Key) -> &mut Input<'a, T> {
+        self.skip_key = key;
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Input<'a, T> {
+        self.stream = stream;
+        self
+    }
+
     /// Enables user interaction and returns the result.
     ///
     /// If the user confirms the result is `true`, `false` otherwise.
-    /// The dialog is rendered on stderr.
+    /// The dialog is rendered on stderr unless changed with `on`.
     pub fn interact(&self) -> io::Result<T> {
-        self.interact_on(&Term::stderr())
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    ///
+    /// The prompt runs on a thread of its own, so other tasks keep running
+    /// while the user is typing.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<T>>
+    where
+        'a: 'static,
+        T: Send + 'static,
+    {
+        run_detached_mut(self, Input::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but returns `None` if the user skipped an optional
+    /// prompt.
+    pub fn interact_opt(&self) -> io::Result<Option<T>> {
+        self.interact_on_opt(&self.stream.term())
+    }
+
+    /// Like `interact_opt` but returns a future for use within a tokio
+    /// runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_opt_async(&mut self) -> PromptFuture<'_, io::Result<Option<T>>>
+    where
+        'a: 'static,
+        T: Send + 'static,
+    {
+        run_detached_mut(self, Input::new(), |prompt| prompt.interact_opt())
     }
 
     /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on(&self, term: &Term) -> io::Result<T> {
-        let mut render = TermThemeRenderer::new(term, self.theme);
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<T> {
+        self._interact_on(term, false)?
+            .ok_or_else(|| io::Error::other("Skip not allowed in this case"))
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<T>> {
+        self._interact_on(term, self.optional)
+    }
+
+    fn _interact_on(&self, term: &dyn Backend, allow_skip: bool) -> io::Result<Option<T>> {
+        let read = || {
+            let rv = self.read_answer(term, allow_skip)?;
+            if let (Some(key), Some(value), false) = (&self.remember_key, &rv, self.hide_input) {
+                state::remember(key, &value.to_string());
+            }
+            Ok(rv)
+        };
+        observe_secret(&self.prompt, self.hide_input, read, |rv| {
+            rv.as_ref()
+                .map(|value| self.shown(&value.to_string()).into_owned())
+        })
+    }
+
+    fn read_answer(&self, term: &dyn Backend, allow_skip: bool) -> io::Result<Option<T>> {
+        let _guard = TermGuard::new(term);
+        let default = self.default_value();
+        let answer = self
+            .answer
+            .clone()
+            .or_else(|| self.assume.selection(default.clone()));
+        if let Some(ref answer) = answer {
+            let mut render = TermThemeRenderer::new(term, &*self.theme);
+            render.set_report(self.reporter.as_ref());
+            render.single_prompt_selection(&self.prompt, &self.shown(&answer.to_string()))?;
+            return Ok(Some(answer.clone()));
+        }
+        if let Some(rv) = self.non_interactive.answer(term, default.clone()) {
+            return rv.map(Some);
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        render.set_report(self.reporter.as_ref());
+        let mut initial = self.initial_text.clone().unwrap_or_default();
+        if let (true, false, Some(value)) = (
+            self.confirm_default,
+            render.is_accessible(),
+            default.as_ref(),
+        ) {
+            let default = value.to_string();
+            render.default_confirmation(&self.prompt, &default)?;
+            let messages = messages();
+            loop {
+                match render.clear_interrupted(read_char(term))? {
+                    '\n' | '\r' => {}
+                    c if messages.is_yes_no_key(c, true) => {}
+                    c if messages.is_yes_no_key(c, false) => break,
+                    c if messages.is_edit_key(c) => {
+                        initial = default;
+                        break;
+                    }
+                    _ => continue,
+                }
+                term.clear_line()?;
+                render.single_prompt_selection(&self.prompt, &self.shown(&default))?;
+                return Ok(Some(value.clone()));
+            }
+            term.clear_line()?;
+        }
+        let mut attempts = 0;
+        let mut warned = None;
         loop {
-            let default_string = self.default.as_ref().map(|x| x.to_string());
+            let default_string = default.as_ref().map(|x| x.to_string());
             render.input_prompt(
                 &self.prompt,
                 if self.show_default {
@@ -234,36 +868,185 @@ where
                     None
                 },
             )?;
-            let input = term.read_line()?;
-            render.add_line();
+            if !wait_for_line_key(term, self.timeout, "")? {
+                term.write_line("")?;
+                return self.time_out(&mut render, default);
+            }
+            let completer = self
+                .completer
+                .as_ref()
+                .map(|completer| &**completer as &dyn Completer);
+            let skip = if allow_skip {
+                Some(&self.skip_key)
+            } else {
+                None
+            };
+            let input = if self.hide_input {
+                read_hidden_line(term, skip, self.timeout)
+            } else if render.is_accessible() {
+                read_plain_line(term).map(|input| {
+                    let skipped = input.is_empty() && default.is_none() && !self.permit_empty;
+                    if skip.is_some() && skipped {
+                        None
+                    } else {
+                        Some(input)
+                    }
+                })
+            } else if let (Some(completer), Some(splitter)) =
+                (completer, self.word_splitter.as_ref())
+            {
+                let completer = WordCompleter {
+                    completer,
+                    splitter: &**splitter,
+                };
+                read_edited_line(term, &initial, Some(&completer), skip, self.timeout)
+            } else {
+                read_edited_line(term, &initial, completer, skip, self.timeout)
+            };
+            initial.clear();
+            if input
+                .as_ref()
+                .is_err_and(|err| err.kind() == io::ErrorKind::TimedOut)
+            {
+                return self.time_out(&mut render, default);
+            }
+            let input = match render.clear_interrupted(input)? {
+                Some(input) => input,
+                None => {
+                    render.add_input_line("");
+                    render.clear()?;
+                    render.single_prompt_selection(&self.prompt, &messages().skipped)?;
+                    return Ok(None);
+                }
+            };
+            if self.hide_input {
+                render.add_line();
+            } else {
+                render.add_input_line(&input);
+            }
+            let input = match self.post_process {
+                Some(ref f) => f(&input),
+                None => input,
+            };
             if input.is_empty() {
                 render.clear()?;
-                if let Some(ref default) = self.default {
-                    render.single_prompt_selection(&self.prompt, &default.to_string())?;
-                    return Ok(default.clone());
+                if let Some(ref default) = default {
+                    let shown = default.to_string();
+                    render.single_prompt_selection(&self.prompt, &self.shown(&shown))?;
+                    return Ok(Some(default.clone()));
                 } else if !self.permit_empty {
                     continue;
                 }
             }
             render.clear()?;
             if let Some(ref validator) = self.validator {
-                if let Some(err) = validator(&input) {
+                match validator(&input) {
+                    Validation::Valid => {}
+                    Validation::Warning(_) if warned.as_ref() == Some(&input) => {}
+                    Validation::Warning(warning) => {
+                        let confirm = messages().confirm_warning.clone();
+                        render.warning(&format!("{} ({})", warning, confirm))?;
+                        initial = input.clone();
+                        warned = Some(input);
+                        continue;
+                    }
+                    Validation::Error(err) => {
+                        render.error(&err)?;
+                        count_attempt(&mut attempts, self.max_invalid_attempts)?;
+                        continue;
+                    }
+                }
+            }
+            #[cfg(feature = "tokio")]
+            {
+                if let Err(err) = self.validate_async(&mut render, &input)? {
                     render.error(&err)?;
+                    count_attempt(&mut attempts, self.max_invalid_attempts)?;
                     continue;
                 }
             }
             match input.parse::<T>() {
                 Ok(value) => {
-                    render.single_prompt_selection(&self.prompt, &input)?;
-                    return Ok(value);
+                    if let (Some(completer), false) = (self.completer.as_ref(), self.hide_input) {
+                        completer.remember(&input);
+                    }
+                    render.single_prompt_selection(&self.prompt, &self.shown(&input))?;
+                    return Ok(Some(value));
                 }
                 Err(err) => {
-                    render.error(&err.to_string())?;
+                    let msg = match self.parse_error_msg {
+                        Some(ref f) => f(&input, &err),
+                        None => err.to_string(),
+                    };
+                    render.error(&msg)?;
+                    count_attempt(&mut attempts, self.max_invalid_attempts)?;
                     continue;
                 }
             }
         }
     }
+
+    /// Answers with the default once the timeout elapsed, or fails if
+    /// there is none.
+    fn time_out(
+        &self,
+        render: &mut TermThemeRenderer,
+        default: Option<T>,
+    ) -> io::Result<Option<T>> {
+        render.add_line();
+        render.clear()?;
+        match default {
+            Some(default) => {
+                let shown = default.to_string();
+                render.single_prompt_selection(&self.prompt, &self.shown(&shown))?;
+                Ok(Some(default))
+            }
+            None => Err(timed_out()),
+        }
+    }
+
+    /// Returns the text the answer is reported as.
+    fn shown<'s>(&self, answer: &'s str) -> Cow<'s, str> {
+        if self.hide_input {
+            Cow::Owned(messages().hidden.clone())
+        } else {
+            Cow::Borrowed(answer)
+        }
+    }
+
+    /// Runs the asynchronous validators while a spinner is shown on the
+    /// prompt line.
+    #[cfg(feature = "tokio")]
+    fn validate_async(
+        &self,
+        render: &mut TermThemeRenderer,
+        input: &str,
+    ) -> io::Result<Result<(), String>> {
+        let message = format!("{}: {}", self.prompt, self.shown(input));
+        for validator in &self.async_validators {
+            let rx = spawn_future(validator(input.to_string()));
+            let mut tick = 0;
+            let rv = loop {
+                if !render.is_accessible() {
+                    render.begin_frame();
+                    render.spinner(&message, tick)?;
+                    render.end_frame()?;
+                }
+                match rx.recv_timeout(SPINNER_INTERVAL) {
+                    Ok(rv) => break Ok(rv),
+                    Err(RecvTimeoutError::Timeout) => tick += 1,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        break Err(io::Error::other("Validator did not complete"))
+                    }
+                }
+            };
+            render.clear()?;
+            if let Err(err) = rv? {
+                return Ok(Err(err));
+            }
+        }
+        Ok(Ok(()))
+    }
 }
 
 impl<'a> PasswordInput<'a> {
@@ -273,12 +1056,31 @@ impl<'a> PasswordInput<'a> {
     }
 
     /// Creates the password input with a specific theme.
-    pub fn with_theme(theme: &'a Theme) -> PasswordInput<'a> {
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> PasswordInput<'a> {
+        PasswordInput::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    ///
+    /// The resulting prompt does not borrow anything and can be stored or
+    /// returned from functions freely.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> PasswordInput<'static> {
+        PasswordInput::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> PasswordInput<'a> {
         PasswordInput {
             prompt: "".into(),
-            theme: theme,
+            theme,
+            reporter: None,
             allow_empty_password: false,
             confirmation_prompt: None,
+            max_invalid_attempts: None,
+            timeout: None,
+            non_interactive: NonInteractive::Prompt,
+            answer: None,
+            sources: vec![],
+            stream: Stream::Stderr,
         }
     }
 
@@ -288,6 +1090,30 @@ impl<'a> PasswordInput<'a> {
         self
     }
 
+    /// Sets a function that rewrites the report line.
+    ///
+    /// The function is given the placeholder shown in place of the
+    /// password, e.g. `[hidden]`.  Returning `None` leaves the report line
+    /// out.
+    pub fn with_report<F>(&mut self, f: F) -> &mut PasswordInput<'a>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'a,
+    {
+        self.reporter = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut PasswordInput<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
     /// Enables confirmation prompting.
     pub fn with_confirmation(
         &mut self,
@@ -298,6 +1124,15 @@ impl<'a> PasswordInput<'a> {
         self
     }
 
+    /// Gives up after the given number of mismatched confirmations.
+    ///
+    /// Once the limit is reached `interact` fails with a `TooManyAttempts`
+    /// error.
+    pub fn max_invalid_attempts(&mut self, n: usize) -> &mut PasswordInput<'a> {
+        self.max_invalid_attempts = Some(n);
+        self
+    }
+
     /// Allows/Disables empty password.
     ///
     /// By default this setting is set to false (i.e. password is not empty).
@@ -306,18 +1141,128 @@ impl<'a> PasswordInput<'a> {
         self
     }
 
+    /// Sets a timeout for the user to type.
+    ///
+    /// The timeout restarts with every keypress.  When it elapses an
+    /// error of kind `TimedOut` is returned.  Timeouts are currently only
+    /// supported on unix systems.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut PasswordInput<'a> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets what the prompt does if it is not connected to a terminal.
+    ///
+    /// The default is to prompt anyway.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut PasswordInput<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Answers the prompt with `password` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, password: &str) -> &mut PasswordInput<'a> {
+        self.answer = Some(password.into());
+        self
+    }
+
+    /// Looks for the password in `source` before asking the user.
+    ///
+    /// Sources are tried in the order they were added and the first one
+    /// with a password answers the prompt, even without a terminal.
+    pub fn with_source(&mut self, source: SecretSource) -> &mut PasswordInput<'a> {
+        self.sources.push(source);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut PasswordInput<'a> {
+        self.stream = stream;
+        self
+    }
+
     /// Enables user interaction and returns the result.
     ///
     /// If the user confirms the result is `true`, `false` otherwise.
-    /// The dialog is rendered on stderr.
+    /// The dialog is rendered on stderr unless changed with `on`.
     pub fn interact(&self) -> io::Result<String> {
-        self.interact_on(&Term::stderr())
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    ///
+    /// The prompt runs on a thread of its own, so other tasks keep running
+    /// while the user is typing.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<String>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, PasswordInput::new(), |prompt| prompt.interact())
     }
 
     /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on(&self, term: &Term) -> io::Result<String> {
-        let mut render = TermThemeRenderer::new(term, self.theme);
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<String> {
+        self.interact_with_source_on(term)
+            .map(|(password, _)| password)
+    }
+
+    /// Like `interact` but also returns the source the password came from,
+    /// or `None` if the user typed it.
+    pub fn interact_with_source(&self) -> io::Result<(String, Option<&SecretSource>)> {
+        self.interact_with_source_on(&self.stream.term())
+    }
+
+    /// Like `interact_with_source` but allows a specific terminal to be set.
+    pub fn interact_with_source_on(
+        &self,
+        term: &dyn Backend,
+    ) -> io::Result<(String, Option<&SecretSource>)> {
+        let read = || self.read_sourced(term);
+        observe_secret(&self.prompt, true, read, |_| {
+            Some(messages().hidden.clone())
+        })
+    }
+
+    fn read_sourced(&self, term: &dyn Backend) -> io::Result<(String, Option<&SecretSource>)> {
+        if self.answer.is_none() {
+            for source in &self.sources {
+                if let Some(password) = source.read() {
+                    let _guard = TermGuard::new(term);
+                    let messages = messages();
+                    let report = fill(
+                        &messages.secret_from,
+                        &[("hidden", &messages.hidden), ("source", source)],
+                    );
+                    let mut render = TermThemeRenderer::new(term, &*self.theme);
+                    render.set_report(self.reporter.as_ref());
+                    render.single_prompt_selection(&self.prompt, &report)?;
+                    return Ok((password, Some(source)));
+                }
+            }
+        }
+        self.read_answer(term).map(|password| (password, None))
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<String> {
+        let _guard = TermGuard::new(term);
+        if let Some(ref answer) = self.answer {
+            let mut render = TermThemeRenderer::new(term, &*self.theme);
+            render.set_report(self.reporter.as_ref());
+            render.password_prompt_selection(&self.prompt)?;
+            return Ok(answer.clone());
+        }
+        if let Some(rv) = self.non_interactive.answer(term, None) {
+            return rv;
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        render.set_report(self.reporter.as_ref());
         render.set_prompts_reset_height(false);
+        let mut attempts = 0;
         loop {
             let password = self.prompt_password(&mut render, &self.prompt)?;
             if let Some((ref prompt, ref err)) = self.confirmation_prompt {
@@ -328,6 +1273,7 @@ impl<'a> PasswordInput<'a> {
                     return Ok(password);
                 }
                 render.error(err)?;
+                count_attempt(&mut attempts, self.max_invalid_attempts)?;
             } else {
                 render.clear()?;
                 render.password_prompt_selection(&self.prompt)?;
@@ -339,7 +1285,16 @@ impl<'a> PasswordInput<'a> {
     fn prompt_password(&self, render: &mut TermThemeRenderer, prompt: &str) -> io::Result<String> {
         loop {
             render.password_prompt(prompt)?;
-            let input = render.term().read_secure_line()?;
+            let input = read_secure_line(render.term(), self.timeout);
+            if input
+                .as_ref()
+                .is_err_and(|err| err.kind() == io::ErrorKind::TimedOut)
+            {
+                render.add_line();
+                render.clear()?;
+                return Err(timed_out());
+            }
+            let input = render.clear_interrupted(input)?;
             render.add_line();
             if !input.is_empty() || self.allow_empty_password {
                 return Ok(input);
@@ -347,3 +1302,309 @@ impl<'a> PasswordInput<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fmt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use theme::ColorfulTheme;
+    use virtual_term::VirtualTerm;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    /// A theme that counts the prompts it renders.
+    struct CountingTheme {
+        prompts: AtomicUsize,
+    }
+
+    impl Theme for CountingTheme {
+        fn format_singleline_prompt(
+            &self,
+            f: &mut dyn fmt::Write,
+            prompt: &str,
+            _default: Option<&str>,
+        ) -> fmt::Result {
+            self.prompts.fetch_add(1, Ordering::SeqCst);
+            write!(f, "{}: ", prompt)
+        }
+    }
+
+    #[test]
+    fn test_builders_are_send() {
+        assert_send(&Confirmation::new());
+        assert_send(&Confirmation::with_owned_theme(ColorfulTheme::default()));
+        assert_send(&Input::<String>::new());
+        assert_send(&Input::<String>::with_owned_theme(ColorfulTheme::default()));
+        assert_send(&PasswordInput::new());
+        assert_send(&PasswordInput::with_owned_theme(ColorfulTheme::default()));
+    }
+
+    #[test]
+    fn test_borrowed_theme_and_validator() {
+        let theme = CountingTheme {
+            prompts: AtomicUsize::new(0),
+        };
+        let seen = Mutex::new(vec![]);
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("x\nxy\n");
+        let mut input = Input::<String>::with_theme(&theme);
+        input.with_prompt("Name").validate_with(|text: &str| {
+            seen.lock().unwrap().push(text.to_string());
+            if text.len() < 2 {
+                Err("too short")
+            } else {
+                Ok(())
+            }
+        });
+        assert_send(&input);
+        let name = input.interact_on(&term).unwrap();
+        assert_eq!(name, "xy");
+        assert_eq!(*seen.lock().unwrap(), vec!["x", "xy"]);
+        assert_eq!(theme.prompts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_confirmation_report() {
+        let term = VirtualTerm::new(10, 40);
+        let answer = Confirmation::new()
+            .with_text("Deploy?")
+            .with_answer(true)
+            .with_report(|answer| Some(answer.to_string()))
+            .interact_on(&term)
+            .unwrap();
+        assert!(answer);
+        assert_eq!(term.screen(), "Deploy? yes");
+        let term = VirtualTerm::new(10, 40);
+        Confirmation::new()
+            .with_text("Deploy?")
+            .with_answer(false)
+            .with_report(|answer| Some(answer.to_uppercase()))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(term.screen(), "Deploy? NO");
+        let term = VirtualTerm::new(10, 40);
+        Confirmation::with_owned_theme(ColorfulTheme::plain())
+            .with_text("Deploy?")
+            .with_answer(false)
+            .with_report(|answer| Some(answer.to_uppercase()))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(term.screen(), "✔ Deploy? · NO");
+    }
+
+    #[test]
+    fn test_input_remember_key() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("8443\n");
+        let port = Input::<u16>::new()
+            .with_prompt("Port")
+            .default(80)
+            .remember_key("test-port")
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(port, 8443);
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("\n");
+        let port = Input::<u16>::new()
+            .with_prompt("Port")
+            .default(80)
+            .remember_key("test-port")
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(port, 8443);
+        assert_eq!(term.screen(), "Port: 8443");
+    }
+
+    #[test]
+    fn test_timeout_while_typing() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("ad").pause();
+        let name = Input::<String>::new()
+            .with_prompt("Name")
+            .default("guest".into())
+            .with_timeout(Duration::from_millis(10))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(name, "guest");
+        assert_eq!(term.screen(), "Name: guest");
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("hun").pause().text("ter2\n");
+        let err = PasswordInput::new()
+            .with_prompt("Password")
+            .with_timeout(Duration::from_millis(10))
+            .interact_on(&term)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(term.screen(), "");
+    }
+
+    #[test]
+    fn test_confirm_default() {
+        let input = || {
+            let mut input = Input::<String>::new();
+            input
+                .with_prompt("Name")
+                .default("guest".into())
+                .confirm_default(true);
+            input
+        };
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("y");
+        assert_eq!(input().interact_on(&term).unwrap(), "guest");
+        assert_eq!(term.frames(), vec!["Name [guest] use this? [Y/n/e]"]);
+        assert_eq!(term.screen(), "Name: guest");
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("e").keys(&[Key::Backspace]).text("d\n");
+        assert_eq!(input().interact_on(&term).unwrap(), "guesd");
+        assert_eq!(term.frames()[1], "Name [guest]: guest");
+        assert_eq!(term.screen(), "Name: guesd");
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("nroot\n");
+        assert_eq!(input().interact_on(&term).unwrap(), "root");
+        assert_eq!(term.frames()[1], "Name [guest]:");
+        assert_eq!(term.screen(), "Name: root");
+    }
+
+    #[test]
+    fn test_parse_error_msg() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("ten\n10\n");
+        let count = Input::<u32>::new()
+            .with_prompt("Count")
+            .with_parse_error_msg(|raw, _| format!("{} is not a whole number", raw))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(count, 10);
+        assert_eq!(term.frames()[4], "error: ten is not a whole number\nCount:");
+        assert_eq!(term.screen(), "Count: 10");
+    }
+
+    #[test]
+    fn test_optional_input() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("ab").keys(&[Key::Escape]);
+        let tag = Input::<String>::new()
+            .with_prompt("Tag")
+            .optional(true)
+            .interact_on_opt(&term)
+            .unwrap();
+        assert_eq!(tag, None);
+        assert_eq!(term.frames(), vec!["Tag:", "Tag: a", "Tag: ab"]);
+        assert_eq!(term.screen(), "Tag: (skipped)");
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[Key::Tab]);
+        let tag = Input::<String>::new()
+            .with_prompt("Tag")
+            .optional(true)
+            .with_skip_key(Key::Tab)
+            .interact_on_opt(&term)
+            .unwrap();
+        assert_eq!(tag, None);
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[Key::Escape]).text("v1\n");
+        let tag = Input::<String>::new()
+            .with_prompt("Tag")
+            .optional(true)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(tag, "v1");
+        assert_eq!(term.screen(), "Tag: v1");
+    }
+
+    #[test]
+    fn test_hidden_input() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("12a\n1234\n");
+        let pin = Input::<u16>::new()
+            .with_prompt("PIN")
+            .hide_input(true)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(pin, 1234);
+        assert!(term.frames().iter().all(|frame| !frame.contains('1')));
+        assert_eq!(
+            term.frames()[4],
+            "error: invalid digit found in string\nPIN:"
+        );
+        assert_eq!(term.screen(), "PIN: [hidden]");
+    }
+
+    #[test]
+    fn test_post_process() {
+        let seen = Mutex::new(vec![]);
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("  EU-West \n");
+        let region = Input::<String>::new()
+            .with_prompt("Region")
+            .with_post_process(|text| text.trim().to_string())
+            .with_post_process(|text| text.to_lowercase())
+            .validate_with(|text: &str| -> Result<(), &str> {
+                seen.lock().unwrap().push(text.to_string());
+                Ok(())
+            })
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(region, "eu-west");
+        assert_eq!(*seen.lock().unwrap(), vec!["eu-west"]);
+        assert_eq!(term.frames().last().unwrap(), "Region:   EU-West");
+        assert_eq!(term.screen(), "Region: eu-west");
+
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("   \n");
+        let region = Input::<String>::new()
+            .with_prompt("Region")
+            .default("us-east".into())
+            .with_post_process(|text| text.trim().to_string())
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(region, "us-east");
+        assert_eq!(term.screen(), "Region: us-east");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_async_validator_on_every_runtime() {
+        use std::task::Poll;
+
+        let username = |term: &VirtualTerm| {
+            Input::<String>::new()
+                .with_prompt("Username")
+                .validate_async_with(|name: String| {
+                    let taken = name == "root";
+                    std::future::ready(if taken {
+                        Err("this name is taken")
+                    } else {
+                        Ok(())
+                    })
+                })
+                .interact_on(term)
+        };
+        let mut term = VirtualTerm::new(10, 40);
+        term.text("root\nalice\n");
+        assert_eq!(username(&term).unwrap(), "alice");
+        assert_eq!(term.screen(), "Username: alice");
+
+        let runtimes = vec![
+            tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap(),
+            tokio::runtime::Builder::new_multi_thread().build().unwrap(),
+        ];
+        for rt in runtimes {
+            let mut term = VirtualTerm::new(10, 40);
+            term.text("root\nbob\n");
+            let name = rt.block_on(poll_fn(|_| Poll::Ready(username(&term))));
+            assert_eq!(name.unwrap(), "bob");
+            assert!(term.frames()[5].starts_with("error: this name is taken"));
+        }
+    }
+}
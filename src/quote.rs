@@ -0,0 +1,129 @@
+//! Quoting of paths and other words for shell commands.
+//!
+//! Answers such as paths often end up pasted into commands.  The
+//! functions of this module quote a word so that the shell passes it on
+//! unchanged, and leave words that need no quoting as they are:
+//!
+//! ```rust
+//! use dialoguer::quote::{quote, Shell};
+//!
+//! assert_eq!(quote("src/main.rs", Shell::Sh), "src/main.rs");
+//! assert_eq!(quote("My Files/it's.txt", Shell::Sh), r"'My Files/it'\''s.txt'");
+//! assert_eq!(quote("My Files/it's.txt", Shell::PowerShell), "'My Files/it''s.txt'");
+//! ```
+use std::borrow::Cow;
+use std::env;
+use std::path::Path;
+
+/// The shells words can be quoted for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// POSIX sh and compatible shells such as bash and zsh.
+    Sh,
+    /// The fish shell.
+    Fish,
+    /// Windows PowerShell and PowerShell Core.
+    PowerShell,
+}
+
+impl Shell {
+    /// Returns the shell of the user.
+    ///
+    /// This is fish if `SHELL` names it, PowerShell on Windows and sh
+    /// otherwise.
+    pub fn detect() -> Shell {
+        let shell = env::var_os("SHELL");
+        let name = shell
+            .as_ref()
+            .and_then(|shell| Path::new(shell).file_name())
+            .and_then(|name| name.to_str());
+        match name {
+            Some("fish") => Shell::Fish,
+            Some("pwsh") | Some("powershell") => Shell::PowerShell,
+            Some(_) => Shell::Sh,
+            None if cfg!(windows) => Shell::PowerShell,
+            None => Shell::Sh,
+        }
+    }
+}
+
+/// Quotes a word for `shell`.
+pub fn quote(word: &str, shell: Shell) -> Cow<'_, str> {
+    match shell {
+        Shell::Sh => sh(word),
+        Shell::Fish => fish(word),
+        Shell::PowerShell => powershell(word),
+    }
+}
+
+/// Quotes a word for POSIX sh.
+///
+/// The word is put in single quotes, within which only the single quote
+/// itself has to be written as `'\''`.
+pub fn sh(word: &str) -> Cow<'_, str> {
+    if is_plain(word, "-_./,:=@%+") {
+        return Cow::Borrowed(word);
+    }
+    Cow::Owned(format!("'{}'", word.replace('\'', r"'\''")))
+}
+
+/// Quotes a word for fish.
+///
+/// Unlike sh, fish takes backslash escapes of `\` and `'` within single
+/// quotes.
+pub fn fish(word: &str) -> Cow<'_, str> {
+    if is_plain(word, "-_./,:=@%+") {
+        return Cow::Borrowed(word);
+    }
+    let escaped = word.replace('\\', r"\\").replace('\'', r"\'");
+    Cow::Owned(format!("'{}'", escaped))
+}
+
+/// Quotes a word for PowerShell.
+///
+/// Within single quotes PowerShell takes a quote written twice as one,
+/// which includes the typographic single quotes it also accepts.
+pub fn powershell(word: &str) -> Cow<'_, str> {
+    if is_plain(word, r"-_./\:") {
+        return Cow::Borrowed(word);
+    }
+    let mut quoted = String::from("'");
+    for c in word.chars() {
+        if let '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' = c {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    Cow::Owned(quoted)
+}
+
+/// Returns `true` if a word consists of ASCII letters, digits and
+/// `extra` only, and so needs no quoting.
+fn is_plain(word: &str, extra: &str) -> bool {
+    !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        for &shell in &[Shell::Sh, Shell::Fish, Shell::PowerShell] {
+            assert_eq!(quote("notes-2024.txt", shell), "notes-2024.txt");
+            assert_eq!(quote("", shell), "''");
+            assert_eq!(quote("a b", shell), "'a b'");
+        }
+        assert_eq!(sh("$HOME/*"), "'$HOME/*'");
+        assert_eq!(sh(r"C:\it's"), r"'C:\it'\''s'");
+        assert_eq!(fish(r"C:\it's"), r"'C:\\it\'s'");
+        assert_eq!(powershell(r"C:\it's"), r"'C:\it''s'");
+        assert_eq!(powershell(r"C:\Temp"), r"C:\Temp");
+        assert_eq!(powershell("it\u{2019}s"), "'it\u{2019}\u{2019}s'");
+        assert_eq!(sh("~/x"), "'~/x'");
+    }
+}
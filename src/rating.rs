@@ -0,0 +1,281 @@
+use std::io;
+use std::sync::Arc;
+
+use console::{Key, Style};
+
+use backend::{Backend, HiddenCursor, TermGuard};
+use events::{read_key, read_plain_line, wait_for_event, Event};
+use messages::{fill, messages};
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders a rating scale of symbols, e.g. ★★★☆☆.
+///
+/// The rating is changed with the left and right arrow keys (or `h` and
+/// `l`) or picked directly with the number keys, and confirmed with enter.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::Rating;
+///
+/// let stars = Rating::new().with_prompt("How was your experience?").interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Rating<'a> {
+    prompt: String,
+    max: u8,
+    default: u8,
+    answer: Option<u8>,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a> Rating<'a> {
+    /// Creates a new rating prompt with five symbols.
+    pub fn new() -> Rating<'static> {
+        Rating::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Rating<'a> {
+        Rating::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Rating<'static> {
+        Rating::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Rating<'a> {
+        Rating {
+            prompt: "".into(),
+            max: 5,
+            default: 0,
+            answer: None,
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut Rating<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Rating<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the filled stars for this rating alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut Rating<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            values_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the number of symbols and thus the highest rating.
+    ///
+    /// The default is 5.
+    pub fn max(&mut self, val: u8) -> &mut Rating<'a> {
+        self.max = val.max(1);
+        self
+    }
+
+    /// Sets the rating shown initially.
+    ///
+    /// By default no symbol is filled and enter is ignored until the user
+    /// picked a rating.
+    pub fn default(&mut self, val: u8) -> &mut Rating<'a> {
+        self.default = val;
+        self
+    }
+
+    /// Answers the prompt with `value` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    ///
+    /// Values above `max` are lowered to it.
+    pub fn with_answer(&mut self, value: u8) -> &mut Rating<'a> {
+        self.answer = Some(value);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Rating<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the rating, starting at 1.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<u8> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<u8>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Rating::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<u8> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term),
+            |value| Some(value.to_string()),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<u8> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if let Some(value) = self.answer {
+            let value = value.min(self.max);
+            self.report(&mut render, value)?;
+            return Ok(value);
+        }
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+        let _cursor = HiddenCursor::new(term)?;
+        let mut value = self.default.min(self.max);
+        loop {
+            render.begin_frame();
+            render.rating(&self.prompt, value, self.max)?;
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                continue;
+            }
+            match render.clear_interrupted(read_key(term))? {
+                Key::ArrowLeft | Key::Char('h') => value = value.saturating_sub(1).max(1),
+                Key::ArrowRight | Key::Char('l') => value = (value + 1).min(self.max),
+                Key::Char(c) => {
+                    if let Some(digit) = c.to_digit(10) {
+                        if digit >= 1 && digit <= u32::from(self.max) {
+                            value = digit as u8;
+                        }
+                    }
+                }
+                Key::Enter if value > 0 => {
+                    render.clear()?;
+                    self.report(&mut render, value)?;
+                    return Ok(value);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the rating as a typed number for the accessible render mode.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<u8> {
+        let prompt = format!("{} (1-{})", self.prompt, self.max);
+        let default = if self.default > 0 {
+            Some(self.default.min(self.max).to_string())
+        } else {
+            None
+        };
+        loop {
+            render.input_prompt(&prompt, default.as_deref())?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let input = input.trim();
+            let value = if input.is_empty() && self.default > 0 {
+                self.default.min(self.max)
+            } else {
+                match input.parse::<u8>() {
+                    Ok(value) if value >= 1 && value <= self.max => value,
+                    _ => {
+                        render.error(&fill(&messages().number_range, &[("max", &self.max)]))?;
+                        continue;
+                    }
+                }
+            };
+            self.report(render, value)?;
+            return Ok(value);
+        }
+    }
+
+    fn report(&self, render: &mut TermThemeRenderer, value: u8) -> io::Result<()> {
+        render.single_prompt_selection(&self.prompt, &format!("{}/{}", value, self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_rating_keys() {
+        let mut term = VirtualTerm::new(5, 60);
+        term.keys(&[Key::Enter, Key::ArrowRight, Key::ArrowRight, Key::Char('9')]);
+        term.keys(&[Key::Char('4'), Key::ArrowLeft, Key::Enter]);
+        let rating = Rating::new()
+            .with_prompt("Rate")
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(rating, 3);
+        assert_eq!(
+            term.frames(),
+            vec![
+                "Rate: .....",
+                "Rate: .....",
+                "Rate: *....",
+                "Rate: **...",
+                "Rate: **...",
+                "Rate: ****.",
+                "Rate: ***..",
+            ]
+        );
+        assert_eq!(term.screen(), "Rate: 3/5");
+
+        let mut term = VirtualTerm::new(5, 60);
+        term.keys(&[Key::Char('l'), Key::Char('l'), Key::Char('h'), Key::Enter]);
+        let rating = Rating::new()
+            .with_prompt("Rate")
+            .max(3)
+            .default(2)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(rating, 2);
+        assert_eq!(term.frames()[2], "Rate: ***");
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(Rating::new());
+        assert_send(Rating::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,551 @@
+//! Recording interactive sessions and replaying them.
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use console::Key;
+
+use backend::Backend;
+use observer::PromptObserver;
+
+/// A key press or an answer of a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Key(Duration, Key),
+    Answer(String, String),
+}
+
+/// The key presses and answers of an interactive session.
+///
+/// Sessions are saved as text with one key press or answer per line, so
+/// they can be checked in and diffed, e.g. as golden files of tests:
+///
+/// ```text
+/// key 850 down
+/// key 320 enter
+/// ```
+///
+/// Each key press carries the milliseconds since the previous one.  An
+/// answer is saved as `answer`, the prompt, a tab and the answer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    entries: Vec<Entry>,
+}
+
+impl Session {
+    /// Creates an empty session.
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    /// Loads a session saved with `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Session> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Saves the session to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Returns the recorded key presses.
+    pub fn keys(&self) -> Vec<Key> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match *entry {
+                Entry::Key(_, ref key) => Some(key.clone()),
+                Entry::Answer(..) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the recorded answers as pairs of prompt and answer.
+    pub fn answers(&self) -> Vec<(&str, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match *entry {
+                Entry::Answer(ref prompt, ref answer) => Some((prompt.as_str(), answer.as_str())),
+                Entry::Key(..) => None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            match *entry {
+                Entry::Key(delay, ref key) => {
+                    writeln!(f, "key {} {}", delay.as_millis(), format_key(key))?
+                }
+                Entry::Answer(ref prompt, ref answer) => {
+                    writeln!(f, "answer {}\t{}", escape(prompt), escape(answer))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Session {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Session> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid session line: {}", line),
+            )
+        };
+        let mut entries = vec![];
+        for line in s.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = if let Some(rest) = line.strip_prefix("key ") {
+                let mut parts = rest.splitn(2, ' ');
+                let delay = parts.next().and_then(|delay| delay.parse().ok());
+                let key = parts.next().and_then(parse_key);
+                match (delay, key) {
+                    (Some(delay), Some(key)) => Entry::Key(Duration::from_millis(delay), key),
+                    _ => return Err(invalid(line)),
+                }
+            } else if let Some(rest) = line.strip_prefix("answer ") {
+                let mut parts = rest.splitn(2, '\t');
+                match (parts.next(), parts.next()) {
+                    (Some(prompt), Some(answer)) => {
+                        Entry::Answer(unescape(prompt), unescape(answer))
+                    }
+                    _ => return Err(invalid(line)),
+                }
+            } else {
+                return Err(invalid(line));
+            };
+            entries.push(entry);
+        }
+        Ok(Session { entries })
+    }
+}
+
+/// Records the key presses read through a backend.
+///
+/// The recorder passes everything on to the backend it wraps.  To record
+/// the answers as well, set its `observer` with `set_observer`.
+///
+/// ```rust,no_run
+/// # extern crate console;
+/// # extern crate dialoguer;
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use console::Term;
+/// use dialoguer::{set_observer, Recorder, Select};
+///
+/// let recorder = Recorder::new(Term::stderr());
+/// set_observer(Some(recorder.observer()));
+/// Select::new()
+///     .with_prompt("Region")
+///     .items(&["eu-west", "us-east"])
+///     .interact_on(&recorder)?;
+/// recorder.session().save("session.txt")?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+///
+/// Prompts on a terminal that is not interactive read lines from stdin
+/// directly; those are not recorded.
+pub struct Recorder<B: Backend> {
+    term: B,
+    entries: Arc<Mutex<Vec<Entry>>>,
+    last: Cell<Instant>,
+}
+
+impl<B: Backend> Recorder<B> {
+    /// Creates a recorder reading keys from `term`.
+    pub fn new(term: B) -> Recorder<B> {
+        Recorder {
+            term,
+            entries: Arc::default(),
+            last: Cell::new(Instant::now()),
+        }
+    }
+
+    /// Returns an observer that records the answers of prompts.
+    pub fn observer(&self) -> Box<dyn PromptObserver> {
+        Box::new(AnswerRecorder {
+            entries: self.entries.clone(),
+        })
+    }
+
+    /// Returns the session recorded so far.
+    pub fn session(&self) -> Session {
+        Session {
+            entries: self
+                .entries
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clone(),
+        }
+    }
+
+    /// Returns the backend the recorder wraps.
+    pub fn into_inner(self) -> B {
+        self.term
+    }
+}
+
+impl<B: Backend> Backend for Recorder<B> {
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.term.write_str(s)
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        let key = self.term.read_key()?;
+        let now = Instant::now();
+        let delay = (now - self.last.replace(now)).as_millis() as u64;
+        self.entries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Entry::Key(Duration::from_millis(delay), key.clone()));
+        Ok(key)
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.term.size()
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.term.flush()
+    }
+
+    fn is_term(&self) -> bool {
+        self.term.is_term()
+    }
+
+    fn colors_supported(&self) -> bool {
+        self.term.colors_supported()
+    }
+
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        self.term.poll_key(timeout)
+    }
+}
+
+struct AnswerRecorder {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl PromptObserver for AnswerRecorder {
+    fn on_submit(&self, prompt: &str, answer: &str, _elapsed: Duration) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(Entry::Answer(prompt.into(), answer.into()));
+    }
+}
+
+/// Feeds the key presses of a recorded session to prompts.
+///
+/// Output goes to the backend the replay wraps, e.g. a `console::Term`
+/// for a demo.  By default the keys are fed instantly; with `realtime`
+/// they arrive with the delays they were recorded with.  Once all keys
+/// are used up reading fails with `UnexpectedEof`.
+///
+/// ```rust,no_run
+/// # extern crate console;
+/// # extern crate dialoguer;
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use console::Term;
+/// use dialoguer::{Replay, Select, Session};
+///
+/// let mut replay = Replay::new(Term::stderr(), Session::load("session.txt")?);
+/// replay.realtime(true);
+/// Select::new()
+///     .with_prompt("Region")
+///     .items(&["eu-west", "us-east"])
+///     .interact_on(&replay)?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Replay<B: Backend> {
+    term: B,
+    keys: RefCell<VecDeque<(Duration, Key)>>,
+    realtime: bool,
+    due: Cell<Option<Instant>>,
+}
+
+impl<B: Backend> Replay<B> {
+    /// Creates a replay of `session` rendering to `term`.
+    pub fn new(term: B, session: Session) -> Replay<B> {
+        let keys = session
+            .entries
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Entry::Key(delay, key) => Some((delay, key)),
+                Entry::Answer(..) => None,
+            })
+            .collect();
+        Replay {
+            term,
+            keys: RefCell::new(keys),
+            realtime: false,
+            due: Cell::new(None),
+        }
+    }
+
+    /// Sets whether keys arrive with their recorded delays.
+    pub fn realtime(&mut self, realtime: bool) -> &mut Replay<B> {
+        self.realtime = realtime;
+        self
+    }
+
+    /// Returns the number of keys not fed yet.
+    pub fn remaining(&self) -> usize {
+        self.keys.borrow().len()
+    }
+
+    /// Returns the backend the replay wraps.
+    pub fn into_inner(self) -> B {
+        self.term
+    }
+
+    /// Returns when the next key is due, or `None` if it is due now.
+    fn next_due(&self) -> Option<Instant> {
+        if !self.realtime {
+            return None;
+        }
+        let delay = self.keys.borrow().front()?.0;
+        let due = self.due.get().unwrap_or_else(|| Instant::now() + delay);
+        self.due.set(Some(due));
+        Some(due).filter(|&due| due > Instant::now())
+    }
+}
+
+impl<B: Backend> Backend for Replay<B> {
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.term.write_str(s)
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        if let Some(due) = self.next_due() {
+            thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+        self.due.set(None);
+        match self.keys.borrow_mut().pop_front() {
+            Some((_, key)) => Ok(key),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The replayed session has no more keys",
+            )),
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.term.size()
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.term.flush()
+    }
+
+    fn colors_supported(&self) -> bool {
+        self.term.colors_supported()
+    }
+
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        match self.next_due() {
+            Some(due) => {
+                let wait = due.saturating_duration_since(Instant::now());
+                thread::sleep(wait.min(timeout));
+                Ok(wait <= timeout)
+            }
+            None => Ok(true),
+        }
+    }
+}
+
+/// Returns the name a key is saved as.
+fn format_key(key: &Key) -> String {
+    match *key {
+        Key::ArrowUp => "up".into(),
+        Key::ArrowDown => "down".into(),
+        Key::ArrowLeft => "left".into(),
+        Key::ArrowRight => "right".into(),
+        Key::Enter => "enter".into(),
+        Key::Escape => "esc".into(),
+        Key::Backspace => "backspace".into(),
+        Key::Home => "home".into(),
+        Key::End => "end".into(),
+        Key::Tab => "tab".into(),
+        Key::BackTab => "backtab".into(),
+        Key::Alt => "alt".into(),
+        Key::Del => "del".into(),
+        Key::Shift => "shift".into(),
+        Key::Insert => "insert".into(),
+        Key::PageUp => "pgup".into(),
+        Key::PageDown => "pgdn".into(),
+        Key::CtrlC => "ctrl-c".into(),
+        Key::Char(c) => format!("char {}", format_char(c)),
+        Key::UnknownEscSeq(ref chars) => {
+            let chars: Vec<String> = chars.iter().map(|&c| format_char(c)).collect();
+            format!("esc-seq {}", chars.join(" "))
+        }
+        _ => "unknown".into(),
+    }
+}
+
+/// Parses a key saved with `format_key`.
+fn parse_key(name: &str) -> Option<Key> {
+    Some(match name {
+        "up" => Key::ArrowUp,
+        "down" => Key::ArrowDown,
+        "left" => Key::ArrowLeft,
+        "right" => Key::ArrowRight,
+        "enter" => Key::Enter,
+        "esc" => Key::Escape,
+        "backspace" => Key::Backspace,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "tab" => Key::Tab,
+        "backtab" => Key::BackTab,
+        "alt" => Key::Alt,
+        "del" => Key::Del,
+        "shift" => Key::Shift,
+        "insert" => Key::Insert,
+        "pgup" => Key::PageUp,
+        "pgdn" => Key::PageDown,
+        "ctrl-c" => Key::CtrlC,
+        "unknown" => Key::Unknown,
+        _ => {
+            if let Some(c) = name.strip_prefix("char ") {
+                Key::Char(parse_char(c)?)
+            } else if let Some(chars) = name.strip_prefix("esc-seq ") {
+                let chars: Option<Vec<char>> = chars.split(' ').map(parse_char).collect();
+                Key::UnknownEscSeq(chars?)
+            } else {
+                return None;
+            }
+        }
+    })
+}
+
+/// Returns a character as itself, or as `U+XXXX` if it is whitespace or a
+/// control character.
+fn format_char(c: char) -> String {
+    if c.is_whitespace() || c.is_control() {
+        format!("U+{:04X}", c as u32)
+    } else {
+        c.to_string()
+    }
+}
+
+fn parse_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => u32::from_str_radix(s.strip_prefix("U+")?, 16)
+            .ok()
+            .and_then(std::char::from_u32),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut rv = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            rv.push(c);
+            continue;
+        }
+        rv.push(match chars.next() {
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some(c) => c,
+            None => '\\',
+        });
+    }
+    rv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prompts::Confirmation;
+
+    struct Script {
+        keys: RefCell<Vec<Key>>,
+    }
+
+    impl Backend for Script {
+        fn write_str(&self, _s: &str) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn read_key(&self) -> io::Result<Key> {
+            Ok(self.keys.borrow_mut().remove(0))
+        }
+
+        fn size(&self) -> (u16, u16) {
+            (24, 80)
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let script = Script {
+            keys: RefCell::new(vec![Key::Char(' '), Key::Char('n')]),
+        };
+        let recorder = Recorder::new(script);
+        let answers = recorder.observer();
+        let rv = Confirmation::new()
+            .with_text("Deploy?")
+            .interact_on(&recorder)
+            .unwrap();
+        answers.on_submit("Deploy\tnow?", "no", Duration::from_secs(1));
+        let session = recorder.session();
+        assert_eq!(session.keys(), vec![Key::Char(' '), Key::Char('n')]);
+        assert_eq!(session.answers(), vec![("Deploy\tnow?", "no")]);
+
+        let saved = session.to_string();
+        assert!(saved.contains(" char U+0020\n"));
+        let session: Session = saved.parse().unwrap();
+        assert_eq!(session, recorder.session());
+        let replay = Replay::new(
+            Script {
+                keys: RefCell::new(vec![]),
+            },
+            session,
+        );
+        let replayed = Confirmation::new()
+            .with_text("Deploy?")
+            .interact_on(&replay)
+            .unwrap();
+        assert_eq!(replayed, rv);
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(
+            replay.read_key().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_parse_key() {
+        for key in &[
+            Key::Char('U'),
+            Key::Char('\x12'),
+            Key::UnknownEscSeq(vec!['[', 'M']),
+        ] {
+            assert_eq!(parse_key(&format_key(key)).as_ref(), Some(key));
+        }
+        assert!("key 10 hyper".parse::<Session>().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Support for running prompts from within an async runtime.
+use std::future::{poll_fn, Future};
+use std::mem;
+use std::panic;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::Poll;
+use std::thread;
+
+use tokio::runtime::{Builder, Handle, RuntimeFlavor};
+use tokio::sync::oneshot;
+
+/// A boxed future as returned by the `interact_async` family of methods.
+///
+/// The future is `Send`, so prompts can be awaited from within tasks
+/// passed to `tokio::spawn`.
+pub type PromptFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Runs a blocking interaction on a thread of its own.
+///
+/// The thread is started on the first poll and hands back the result over
+/// a oneshot channel, so the runtime keeps running other tasks while the
+/// user is typing, even on a current thread runtime.  The thread enters
+/// the runtime so that the interaction can spawn tasks onto it.
+fn detached<T, F>(f: F) -> impl Future<Output = T> + Send
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let mut f = Some(f);
+    let mut running = None;
+    poll_fn(move |cx| {
+        let &mut (ref mut rx, _) = running.get_or_insert_with(|| {
+            let f = f.take().expect("prompt future polled after completion");
+            let handle = Handle::try_current().ok();
+            let (tx, rx) = oneshot::channel();
+            let thread = thread::spawn(move || {
+                let _guard = handle.as_ref().map(Handle::enter);
+                let _ = tx.send(f());
+            });
+            (rx, thread)
+        });
+        match Pin::new(rx).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(rv)) => Poll::Ready(rv),
+            Poll::Ready(Err(_)) => {
+                // the sender is only dropped unsent if the interaction
+                // panicked, so hand the panic on to the caller
+                let (_, thread) = running.take().unwrap();
+                match thread.join() {
+                    Ok(()) => unreachable!("prompt thread exited without a result"),
+                    Err(payload) => panic::resume_unwind(payload),
+                }
+            }
+        }
+    })
+}
+
+/// Wraps a blocking interaction into a future that runs it on a thread of
+/// its own.
+pub(crate) fn run_detached<T, F>(f: F) -> PromptFuture<'static, T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    Box::pin(detached(f))
+}
+
+/// Like `run_detached` but lends `prompt` to the interaction.
+///
+/// The prompt is swapped for `empty` while the interaction runs and put
+/// back once it is done.  If the future is dropped before that the
+/// interaction keeps running until the user answers, and `empty` stays.
+pub(crate) fn run_detached_mut<'p, P, T, F>(
+    prompt: &'p mut P,
+    empty: P,
+    f: F,
+) -> PromptFuture<'p, T>
+where
+    P: Send + 'static,
+    T: Send + 'static,
+    F: FnOnce(&mut P) -> T + Send + 'static,
+{
+    let mut start = Some((empty, f));
+    let mut running = None;
+    Box::pin(poll_fn(move |cx| {
+        let interaction = running.get_or_insert_with(|| {
+            let (empty, f) = start.take().expect("prompt future polled after completion");
+            let mut lent = mem::replace(&mut *prompt, empty);
+            Box::pin(detached(move || {
+                let rv = f(&mut lent);
+                (lent, rv)
+            }))
+        });
+        interaction.as_mut().poll(cx).map(|(lent, rv)| {
+            *prompt = lent;
+            rv
+        })
+    }))
+}
+
+/// Runs a future in the background and returns a channel receiving its
+/// output.
+///
+/// The future is spawned onto the current runtime if it has worker
+/// threads to run it on while the caller blocks, or else onto a runtime
+/// of its own.
+pub(crate) fn spawn_future<T, F>(fut: F) -> mpsc::Receiver<T>
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let mut fut = Box::pin(fut);
+    let task = poll_fn(move |cx| {
+        fut.as_mut().poll(cx).map(|rv| {
+            let _ = tx.send(rv);
+        })
+    });
+    match Handle::try_current() {
+        Ok(ref handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            handle.spawn(task);
+        }
+        _ => {
+            thread::spawn(move || {
+                if let Ok(rt) = Builder::new_current_thread().build() {
+                    rt.block_on(task);
+                }
+            });
+        }
+    }
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_detached() {
+        let rt = Builder::new_current_thread().build().unwrap();
+        let _guard = rt.enter();
+        let (tx, rx) = mpsc::channel();
+        // the prompt only gets its answer if the runtime keeps running
+        // the other task while the prompt waits
+        let prompt = tokio::spawn(run_detached(move || {
+            rx.recv_timeout(Duration::from_secs(5))
+        }));
+        tokio::spawn(poll_fn(move |_| Poll::Ready(tx.send(42))));
+        assert_eq!(rt.block_on(prompt).unwrap(), Ok(42));
+    }
+
+    #[test]
+    fn test_run_detached_mut() {
+        let mut prompt = vec![1];
+        let rt = Builder::new_multi_thread().build().unwrap();
+        let sum = rt.block_on(run_detached_mut(&mut prompt, vec![], |prompt| {
+            prompt.push(2);
+            prompt.iter().sum::<i32>()
+        }));
+        assert_eq!(sum, 3);
+        assert_eq!(prompt, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_spawn_future() {
+        assert_eq!(spawn_future(std::future::ready(1)).recv(), Ok(1));
+        let rt = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+        let rx = rt.block_on(poll_fn(|_| {
+            Poll::Ready(spawn_future(std::future::ready(2)))
+        }));
+        assert_eq!(rx.recv(), Ok(2));
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let value = rt.block_on(poll_fn(|_| {
+            // the current thread runs the runtime, so waiting here only
+            // works if the future runs elsewhere
+            Poll::Ready(spawn_future(std::future::ready(3)).recv())
+        }));
+        assert_eq!(value, Ok(3));
+    }
+}
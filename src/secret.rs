@@ -0,0 +1,178 @@
+use std::env;
+use std::fmt;
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::io::{FromRawFd, RawFd};
+#[cfg(unix)]
+use std::sync::Mutex;
+
+/// A place a `PasswordInput` looks for the secret before asking for it.
+///
+/// Sources that are not set, empty or fail to read are skipped.  The
+/// report line names the source a secret came from, e.g. `[hidden] from
+/// $DB_PASSWORD`, but never shows the secret itself.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::{PasswordInput, SecretSource};
+///
+/// let mut input = PasswordInput::new();
+/// input
+///     .with_prompt("Database password")
+///     .with_source(SecretSource::Env("DB_PASSWORD".into()));
+/// let (password, source) = input.interact_with_source()?;
+/// if source.is_none() {
+///     println!("Set DB_PASSWORD to skip this prompt");
+/// }
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+#[derive(Debug)]
+pub enum SecretSource {
+    /// An environment variable.
+    Env(String),
+    /// An entry of the system keyring, e.g. the macOS keychain or the
+    /// secret service on Linux.
+    #[cfg(feature = "keyring")]
+    Keyring {
+        /// The service the entry belongs to.
+        service: String,
+        /// The user name of the entry.
+        user: String,
+    },
+    /// A file descriptor inherited from the parent process, see
+    /// `SecretSource::fd`.
+    #[cfg(unix)]
+    Fd(FdSecret),
+}
+
+/// A file descriptor owned by a `SecretSource`.
+///
+/// The descriptor is read at most once and closed afterwards, or when the
+/// source is dropped.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct FdSecret {
+    fd: RawFd,
+    file: Mutex<Option<File>>,
+}
+
+impl SecretSource {
+    /// Creates a source that reads the secret from a file descriptor
+    /// inherited from the parent process, e.g. for a `--password-fd` flag.
+    ///
+    /// The source takes ownership of the descriptor.  Everything is read
+    /// up to the end and a final newline is dropped.  A descriptor that is
+    /// not open is skipped.
+    #[cfg(unix)]
+    pub fn fd(fd: RawFd) -> SecretSource {
+        let file = if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+            None
+        } else {
+            Some(unsafe { File::from_raw_fd(fd) })
+        };
+        SecretSource::Fd(FdSecret {
+            fd,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Returns the secret if the source has one.
+    pub(crate) fn read(&self) -> Option<String> {
+        let secret = match *self {
+            SecretSource::Env(ref name) => env::var(name).ok()?,
+            #[cfg(feature = "keyring")]
+            SecretSource::Keyring {
+                ref service,
+                ref user,
+            } => keyring::Entry::new(service, user)
+                .ok()?
+                .get_password()
+                .ok()?,
+            #[cfg(unix)]
+            SecretSource::Fd(ref fd) => {
+                let mut file = fd
+                    .file
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner())
+                    .take()?;
+                let mut secret = String::new();
+                file.read_to_string(&mut secret).ok()?;
+                if secret.ends_with('\n') {
+                    secret.pop();
+                    if secret.ends_with('\r') {
+                        secret.pop();
+                    }
+                }
+                secret
+            }
+        };
+        if secret.is_empty() {
+            None
+        } else {
+            Some(secret)
+        }
+    }
+}
+
+impl fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SecretSource::Env(ref name) => write!(f, "${}", name),
+            #[cfg(feature = "keyring")]
+            SecretSource::Keyring {
+                ref service,
+                ref user,
+            } => write!(f, "keyring {}/{}", service, user),
+            #[cfg(unix)]
+            SecretSource::Fd(ref fd) => write!(f, "fd {}", fd.fd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    use std::io::Write;
+
+    use prompts::PasswordInput;
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_env_source() {
+        let missing = SecretSource::Env("DIALOGUER_TEST_MISSING_SECRET".into());
+        env::set_var("DIALOGUER_TEST_SECRET", "hunter2");
+        let term = VirtualTerm::new(10, 60);
+        let mut input = PasswordInput::new();
+        input
+            .with_prompt("Password")
+            .with_source(missing)
+            .with_source(SecretSource::Env("DIALOGUER_TEST_SECRET".into()));
+        let answer = input.interact_with_source_on(&term).unwrap();
+        assert_eq!(answer.0, "hunter2");
+        assert_eq!(answer.1.unwrap().to_string(), "$DIALOGUER_TEST_SECRET");
+        assert_eq!(
+            term.screen(),
+            "Password: [hidden] from $DIALOGUER_TEST_SECRET"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fd_source_reads_once() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let mut writer = unsafe { File::from_raw_fd(fds[1]) };
+        writer.write_all(b"hunter2\n").unwrap();
+        drop(writer);
+        let source = SecretSource::fd(fds[0]);
+        assert_eq!(source.read(), Some("hunter2".into()));
+        assert_eq!(source.read(), None);
+        assert_eq!(unsafe { libc::fcntl(fds[0], libc::F_GETFD) }, -1);
+        assert_eq!(source.to_string(), format!("fd {}", fds[0]));
+    }
+}
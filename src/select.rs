@@ -1,28 +1,106 @@
-use std::io;
-use std::iter::repeat;
-use std::ops::Rem;
+use std::env;
+use std::fmt;
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
 
-use theme::{get_default_theme, SelectionStyle, TermThemeRenderer, Theme};
+use backend::{Backend, HiddenCursor, TermGuard};
+use context::{AssumePolicy, NonInteractive};
+use handle::{suspend_prompt, wait_for_event_or_update, PromptHandle, Region, Update};
+use stream::Stream;
+use theme::{
+    get_default_theme, ReportFn, SelectionStyle, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef,
+};
 
-use console::{Key, Term};
+use console::{measure_text_width, strip_ansi_codes, Key, Style};
+use events::{pop_grapheme, read_key, read_plain_line, timed_out, wait_for_key, Event};
+use keymap::{Action, KeyMap};
+use messages::{fill, messages};
+use mouse::{read_mouse, Clicks, Mouse, MouseCapture};
+use observer::{labels, observe};
+use paging::Paging;
+use progress::spin_while;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use state;
+
+/// How an item of a menu behaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    /// A regular item.
+    Enabled,
+    /// An item that is shown but cannot be highlighted.
+    Disabled,
+    /// A header between groups of items.
+    Separator,
+}
+
+/// How long a numbered menu waits for the next digit of an item number.
+const NUMBER_DELAY: Duration = Duration::from_millis(600);
+
+/// The byte offset and letter of the hotkey of an item.
+type Hotkey = Option<(usize, char)>;
+
+/// Fetches the items of a menu when it opens.
+type ItemLoader<'a> = Box<dyn Fn() -> io::Result<Vec<String>> + Send + Sync + 'a>;
+
+/// Describes the item at an index for the preview panel of a menu.
+type PreviewFn<'a> = Box<dyn Fn(usize) -> String + Send + Sync + 'a>;
 
 /// Renders a selection menu.
 pub struct Select<'a> {
     default: usize,
     items: Vec<String>,
+    kinds: Vec<ItemKind>,
     prompt: Option<String>,
     clear: bool,
-    theme: &'a Theme,
+    theme: ThemeRef<'a>,
+    reporter: Option<ReportFn<'a>>,
     paged: bool,
+    alternate_screen: bool,
+    timeout: Option<Duration>,
+    help: Option<String>,
+    searchable: bool,
+    keymap: KeyMap,
+    loader: Option<ItemLoader<'a>>,
+    preview: Option<PreviewFn<'a>>,
+    preview_height: usize,
+    handle: Option<PromptHandle>,
+    columns: bool,
+    numbered: bool,
+    hotkeys: bool,
+    echo: bool,
+    remember_key: Option<String>,
+    default_env: Option<String>,
+    header: Option<String>,
+    non_interactive: NonInteractive,
+    assume: AssumePolicy,
+    answer: Option<usize>,
+    stream: Stream,
 }
 
 /// Renders a multi select checkbox menu.
 pub struct Checkboxes<'a> {
     items: Vec<String>,
+    kinds: Vec<ItemKind>,
     prompt: Option<String>,
     clear: bool,
-    theme: &'a Theme,
+    theme: ThemeRef<'a>,
+    reporter: Option<ReportFn<'a>>,
     paged: bool,
+    alternate_screen: bool,
+    timeout: Option<Duration>,
+    help: Option<String>,
+    min_selected: usize,
+    max_selected: Option<usize>,
+    keymap: KeyMap,
+    loader: Option<ItemLoader<'a>>,
+    handle: Option<PromptHandle>,
+    non_interactive: NonInteractive,
+    assume: AssumePolicy,
+    answer: Option<Vec<usize>>,
+    stream: Stream,
 }
 
 impl<'a> Select<'a> {
@@ -32,21 +110,88 @@ impl<'a> Select<'a> {
     }
 
     /// Same as `new` but with a specific theme.
-    pub fn with_theme(theme: &'a Theme) -> Select<'a> {
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Select<'a> {
+        Select::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    ///
+    /// The resulting prompt does not borrow anything and can be stored or
+    /// returned from functions freely.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Select<'static> {
+        Select::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    /// Creates a menu of the output lines of a shell command.
+    ///
+    /// This is a shortcut for `new` followed by `items_from_command`.
+    ///
+    /// ```rust,no_run
+    /// # fn test() -> Result<(), Box<std::error::Error>> {
+    /// use dialoguer::Select;
+    ///
+    /// let branch = Select::from_command("git branch --format='%(refname:short)'")
+    ///     .with_prompt("Branch")
+    ///     .interact()?;
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    pub fn from_command(command: &str) -> Select<'static> {
+        let mut select = Select::new();
+        select.items_from_command(command);
+        select
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Select<'a> {
         Select {
             default: !0,
             items: vec![],
+            kinds: vec![],
             prompt: None,
             clear: true,
-            theme: theme,
+            theme,
+            reporter: None,
             paged: false,
+            alternate_screen: false,
+            timeout: None,
+            help: None,
+            searchable: false,
+            keymap: KeyMap::default(),
+            loader: None,
+            preview: None,
+            preview_height: 5,
+            handle: None,
+            columns: false,
+            numbered: false,
+            hotkeys: false,
+            echo: false,
+            remember_key: None,
+            default_env: None,
+            header: None,
+            non_interactive: NonInteractive::Prompt,
+            assume: AssumePolicy::Interactive,
+            answer: None,
+            stream: Stream::Stderr,
         }
     }
     /// Enables or disables paging
+    ///
+    /// Menus taller than the terminal are always paged, keeping the prompt
+    /// pinned above the items even if it spans several rows; this also
+    /// shows the position indicator for menus that fit.
     pub fn paged(&mut self, val: bool) -> &mut Select<'a> {
         self.paged = val;
         self
     }
+
+    /// Shows the menu on the alternate screen of the terminal.
+    ///
+    /// The menu then has the whole terminal to itself and leaves no
+    /// trace in the scrollback: the previous screen comes back on exit and
+    /// only the summary line is printed.  The default is `false`.
+    pub fn alternate_screen(&mut self, val: bool) -> &mut Select<'a> {
+        self.alternate_screen = val;
+        self
+    }
     /// Sets the clear behavior of the menu.
     ///
     /// The default is to clear the menu.
@@ -55,26 +200,408 @@ impl<'a> Select<'a> {
         self
     }
 
+    /// Sets whether a summary line is printed after the selection.
+    ///
+    /// The summary is only printed for menus with a prompt and is rendered
+    /// by `Theme::format_select_report`.  The default is to print it.
+    /// `report(false)` is the same as `with_report(|_| None)`; whichever is
+    /// called last wins.
+    pub fn report(&mut self, val: bool) -> &mut Select<'a> {
+        self.reporter = if val { None } else { Some(Box::new(|_| None)) };
+        self
+    }
+
+    /// Sets a function that rewrites the item shown on the report line.
+    ///
+    /// This can e.g. mask a sensitive value or append a unit.  Returning
+    /// `None` leaves the report line out.
+    pub fn with_report<F>(&mut self, f: F) -> &mut Select<'a>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'a,
+    {
+        self.reporter = Some(Box::new(f));
+        self
+    }
+
     /// Sets a default for the menu
     pub fn default(&mut self, val: usize) -> &mut Select<'a> {
         self.default = val;
         self
     }
 
+    /// Remembers the picked item under a key.
+    ///
+    /// Later menus with the same key start on the item picked last, which
+    /// is matched by its text; if no item matches the default applies.
+    pub fn remember_key(&mut self, key: &str) -> &mut Select<'a> {
+        self.remember_key = Some(key.into());
+        self
+    }
+
+    /// Returns the item the menu starts on.
+    fn default_in(&self, items: &[String]) -> usize {
+        self.remember_key
+            .as_ref()
+            .and_then(|key| state::recall(key))
+            .and_then(|value| items.iter().position(|item| *item == value))
+            .or_else(|| {
+                self.default_env
+                    .as_ref()
+                    .and_then(|var| env::var(var).ok())
+                    .and_then(|value| items.iter().position(|item| *item == value))
+            })
+            .unwrap_or(self.default)
+    }
+
+    /// Takes the default from an environment variable if it is set.
+    ///
+    /// The value is matched against the items when the menu is shown, so
+    /// items added later or loaded with `items_from` are included.  Values
+    /// that match no item are ignored.
+    pub fn default_from_env(&mut self, var: &str) -> &mut Select<'a> {
+        self.default_env = Some(var.into());
+        self
+    }
+
+    /// Sets a timeout after which the default is selected.
+    ///
+    /// The timeout restarts with every keypress.  If no default is set an
+    /// error of kind `TimedOut` is returned instead.  Timeouts are currently
+    /// only supported on unix systems.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Select<'a> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Shows a help text below the items.
+    ///
+    /// The help is rendered as a hint by the theme.  Independently of this
+    /// the user can press `?` to toggle a list of the key bindings.
+    pub fn with_help(&mut self, help: &str) -> &mut Select<'a> {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Shows a fixed line above the items, e.g. column titles.
+    ///
+    /// The header is aligned with the item texts and stays in place when
+    /// the menu scrolls.
+    pub fn with_header(&mut self, header: &str) -> &mut Select<'a> {
+        self.header = Some(header.to_string());
+        self
+    }
+
+    /// Enables searching the items.
+    ///
+    /// Pressing `/` starts a search: typed text then filters the items by
+    /// a case-insensitive substring match and Esc clears the filter again.
+    pub fn searchable(&mut self, val: bool) -> &mut Select<'a> {
+        self.searchable = val;
+        self
+    }
+
+    /// Lays out the items in a grid if they are short enough.
+    ///
+    /// The items fill the rows from left to right in as many columns as
+    /// fit the terminal.  The left and right arrow keys (or `h` and `l`)
+    /// then move between columns instead of pages.
+    pub fn columns(&mut self, val: bool) -> &mut Select<'a> {
+        self.columns = val;
+        let (left, right) = if val {
+            (Action::Left, Action::Right)
+        } else {
+            (Action::PageUp, Action::PageDown)
+        };
+        self.keymap
+            .bind(Key::ArrowLeft, left)
+            .bind(Key::Char('h'), left)
+            .bind(Key::ArrowRight, right)
+            .bind(Key::Char('l'), right);
+        self
+    }
+
+    /// Prefixes the items with numbers the user can type to pick them.
+    ///
+    /// Single digits select instantly unless a longer number could follow,
+    /// in which case the menu waits briefly for the next digit.
+    pub fn numbered(&mut self, val: bool) -> &mut Select<'a> {
+        self.numbered = val;
+        self
+    }
+
+    /// Shows the highlighted item after the prompt, e.g.
+    /// `Pick a region: eu-west-1`, updated as the highlight moves.
+    ///
+    /// This keeps the pending choice in view on small terminals where the
+    /// menu scrolls.
+    pub fn echo_highlighted(&mut self, val: bool) -> &mut Select<'a> {
+        self.echo = val;
+        self
+    }
+
+    /// Enables hotkeys marked with `&` in the item texts, e.g. `&Install`.
+    ///
+    /// The letter after the `&` is rendered with the hotkey style of the
+    /// theme and picks its item right away when pressed, taking precedence
+    /// over key bindings.  `&&` stands for a literal `&`.
+    pub fn hotkeys(&mut self, val: bool) -> &mut Select<'a> {
+        self.hotkeys = val;
+        self
+    }
+
+    /// Sets the key bindings of the menu.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut Select<'a> {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Lets `handle` change the items, prompt and the rows below the
+    /// menu while it is shown.
+    pub fn with_handle(&mut self, handle: &PromptHandle) -> &mut Select<'a> {
+        self.handle = Some(handle.clone());
+        self
+    }
+
     /// Add a single item to the selector.
     pub fn item(&mut self, item: &str) -> &mut Select<'a> {
-        self.items.push(item.to_string());
+        self.push_item(item.to_string(), ItemKind::Enabled);
         self
     }
 
     /// Adds multiple items to the selector.
     pub fn items<T: ToString>(&mut self, items: &[T]) -> &mut Select<'a> {
         for item in items {
-            self.items.push(item.to_string());
+            self.push_item(item.to_string(), ItemKind::Enabled);
+        }
+        self
+    }
+
+    /// Adds an item that is shown dimmed and cannot be selected.
+    pub fn disabled_item(&mut self, item: &str) -> &mut Select<'a> {
+        self.push_item(item.to_string(), ItemKind::Disabled);
+        self
+    }
+
+    /// Adds a separator, e.g. a header for the following group of items.
+    ///
+    /// Separators take up an index like items but are skipped when
+    /// navigating.
+    pub fn separator(&mut self, text: &str) -> &mut Select<'a> {
+        self.push_item(text.to_string(), ItemKind::Separator);
+        self
+    }
+
+    /// Fetches items when the prompt opens instead of upfront.
+    ///
+    /// The menu shows a spinner while `loader` runs on another thread and
+    /// appends the returned items to the ones added with `item` and
+    /// `items`.  The user can reload them with ctrl-r; an error of the
+    /// loader is returned from `interact`.
+    pub fn items_from<F>(&mut self, loader: F) -> &mut Select<'a>
+    where
+        F: Fn() -> io::Result<Vec<String>> + Send + Sync + 'a,
+    {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    /// Runs a shell command when the prompt opens and adds the lines it
+    /// prints as items.
+    ///
+    /// The command is a loader as with `items_from`: a spinner shows
+    /// while it runs and ctrl-r runs it again.  It is run through `sh -c`,
+    /// or `cmd /C` on Windows, and ANSI styling and blank lines are
+    /// dropped from its output.  If it fails, `interact` returns an error
+    /// with the first line of its error output.
+    pub fn items_from_command(&mut self, command: &str) -> &mut Select<'a> {
+        let command = command.to_string();
+        self.items_from(move || run_command(&command))
+    }
+
+    /// Adds the lines read from `reader` as items.
+    ///
+    /// Like with `items_from_command` ANSI styling and blank lines are
+    /// dropped, so the output of other tools can be piped in.
+    pub fn items_from_reader<R: Read>(&mut self, mut reader: R) -> io::Result<&mut Select<'a>> {
+        let mut output = String::new();
+        reader.read_to_string(&mut output)?;
+        for line in output_lines(&output) {
+            self.push_item(line, ItemKind::Enabled);
         }
+        Ok(self)
+    }
+
+    /// Shows a panel below the menu describing the highlighted item.
+    ///
+    /// `preview` gets the index of the item and returns the text of the
+    /// panel, e.g. the description of a package.  It is called whenever
+    /// the highlight moves to another item.
+    pub fn with_preview<F>(&mut self, preview: F) -> &mut Select<'a>
+    where
+        F: Fn(usize) -> String + Send + Sync + 'a,
+    {
+        self.preview = Some(Box::new(preview));
+        self
+    }
+
+    /// Sets the number of rows of the preview panel.
+    ///
+    /// Longer previews are cut off and shorter ones padded so the menu
+    /// does not move.  The default is 5.
+    pub fn preview_height(&mut self, rows: usize) -> &mut Select<'a> {
+        self.preview_height = rows;
         self
     }
 
+    /// Removes all items.
+    pub(crate) fn clear_items(&mut self) {
+        self.items.clear();
+        self.kinds.clear();
+    }
+
+    fn push_item(&mut self, item: String, kind: ItemKind) {
+        self.items.push(item);
+        self.kinds.push(kind);
+    }
+
+    /// Returns the labels to display for the items and the byte offset of
+    /// the item text within them.
+    fn labels(&self, items: &[String], kinds: &[ItemKind]) -> (Vec<String>, usize) {
+        if !self.numbered {
+            return (items.to_vec(), 0);
+        }
+        let width = items.len().to_string().len();
+        let labels = items
+            .iter()
+            .zip(kinds)
+            .enumerate()
+            .map(|(idx, (item, kind))| match *kind {
+                ItemKind::Enabled => format!("{:>2$}. {}", idx + 1, item, width),
+                ItemKind::Disabled => format!("{:2$}  {}", "", item, width),
+                ItemKind::Separator => item.clone(),
+            })
+            .collect();
+        (labels, width + 2)
+    }
+
+    /// Reads the rest of a typed item number starting with `number`.
+    ///
+    /// While a longer number is possible this waits briefly for another
+    /// digit; any other key ends the number and is discarded.
+    fn read_item_number(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        mut number: usize,
+        kinds: &[ItemKind],
+    ) -> io::Result<Option<usize>> {
+        while number > 0 && number * 10 <= kinds.len() && wait_for_key(term, Some(NUMBER_DELAY))? {
+            match render.clear_interrupted(read_key(term))? {
+                Key::Char(c) if c.is_ascii_digit() => {
+                    number = number * 10 + c.to_digit(10).unwrap() as usize;
+                }
+                _ => break,
+            }
+        }
+        Ok(parse_item_number(&number.to_string(), kinds))
+    }
+
+    /// Remembers the picked item and prints the summary line for it.
+    fn finish_selection(
+        &self,
+        render: &mut TermThemeRenderer,
+        prompt: Option<&str>,
+        item: &str,
+    ) -> io::Result<()> {
+        render.leave_alternate_screen()?;
+        if let Some(ref key) = self.remember_key {
+            state::remember(key, item);
+        }
+        match prompt {
+            Some(prompt) => render.select_report(prompt, item),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the rows of the preview panel for the highlighted item.
+    ///
+    /// The preview of the last item is kept in `cache` so it is not
+    /// computed again for every frame.
+    fn preview_rows(
+        &self,
+        sel: usize,
+        kinds: &[ItemKind],
+        cache: &mut Option<(usize, String)>,
+    ) -> Vec<String> {
+        let preview = match self.preview {
+            Some(ref preview) => preview,
+            None => return vec![],
+        };
+        let enabled = kinds.get(sel) == Some(&ItemKind::Enabled);
+        if enabled && cache.as_ref().map(|cache| cache.0) != Some(sel) {
+            *cache = Some((sel, preview(sel)));
+        }
+        let text = match *cache {
+            Some((idx, ref text)) if enabled && idx == sel => text.as_str(),
+            _ => "",
+        };
+        let mut rows: Vec<String> = text
+            .lines()
+            .take(self.preview_height)
+            .map(String::from)
+            .collect();
+        rows.resize(self.preview_height, String::new());
+        rows
+    }
+
+    /// Removes the hotkey markers from the items and returns the hotkeys.
+    fn strip_hotkeys(&self, items: &mut [String]) -> Vec<Hotkey> {
+        items
+            .iter_mut()
+            .map(|item| {
+                if !self.hotkeys {
+                    return None;
+                }
+                let (text, hotkey) = parse_hotkey(item);
+                *item = text;
+                hotkey
+            })
+            .collect()
+    }
+
+    /// Adds items with attached values and returns a menu yielding values.
+    ///
+    /// Every item is a pair of the label to display and the value `interact`
+    /// returns when the item is picked.  Configure the menu before calling
+    /// this as the returned `SelectValues` only offers the `interact` family.
+    ///
+    /// ```rust,no_run
+    /// # fn test() -> Result<(), Box<std::error::Error>> {
+    /// use dialoguer::Select;
+    ///
+    /// let port: u16 = Select::new()
+    ///     .with_prompt("Port")
+    ///     .items_with_values(&[("http", 80), ("https", 443)])
+    ///     .interact()?;
+    /// # Ok(()) } fn main() { test().unwrap(); }
+    /// ```
+    pub fn items_with_values<L: ToString, T: Clone>(
+        &mut self,
+        items: &[(L, T)],
+    ) -> SelectValues<'_, 'a, T> {
+        let offset = self.items.len();
+        let mut values = Vec::with_capacity(items.len());
+        for (label, value) in items {
+            self.push_item(label.to_string(), ItemKind::Enabled);
+            values.push(value.clone());
+        }
+        SelectValues {
+            select: self,
+            offset,
+            values,
+        }
+    }
+
     /// Prefaces the menu with a prompt.
     ///
     /// When a prompt is set the system also prints out a confirmation after
@@ -84,25 +611,102 @@ impl<'a> Select<'a> {
         self
     }
 
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Select<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted item for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut Select<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets what the menu does if it is not connected to a terminal.
+    ///
+    /// The default is to prompt anyway.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut Select<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Sets whether the menu answers itself with its default, e.g. for a
+    /// `--yes` flag.
+    ///
+    /// The default is to ask.  A menu without a default always asks.
+    pub fn assume(&mut self, policy: AssumePolicy) -> &mut Select<'a> {
+        self.assume = policy;
+        self
+    }
+
+    /// Answers the prompt with the item at `index` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, index: usize) -> &mut Select<'a> {
+        self.answer = Some(index);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Select<'a> {
+        self.stream = stream;
+        self
+    }
+
     /// Enables user interaction and returns the result.
     ///
     /// The index of the selected item.
-    /// The dialog is rendered on stderr.
+    /// The dialog is rendered on stderr unless changed with `on`.
     pub fn interact(&self) -> io::Result<usize> {
-        self.interact_on(&Term::stderr())
+        self.interact_on(&self.stream.term())
     }
 
     /// Enables user interaction and returns the result.
     ///
     /// The index of the selected item. None if the user
     /// cancelled with Esc or 'q'.
-    /// The dialog is rendered on stderr.
+    /// The dialog is rendered on stderr unless changed with `on`.
     pub fn interact_opt(&self) -> io::Result<Option<usize>> {
-        self._interact_on(&Term::stderr(), true)
+        self._interact_on(&self.stream.term(), true)
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    ///
+    /// The prompt runs on a thread of its own, so other tasks keep running
+    /// while the user is typing.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<usize>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Select::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact_opt` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_opt_async(&mut self) -> PromptFuture<'_, io::Result<Option<usize>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Select::new(), |prompt| prompt.interact_opt())
     }
 
     /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on(&self, term: &Term) -> io::Result<usize> {
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<usize> {
         self._interact_on(term, false)?.ok_or(io::Error::new(
             io::ErrorKind::Other,
             "Quit not allowed in this case",
@@ -110,107 +714,475 @@ impl<'a> Select<'a> {
     }
 
     /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on_opt(&self, term: &Term) -> io::Result<Option<usize>> {
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<usize>> {
         self._interact_on(term, true)
     }
 
-    /// Like `interact` but allows a specific terminal to be set.
-    fn _interact_on(&self, term: &Term, allow_quit: bool) -> io::Result<Option<usize>> {
-        let mut page = 0;
-        let mut capacity = self.items.len();
-        if self.paged {
-            capacity = term.size().0 as usize - 1;
-        }
-        let pages = (self.items.len() / capacity) + 1;
-        let mut render = TermThemeRenderer::new(term, self.theme);
-        let mut sel = self.default;
-        if let Some(ref prompt) = self.prompt {
+    fn _interact_on(&self, term: &dyn Backend, allow_quit: bool) -> io::Result<Option<usize>> {
+        let prompt = self.prompt.as_deref().unwrap_or("");
+        observe(
+            prompt,
+            || self.read_answer(term, allow_quit),
+            |rv| rv.map(|idx| labels(&self.items, &[idx])),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend, allow_quit: bool) -> io::Result<Option<usize>> {
+        let _guard = TermGuard::new(term);
+        let default = self.default_in(&self.items);
+        let default = if default < self.items.len() {
+            Some(default)
+        } else {
+            None
+        };
+        if let Some(idx) = self.answer.or_else(|| self.assume.selection(default)) {
+            let item = self.items.get(idx).ok_or_else(|| invalid_answer(idx))?;
+            let mut render = TermThemeRenderer::new(term, &*self.theme);
+            render.set_report(self.reporter.as_ref());
+            self.finish_selection(&mut render, self.prompt.as_deref(), item)?;
+            return Ok(Some(idx));
+        }
+        let default = default.map(Some);
+        if let Some(rv) = self.non_interactive.answer(term, default) {
+            return rv;
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        render.set_report(self.reporter.as_ref());
+        if self.alternate_screen {
+            render.enter_alternate_screen()?;
+        }
+        let echo = self.echo && !render.is_accessible();
+        if let (Some(ref prompt), false) = (&self.prompt, echo) {
             render.prompt(prompt)?;
         }
-        let mut size_vec = Vec::new();
-        for items in self.items.iter().as_slice() {
-            let size = &items.len();
-            size_vec.push(size.clone());
+        let (mut items, mut kinds) =
+            load_items(&mut render, &self.items, &self.kinds, &self.loader)?;
+        let mut hotkeys = self.strip_hotkeys(&mut items);
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render, allow_quit, &items, &kinds);
+        }
+        let _cursor = HiddenCursor::new(term)?;
+        let mouse = MouseCapture::new(term)?;
+        let _open = self.handle.as_ref().map(|handle| handle.open());
+        let mut clicks = Clicks::default();
+        let mut paging = Paging::new(items.len(), self.paged);
+        let default = self.default_in(&items);
+        let mut sel = default;
+        let mut show_keys = false;
+        let mut query: Option<String> = None;
+        let mut preview_cache = None;
+        let mut prompt = self.prompt.clone();
+        let mut region = Region::default();
+        let mut visible: Vec<usize> = (0..items.len()).collect();
+        let mut actions = vec![Action::Up, Action::Down];
+        if self.columns {
+            actions.extend_from_slice(&[Action::Left, Action::Right]);
+        }
+        actions.extend_from_slice(&[
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Confirm,
+        ]);
+        if self.searchable {
+            actions.push(Action::Search);
+        }
+        if allow_quit {
+            actions.push(Action::Cancel);
+        }
+        if self.loader.is_some() {
+            actions.push(Action::Refresh);
+        }
+        actions.push(Action::Help);
+        let mut keys = self.keymap.help_lines(&actions);
+        if self.numbered {
+            let text = format!("{:<12}{}", "0-9", messages().pick_by_number);
+            keys.insert(keys.len() - 1, text);
         }
         loop {
-            for (idx, item) in self
-                .items
-                .iter()
-                .enumerate()
-                .skip(page * capacity)
-                .take(capacity)
-            {
-                render.selection(
-                    item,
-                    if sel == idx {
-                        SelectionStyle::MenuSelected
-                    } else {
-                        SelectionStyle::MenuUnselected
-                    },
-                )?;
+            let pos = visible.iter().position(|&idx| idx == sel);
+            let (mut labels, shift) = self.labels(&items, &kinds);
+            if query.is_none() {
+                mark_hotkeys(&*self.theme, &mut labels, &hotkeys, shift)
+                    .map_err(io::Error::other)?;
             }
-            match term.read_key()? {
-                Key::ArrowDown | Key::Char('j') => {
-                    if sel == !0 {
-                        sel = 0;
-                    } else {
-                        sel = (sel as u64 + 1).rem(self.items.len() as u64) as usize;
+            let (cols, cell_width) = if self.columns {
+                grid_layout(&*self.theme, &labels, term.size().1 as usize)
+            } else {
+                (1, 0)
+            };
+            let row = pos.map_or(!0, |pos| pos / cols);
+            let preview = self.preview_rows(sel, &kinds, &mut preview_cache);
+            let reserved = render.prompt_rows()
+                + (echo && prompt.is_some()) as usize
+                + query.is_some() as usize
+                + self.header.is_some() as usize
+                + region.height()
+                + preview.len()
+                + help_rows(&self.help, show_keys, &keys);
+            paging.set_items(visible.len().div_ceil(cols));
+            paging.update(term, reserved, row);
+            render.begin_frame();
+            if let (true, Some(ref prompt)) = (echo, &prompt) {
+                render.input_line(prompt, items.get(sel).map_or("", |item| item.as_str()))?;
+            }
+            if let Some(ref query) = query {
+                render.search_prompt(query)?;
+            }
+            if let Some(ref header) = self.header {
+                render.table_header(&format!("{}{}", " ".repeat(shift), header))?;
+            }
+            let rows = visible
+                .chunks(cols)
+                .skip(paging.offset())
+                .take(paging.capacity());
+            for cells in rows.clone().filter(|_| cols > 1) {
+                let cells: Vec<_> = cells
+                    .iter()
+                    .map(|&idx| {
+                        let span = query
+                            .as_ref()
+                            .and_then(|query| find_match(&items[idx], query))
+                            .map(|(start, end)| (start + shift, end + shift));
+                        format_cell(&*self.theme, &labels[idx], kinds[idx], span, sel == idx)
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(io::Error::other)?;
+                render.grid_row(&cells, cell_width)?;
+            }
+            for &idx in rows.filter(|_| cols == 1).flatten() {
+                let item = &labels[idx];
+                let span = query
+                    .as_ref()
+                    .and_then(|query| find_match(&items[idx], query))
+                    .map(|(start, end)| (start + shift, end + shift));
+                match kinds[idx] {
+                    ItemKind::Enabled => render.selection_with_match(
+                        item,
+                        span,
+                        if sel == idx {
+                            SelectionStyle::MenuSelected
+                        } else {
+                            SelectionStyle::MenuUnselected
+                        },
+                    )?,
+                    ItemKind::Disabled => {
+                        render.disabled_item(item, SelectionStyle::MenuUnselected)?
                     }
+                    ItemKind::Separator => render.separator(item)?,
                 }
-                Key::Escape | Key::Char('q') => {
-                    if allow_quit {
-                        if self.clear {
-                            term.clear_last_lines(self.items.len())?;
-                        }
-                        return Ok(None);
+            }
+            if let Some(indicator) = paging.indicator(row) {
+                render.hint(&indicator)?;
+            }
+            region.render(&mut render)?;
+            for line in &preview {
+                render.preview_line(line)?;
+            }
+            render_help(&mut render, &self.help, show_keys, &keys)?;
+            render.end_frame()?;
+            match wait_for_event_or_update(term, self.timeout, self.handle.as_ref())? {
+                Some(Event::Key) => {}
+                Some(Event::Resize) => {
+                    render.clear()?;
+                    if let (Some(ref prompt), false) = (&prompt, echo) {
+                        render.prompt(prompt)?;
+                    }
+                    continue;
+                }
+                Some(Event::Timeout) => {
+                    if self.clear {
+                        render.clear()?;
                     }
+                    if default >= items.len() {
+                        return Err(timed_out());
+                    }
+                    self.finish_selection(&mut render, prompt.as_deref(), &items[default])?;
+                    return Ok(Some(default));
                 }
-                Key::ArrowUp | Key::Char('k') => {
+                None => {
+                    let updates = self.handle.iter().flat_map(|handle| handle.take_updates());
+                    for update in updates {
+                        match update {
+                            Update::AddItem(item) => {
+                                let mut new_items = vec![item];
+                                hotkeys.extend(self.strip_hotkeys(&mut new_items));
+                                items.extend(new_items);
+                                kinds.push(ItemKind::Enabled);
+                            }
+                            Update::SetItems(mut new_items) => {
+                                hotkeys = self.strip_hotkeys(&mut new_items);
+                                kinds = vec![ItemKind::Enabled; new_items.len()];
+                                items = new_items;
+                            }
+                            Update::SetPrompt(text) => {
+                                render.clear()?;
+                                if !echo {
+                                    render.prompt(&text)?;
+                                }
+                                prompt = Some(text);
+                            }
+                            Update::Suspend(suspension) => {
+                                let alternate_screen = self.alternate_screen;
+                                suspend_prompt(&mut render, &mouse, alternate_screen, suspension)?;
+                                if let (Some(ref prompt), false) = (&prompt, echo) {
+                                    render.prompt(prompt)?;
+                                }
+                            }
+                            update => region.apply(update),
+                        }
+                    }
                     if sel == !0 {
-                        sel = self.items.len() - 1;
+                        let query = query.as_ref().map_or("", |query| query.as_str());
+                        visible = filter_items(&items, &kinds, query);
                     } else {
-                        sel = ((sel as i64 - 1 + self.items.len() as i64)
-                            % (self.items.len() as i64)) as usize;
+                        refilter_items(&items, &kinds, &query, &mut visible, &mut sel);
                     }
+                    continue;
                 }
-                Key::ArrowLeft | Key::Char('h') => {
-                    if self.paged {
-                        if page == 0 {
-                            page = pages - 1;
-                        } else {
-                            page = page - 1;
-                        }
-                        sel = page * capacity;
+            }
+            let key = render.clear_interrupted(read_key(term))?;
+            let visible_kinds: Vec<_> = visible.iter().map(|&idx| kinds[idx]).collect();
+            let searching = query.is_some();
+            let mut refilter = false;
+            let hotkey = match key {
+                Key::Char(c) if !searching => find_hotkey(&hotkeys, &kinds, c),
+                _ => None,
+            };
+            let mut action = match key {
+                _ if hotkey.is_some() => {
+                    sel = hotkey.unwrap_or(sel);
+                    Some(Action::Confirm)
+                }
+                Key::Char(c) if searching => {
+                    if let Some(ref mut query) = query {
+                        query.push(c);
                     }
+                    refilter = true;
+                    None
                 }
-                Key::ArrowRight | Key::Char('l') => {
-                    if self.paged {
-                        if page == pages - 1 {
-                            page = 0;
-                        } else {
-                            page = page + 1;
+                Key::Backspace if searching => {
+                    if let Some(ref mut query) = query {
+                        pop_grapheme(query);
+                    }
+                    refilter = true;
+                    None
+                }
+                ref key => self.keymap.action(key),
+            };
+            match read_mouse(term, &key)? {
+                Some(Mouse::ScrollUp) => action = Some(Action::PageUp),
+                Some(Mouse::ScrollDown) => action = Some(Action::PageDown),
+                Some(Mouse::Click { row, col }) => {
+                    let top = query.is_some() as usize + self.header.is_some() as usize;
+                    let cell = if cols > 1 { col / cell_width } else { 0 };
+                    let clicked = render
+                        .frame_line_at(row)?
+                        .and_then(|line| line.checked_sub(top))
+                        .filter(|&line| line < paging.capacity() && cell < cols)
+                        .and_then(|line| visible.get((paging.offset() + line) * cols + cell))
+                        .cloned()
+                        .filter(|&idx| kinds[idx] == ItemKind::Enabled);
+                    if let Some(idx) = clicked {
+                        sel = idx;
+                        if clicks.click(idx) {
+                            action = Some(Action::Confirm);
                         }
-                        sel = page * capacity;
                     }
                 }
-
-                Key::Enter | Key::Char(' ') if sel != !0 => {
+                None => {}
+            }
+            let digit = match key {
+                Key::Char(c) if self.numbered && !searching && action.is_none() => c.to_digit(10),
+                _ => None,
+            };
+            if let Some(digit) = digit {
+                match self.read_item_number(term, &mut render, digit as usize, &kinds)? {
+                    Some(idx) => {
+                        sel = idx;
+                        action = Some(Action::Confirm);
+                    }
+                    None => continue,
+                }
+            }
+            let page_row = action.and_then(|action| paging.handle_action(action, row));
+            if let Some(new_row) = page_row {
+                let new_pos = (new_row * cols + pos.unwrap_or(0) % cols).min(visible.len() - 1);
+                let forward = pos.is_none_or(|pos| new_pos >= pos);
+                if let Some(new_pos) = find_enabled(&visible_kinds, new_pos, forward) {
+                    sel = visible[new_pos];
+                }
+                continue;
+            }
+            let len = visible.len();
+            let step = match action {
+                Some(Action::Up) | Some(Action::Down) => cols,
+                _ => 1,
+            };
+            match action {
+                Some(Action::Down) | Some(Action::Right) => {
+                    let start = pos.map_or(0, |pos| (pos + step) % len);
+                    if let Some(new_pos) = find_enabled(&visible_kinds, start, true) {
+                        sel = visible[new_pos];
+                    }
+                }
+                Some(Action::Up) | Some(Action::Left) => {
+                    let start =
+                        pos.map_or(len.wrapping_sub(1), |pos| (pos + len * step - step) % len);
+                    if let Some(new_pos) = find_enabled(&visible_kinds, start, false) {
+                        sel = visible[new_pos];
+                    }
+                }
+                Some(Action::Cancel) if searching => {
+                    query = None;
+                    visible = (0..items.len()).collect();
+                }
+                Some(Action::Search) if self.searchable => {
+                    query = Some(String::new());
+                }
+                Some(Action::Cancel) if allow_quit => {
                     if self.clear {
-                        render.clear()?;
+                        render.clear_preserve_prompt()?;
                     }
-                    if let Some(ref prompt) = self.prompt {
-                        render.single_prompt_selection(prompt, &self.items[sel])?;
+                    return Ok(None);
+                }
+                Some(Action::Help) => {
+                    show_keys = !show_keys;
+                }
+                Some(Action::Refresh) if self.loader.is_some() => {
+                    let (mut new_items, new_kinds) =
+                        load_items(&mut render, &self.items, &self.kinds, &self.loader)?;
+                    hotkeys = self.strip_hotkeys(&mut new_items);
+                    items = new_items;
+                    kinds = new_kinds;
+                    refilter = true;
+                }
+                Some(Action::Confirm) | Some(Action::Toggle)
+                    if sel != !0 && kinds[sel] == ItemKind::Enabled =>
+                {
+                    if self.clear {
+                        render.clear()?;
                     }
+                    self.finish_selection(&mut render, prompt.as_deref(), &items[sel])?;
                     return Ok(Some(sel));
                 }
                 _ => {}
             }
-            if sel < page * capacity || sel >= (page + 1) * capacity {
-                page = sel / capacity;
+            if refilter {
+                refilter_items(&items, &kinds, &query, &mut visible, &mut sel);
+            }
+        }
+    }
+
+    /// Prints the items as a numbered list and reads the typed number.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        allow_quit: bool,
+        items: &[String],
+        kinds: &[ItemKind],
+    ) -> io::Result<Option<usize>> {
+        if let Some(ref header) = self.header {
+            render.hint(header)?;
+        }
+        render_numbered_items(render, items, kinds)?;
+        if let Some(ref help) = self.help {
+            render.hint(help)?;
+        }
+        let default = Some(self.default_in(items)).filter(|&idx| idx < items.len());
+        let default_str = default.map(|idx| (idx + 1).to_string());
+        loop {
+            render.input_prompt(&messages().enter_number, default_str.as_deref())?;
+            let sel = if !wait_for_key(term, self.timeout)? {
+                term.write_line("")?;
+                Some(default.ok_or_else(timed_out)?)
+            } else {
+                let input = render.clear_interrupted(read_plain_line(term))?;
+                let input = input.trim();
+                if input.is_empty() {
+                    default
+                } else if allow_quit && input == "q" {
+                    return Ok(None);
+                } else {
+                    let sel = parse_item_number(input, kinds);
+                    if sel.is_none() {
+                        render.error(&messages().invalid_number)?;
+                    }
+                    sel
+                }
+            };
+            if let Some(sel) = sel {
+                self.finish_selection(render, self.prompt.as_deref(), &items[sel])?;
+                return Ok(Some(sel));
+            }
+        }
+    }
+}
+
+/// A selection menu returning the values attached to its items.
+///
+/// Created by `Select::items_with_values`.
+pub struct SelectValues<'s, 'a: 's, T> {
+    select: &'s mut Select<'a>,
+    offset: usize,
+    values: Vec<T>,
+}
+
+impl<'s, 'a, T: Clone> SelectValues<'s, 'a, T> {
+    /// Enables user interaction and returns the value of the selected item.
+    ///
+    /// The dialog is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<T> {
+        self.interact_on(&self.select.stream.term())
+    }
+
+    /// Like `interact` but returns `None` if the user cancelled with Esc or 'q'.
+    pub fn interact_opt(&self) -> io::Result<Option<T>> {
+        self.interact_on_opt(&self.select.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<T>>
+    where
+        'a: 'static,
+        T: Send + 'static,
+    {
+        let offset = self.offset;
+        let values = self.values.clone();
+        run_detached_mut(&mut *self.select, Select::new(), move |select| {
+            SelectValues {
+                select,
+                offset,
+                values,
             }
-            render.clear_preserve_prompt(&size_vec)?;
+            .interact()
+        })
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<T> {
+        self.value(self.select.interact_on(term)?)
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<T>> {
+        match self.select.interact_on_opt(term)? {
+            Some(idx) => self.value(idx).map(Some),
+            None => Ok(None),
         }
     }
+
+    fn value(&self, idx: usize) -> io::Result<T> {
+        idx.checked_sub(self.offset)
+            .and_then(|idx| self.values.get(idx))
+            .cloned()
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "Selected item has no value")
+            })
+    }
 }
 
 impl<'a> Checkboxes<'a> {
@@ -220,20 +1192,60 @@ impl<'a> Checkboxes<'a> {
     }
 
     /// Sets a theme other than the default one.
-    pub fn with_theme(theme: &'a Theme) -> Checkboxes<'a> {
-        Checkboxes {
-            items: vec![],
-            clear: true,
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Checkboxes<'a> {
+        Checkboxes::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    ///
+    /// The resulting prompt does not borrow anything and can be stored or
+    /// returned from functions freely.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Checkboxes<'static> {
+        Checkboxes::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Checkboxes<'a> {
+        Checkboxes {
+            items: vec![],
+            kinds: vec![],
+            clear: true,
             prompt: None,
-            theme: theme,
+            theme,
+            reporter: None,
             paged: false,
+            alternate_screen: false,
+            timeout: None,
+            help: None,
+            min_selected: 0,
+            max_selected: None,
+            keymap: KeyMap::default(),
+            loader: None,
+            handle: None,
+            non_interactive: NonInteractive::Prompt,
+            assume: AssumePolicy::Interactive,
+            answer: None,
+            stream: Stream::Stderr,
         }
     }
     /// Enables or disables paging
+    ///
+    /// Menus taller than the terminal are always paged, keeping the prompt
+    /// pinned above the items even if it spans several rows; this also
+    /// shows the position indicator for menus that fit.
     pub fn paged(&mut self, val: bool) -> &mut Checkboxes<'a> {
         self.paged = val;
         self
     }
+
+    /// Shows the checkbox menu on the alternate screen of the terminal.
+    ///
+    /// The checkbox menu then has the whole terminal to itself and leaves no
+    /// trace in the scrollback: the previous screen comes back on exit and
+    /// only the summary line is printed.  The default is `false`.
+    pub fn alternate_screen(&mut self, val: bool) -> &mut Checkboxes<'a> {
+        self.alternate_screen = val;
+        self
+    }
     /// Sets the clear behavior of the checkbox menu.
     ///
     /// The default is to clear the checkbox menu.
@@ -242,20 +1254,125 @@ impl<'a> Checkboxes<'a> {
         self
     }
 
+    /// Sets whether a summary line is printed after confirming.
+    ///
+    /// The summary is only printed for menus with a prompt and is rendered
+    /// by `Theme::format_checkboxes_report`.  The default is to print it.
+    /// `report(false)` is the same as `with_report(|_| None)`; whichever is
+    /// called last wins.
+    pub fn report(&mut self, val: bool) -> &mut Checkboxes<'a> {
+        self.reporter = if val { None } else { Some(Box::new(|_| None)) };
+        self
+    }
+
+    /// Sets a function that rewrites the items shown on the report line.
+    ///
+    /// The function is given the checked items joined with `, `.  This can
+    /// e.g. mask a sensitive value or append a unit.  Returning `None`
+    /// leaves the report line out.
+    pub fn with_report<F>(&mut self, f: F) -> &mut Checkboxes<'a>
+    where
+        F: Fn(&str) -> Option<String> + Send + 'a,
+    {
+        self.reporter = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a timeout after which the prompt gives up.
+    ///
+    /// The timeout restarts with every keypress.  When it elapses an error
+    /// of kind `TimedOut` is returned.  Timeouts are currently only
+    /// supported on unix systems.
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Checkboxes<'a> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the minimum number of items that need to be checked.
+    ///
+    /// The constraint is checked when the user confirms the selection.
+    pub fn min_selected(&mut self, val: usize) -> &mut Checkboxes<'a> {
+        self.min_selected = val;
+        self
+    }
+
+    /// Sets the maximum number of items that can be checked.
+    ///
+    /// The constraint is checked when the user confirms the selection.
+    pub fn max_selected(&mut self, val: usize) -> &mut Checkboxes<'a> {
+        self.max_selected = Some(val);
+        self
+    }
+
+    /// Shows a help text below the items.
+    ///
+    /// The help is rendered as a hint by the theme.  Independently of this
+    /// the user can press `?` to toggle a list of the key bindings.
+    pub fn with_help(&mut self, help: &str) -> &mut Checkboxes<'a> {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Sets the key bindings of the menu.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut Checkboxes<'a> {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Lets `handle` change the items, prompt and the rows below the
+    /// menu while it is shown.
+    pub fn with_handle(&mut self, handle: &PromptHandle) -> &mut Checkboxes<'a> {
+        self.handle = Some(handle.clone());
+        self
+    }
+
     /// Add a single item to the selector.
     pub fn item(&mut self, item: &str) -> &mut Checkboxes<'a> {
-        self.items.push(item.to_string());
+        self.push_item(item.to_string(), ItemKind::Enabled);
         self
     }
 
     /// Adds multiple items to the selector.
     pub fn items(&mut self, items: &[&str]) -> &mut Checkboxes<'a> {
         for item in items {
-            self.items.push(item.to_string());
+            self.push_item(item.to_string(), ItemKind::Enabled);
         }
         self
     }
 
+    /// Adds an item that is shown dimmed and cannot be checked.
+    pub fn disabled_item(&mut self, item: &str) -> &mut Checkboxes<'a> {
+        self.push_item(item.to_string(), ItemKind::Disabled);
+        self
+    }
+
+    /// Adds a separator, e.g. a header for the following group of items.
+    ///
+    /// Separators take up an index like items but are skipped when
+    /// navigating.
+    pub fn separator(&mut self, text: &str) -> &mut Checkboxes<'a> {
+        self.push_item(text.to_string(), ItemKind::Separator);
+        self
+    }
+
+    /// Fetches items when the prompt opens instead of upfront.
+    ///
+    /// The menu shows a spinner while `loader` runs on another thread and
+    /// appends the returned items to the ones added with `item` and
+    /// `items`.  An error of the loader is returned from `interact`.
+    pub fn items_from<F>(&mut self, loader: F) -> &mut Checkboxes<'a>
+    where
+        F: Fn() -> io::Result<Vec<String>> + Send + Sync + 'a,
+    {
+        self.loader = Some(Box::new(loader));
+        self
+    }
+
+    fn push_item(&mut self, item: String, kind: ItemKind) {
+        self.items.push(item);
+        self.kinds.push(kind);
+    }
+
     /// Prefaces the menu with a prompt.
     ///
     /// When a prompt is set the system also prints out a confirmation after
@@ -265,136 +1382,723 @@ impl<'a> Checkboxes<'a> {
         self
     }
 
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Checkboxes<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted item for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut Checkboxes<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets what the menu does if it is not connected to a terminal.
+    ///
+    /// The default is to prompt anyway.
+    pub fn non_interactive(&mut self, policy: NonInteractive) -> &mut Checkboxes<'a> {
+        self.non_interactive = policy;
+        self
+    }
+
+    /// Sets whether the menu answers itself, e.g. for a `--yes` flag.
+    ///
+    /// The default is to ask.  The answer is no checked items, so a menu
+    /// that requires a selection always asks.
+    pub fn assume(&mut self, policy: AssumePolicy) -> &mut Checkboxes<'a> {
+        self.assume = policy;
+        self
+    }
+
+    /// Answers the prompt with the items at `indices` checked instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, indices: &[usize]) -> &mut Checkboxes<'a> {
+        self.answer = Some(indices.to_vec());
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Checkboxes<'a> {
+        self.stream = stream;
+        self
+    }
+
     /// Enables user interaction and returns the result.
     ///
     /// The user can select the items with the space bar and on enter
     /// the selected items will be returned.
     pub fn interact(&self) -> io::Result<Vec<usize>> {
-        self.interact_on(&Term::stderr())
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    ///
+    /// The prompt runs on a thread of its own, so other tasks keep running
+    /// while the user is typing.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<Vec<usize>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Checkboxes::new(), |prompt| prompt.interact())
     }
 
     /// Like `interact` but allows a specific terminal to be set.
-    pub fn interact_on(&self, term: &Term) -> io::Result<Vec<usize>> {
-        let mut page = 0;
-        let mut capacity = self.items.len();
-        if self.paged {
-            capacity = term.size().0 as usize - 1;
-        }
-        let pages = (self.items.len() / capacity) + 1;
-        let mut render = TermThemeRenderer::new(term, self.theme);
-        let mut sel = 0;
-        if let Some(ref prompt) = self.prompt {
-            render.prompt(prompt)?;
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<Vec<usize>> {
+        let prompt = self.prompt.as_deref().unwrap_or("");
+        observe(
+            prompt,
+            || self.read_answer(term),
+            |rv| Some(labels(&self.items, rv)),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<Vec<usize>> {
+        let _guard = TermGuard::new(term);
+        let default = if self.min_selected == 0 {
+            Some(vec![])
+        } else {
+            None
+        };
+        let answer = self
+            .answer
+            .clone()
+            .or_else(|| self.assume.selection(default.clone()));
+        if let Some(selections) = answer {
+            if let Some(&idx) = selections.iter().find(|&&idx| idx >= self.items.len()) {
+                return Err(invalid_answer(idx));
+            }
+            let mut render = TermThemeRenderer::new(term, &*self.theme);
+            render.set_report(self.reporter.as_ref());
+            self.report_selections(
+                &mut render,
+                self.prompt.as_deref(),
+                &self.items,
+                &selections,
+            )?;
+            return Ok(selections);
         }
-        let mut size_vec = Vec::new();
-        for items in self.items.iter().as_slice() {
-            let size = &items.len();
-            size_vec.push(size.clone());
+        if let Some(rv) = self.non_interactive.answer(term, default) {
+            return rv;
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        render.set_report(self.reporter.as_ref());
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+        if self.alternate_screen {
+            render.enter_alternate_screen()?;
+        }
+        let _cursor = HiddenCursor::new(term)?;
+        let mouse = MouseCapture::new(term)?;
+        let _open = self.handle.as_ref().map(|handle| handle.open());
+        let mut clicks = Clicks::default();
+        let mut prompt = self.prompt.clone();
+        if let Some(ref prompt) = prompt {
+            render.prompt(prompt)?;
         }
-        let mut checked: Vec<_> = repeat(false).take(self.items.len()).collect();
+        let (mut items, mut kinds) =
+            load_items(&mut render, &self.items, &self.kinds, &self.loader)?;
+        let mut paging = Paging::new(items.len(), self.paged);
+        let mut sel = find_enabled(&kinds, 0, true).unwrap_or(0);
+        let mut show_keys = false;
+        let keys = self.keymap.help_lines(&[
+            Action::Up,
+            Action::Down,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Toggle,
+            Action::ToggleAll,
+            Action::Invert,
+            Action::Confirm,
+            Action::Cancel,
+            Action::Help,
+        ]);
+        let mut checked: Vec<_> = vec![false; items.len()];
+        let mut error: Option<String> = None;
+        let mut region = Region::default();
         loop {
-            for (idx, item) in self
-                .items
+            let reserved = render.prompt_rows()
+                + error.is_some() as usize
+                + region.height()
+                + help_rows(&self.help, show_keys, &keys);
+            paging.update(term, reserved, sel);
+            render.begin_frame();
+            paging.set_items(items.len());
+            for (idx, item) in items
                 .iter()
                 .enumerate()
-                .skip(page * capacity)
-                .take(capacity)
+                .skip(paging.offset())
+                .take(paging.capacity())
             {
-                render.selection(
-                    item,
-                    match (checked[idx], sel == idx) {
-                        (true, true) => SelectionStyle::CheckboxCheckedSelected,
-                        (true, false) => SelectionStyle::CheckboxCheckedUnselected,
-                        (false, true) => SelectionStyle::CheckboxUncheckedSelected,
-                        (false, false) => SelectionStyle::CheckboxUncheckedUnselected,
-                    },
-                )?;
-            }
-            match term.read_key()? {
-                Key::ArrowDown | Key::Char('j') => {
-                    if sel == !0 {
-                        sel = 0;
-                    } else {
-                        sel = (sel as u64 + 1).rem(self.items.len() as u64) as usize;
+                match kinds[idx] {
+                    ItemKind::Enabled => render.selection(
+                        item,
+                        match (checked[idx], sel == idx) {
+                            (true, true) => SelectionStyle::CheckboxCheckedSelected,
+                            (true, false) => SelectionStyle::CheckboxCheckedUnselected,
+                            (false, true) => SelectionStyle::CheckboxUncheckedSelected,
+                            (false, false) => SelectionStyle::CheckboxUncheckedUnselected,
+                        },
+                    )?,
+                    ItemKind::Disabled => {
+                        render.disabled_item(item, SelectionStyle::CheckboxUncheckedUnselected)?
                     }
+                    ItemKind::Separator => render.separator(item)?,
                 }
-                Key::ArrowUp | Key::Char('k') => {
-                    if sel == !0 {
-                        sel = self.items.len() - 1;
-                    } else {
-                        sel = ((sel as i64 - 1 + self.items.len() as i64)
-                            % (self.items.len() as i64)) as usize;
+            }
+            if let Some(indicator) = paging.indicator(sel) {
+                render.hint(&indicator)?;
+            }
+            region.render(&mut render)?;
+            if let Some(ref error) = error {
+                render.error(error)?;
+            }
+            render_help(&mut render, &self.help, show_keys, &keys)?;
+            render.end_frame()?;
+            match wait_for_event_or_update(term, self.timeout, self.handle.as_ref())? {
+                Some(Event::Key) => {}
+                Some(Event::Resize) => {
+                    render.clear()?;
+                    if let Some(ref prompt) = prompt {
+                        render.prompt(prompt)?;
                     }
+                    continue;
                 }
-                Key::ArrowLeft | Key::Char('h') => {
-                    if self.paged {
-                        if page == 0 {
-                            page = pages - 1;
-                        } else {
-                            page = page - 1;
+                Some(Event::Timeout) => {
+                    if self.clear {
+                        render.clear()?;
+                    }
+                    return Err(timed_out());
+                }
+                None => {
+                    let updates = self.handle.iter().flat_map(|handle| handle.take_updates());
+                    for update in updates {
+                        match update {
+                            Update::AddItem(item) => {
+                                items.push(item);
+                                kinds.push(ItemKind::Enabled);
+                                checked.push(false);
+                            }
+                            Update::SetItems(new_items) => {
+                                kinds = vec![ItemKind::Enabled; new_items.len()];
+                                checked = vec![false; new_items.len()];
+                                items = new_items;
+                            }
+                            Update::SetPrompt(text) => {
+                                render.clear()?;
+                                render.prompt(&text)?;
+                                prompt = Some(text);
+                            }
+                            Update::Suspend(suspension) => {
+                                let alternate_screen = self.alternate_screen;
+                                suspend_prompt(&mut render, &mouse, alternate_screen, suspension)?;
+                                if let Some(ref prompt) = prompt {
+                                    render.prompt(prompt)?;
+                                }
+                            }
+                            update => region.apply(update),
                         }
-                        sel = page * capacity;
                     }
+                    if kinds.get(sel) != Some(&ItemKind::Enabled) {
+                        sel = find_enabled(&kinds, 0, true).unwrap_or(0);
+                    }
+                    continue;
                 }
-                Key::ArrowRight | Key::Char('l') => {
-                    if self.paged {
-                        if page == pages - 1 {
-                            page = 0;
-                        } else {
-                            page = page + 1;
+            }
+            error = None;
+            let key = render.clear_interrupted(read_key(term))?;
+            let mut action = self.keymap.action(&key);
+            match read_mouse(term, &key)? {
+                Some(Mouse::ScrollUp) => action = Some(Action::PageUp),
+                Some(Mouse::ScrollDown) => action = Some(Action::PageDown),
+                Some(Mouse::Click { row, .. }) => {
+                    let clicked = render
+                        .frame_line_at(row)?
+                        .filter(|&line| line < paging.capacity())
+                        .map(|line| paging.offset() + line)
+                        .filter(|&idx| kinds.get(idx) == Some(&ItemKind::Enabled));
+                    if let Some(idx) = clicked {
+                        sel = idx;
+                        if clicks.click(idx) {
+                            action = Some(Action::Toggle);
                         }
-                        sel = page * capacity;
                     }
                 }
-                Key::Char(' ') => {
+                None => {}
+            }
+            if let Some(new_sel) = action.and_then(|action| paging.handle_action(action, sel)) {
+                let forward = sel == !0 || new_sel >= sel;
+                sel = find_enabled(&kinds, new_sel, forward).unwrap_or(sel);
+                continue;
+            }
+            match action {
+                Some(Action::Down) => {
+                    let start = if sel == !0 { 0 } else { sel + 1 };
+                    sel = find_enabled(&kinds, start, true).unwrap_or(sel);
+                }
+                Some(Action::Up) => {
+                    let start = if sel == !0 { items.len() } else { sel };
+                    sel = find_enabled(&kinds, (start + items.len()).wrapping_sub(1), false)
+                        .unwrap_or(sel);
+                }
+                Some(Action::Toggle) if kinds.get(sel) == Some(&ItemKind::Enabled) => {
                     checked[sel] = !checked[sel];
                 }
-                Key::Escape => {
+                Some(Action::ToggleAll) => {
+                    let all = self
+                        .kinds
+                        .iter()
+                        .zip(&checked)
+                        .all(|(&kind, &checked)| checked || kind != ItemKind::Enabled);
+                    for (idx, kind) in kinds.iter().enumerate() {
+                        if *kind == ItemKind::Enabled {
+                            checked[idx] = !all;
+                        }
+                    }
+                }
+                Some(Action::Invert) => {
+                    for (idx, kind) in kinds.iter().enumerate() {
+                        if *kind == ItemKind::Enabled {
+                            checked[idx] = !checked[idx];
+                        }
+                    }
+                }
+                Some(Action::Help) => {
+                    show_keys = !show_keys;
+                }
+                Some(Action::Cancel) if self.min_selected > 0 => {
+                    error = self.check_count(0);
+                }
+                Some(Action::Cancel) => {
                     if self.clear {
                         render.clear()?;
                     }
-                    if let Some(ref prompt) = self.prompt {
-                        render.multi_prompt_selection(prompt, &[][..])?;
-                    }
+                    self.report_selections(&mut render, prompt.as_deref(), &items, &[])?;
                     return Ok(vec![]);
                 }
-                Key::Enter => {
+                Some(Action::Confirm) => {
+                    error = self.check_count(checked.iter().filter(|&&x| x).count());
+                    if error.is_some() {
+                        continue;
+                    }
                     if self.clear {
                         render.clear()?;
                     }
-                    if let Some(ref prompt) = self.prompt {
-                        let mut selections: Vec<_> = checked
-                            .iter()
-                            .enumerate()
-                            .filter_map(|(idx, &checked)| {
-                                if checked {
-                                    Some(self.items[idx].as_str())
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        render.multi_prompt_selection(prompt, &selections[..])?;
-                    }
-                    return Ok(checked
+                    let selections: Vec<_> = checked
                         .into_iter()
                         .enumerate()
                         .filter_map(|(idx, checked)| if checked { Some(idx) } else { None })
-                        .collect());
+                        .collect();
+                    self.report_selections(&mut render, prompt.as_deref(), &items, &selections)?;
+                    return Ok(selections);
                 }
                 _ => {}
             }
-            if sel < page * capacity || sel >= (page + 1) * capacity {
-                page = sel / capacity;
+        }
+    }
+
+    /// Prints the items as a numbered list and reads the typed numbers.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<Vec<usize>> {
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        let (items, kinds) = load_items(render, &self.items, &self.kinds, &self.loader)?;
+        render_numbered_items(render, &items, &kinds)?;
+        if let Some(ref help) = self.help {
+            render.hint(help)?;
+        }
+        loop {
+            render.input_prompt(&messages().enter_numbers, None)?;
+            if !wait_for_key(term, self.timeout)? {
+                term.write_line("")?;
+                return Err(timed_out());
             }
-            render.clear_preserve_prompt(&size_vec)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let mut selections = vec![];
+            for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+                if part.is_empty() {
+                    continue;
+                }
+                match parse_item_number(part, &kinds) {
+                    Some(idx) => selections.push(idx),
+                    None => {
+                        selections.clear();
+                        render.error(&fill(&messages().invalid_numbers, &[("input", &part)]))?;
+                        break;
+                    }
+                }
+            }
+            if selections.is_empty() && !input.trim().is_empty() {
+                continue;
+            }
+            selections.sort();
+            selections.dedup();
+            if let Some(err) = self.check_count(selections.len()) {
+                render.error(&err)?;
+                continue;
+            }
+            self.report_selections(render, self.prompt.as_deref(), &items, &selections)?;
+            return Ok(selections);
+        }
+    }
+
+    /// Prints the summary line for the checked items.
+    fn report_selections(
+        &self,
+        render: &mut TermThemeRenderer,
+        prompt: Option<&str>,
+        items: &[String],
+        selections: &[usize],
+    ) -> io::Result<()> {
+        render.leave_alternate_screen()?;
+        match prompt {
+            Some(prompt) => {
+                let items: Vec<_> = selections.iter().map(|&idx| items[idx].as_str()).collect();
+                render.checkboxes_report(prompt, &items)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error message if `count` violates the selection limits.
+    fn check_count(&self, count: usize) -> Option<String> {
+        let messages = messages();
+        let (text, limit) = match self.max_selected {
+            _ if count < self.min_selected => (&messages.select_at_least, self.min_selected),
+            Some(max) if count > max => (&messages.select_at_most, max),
+            _ => return None,
+        };
+        Some(fill(
+            text,
+            &[("count", &limit), ("items", &messages.items(limit))],
+        ))
+    }
+}
+
+/// Returns the static items followed by the ones of the loader.
+fn load_items(
+    render: &mut TermThemeRenderer,
+    items: &[String],
+    kinds: &[ItemKind],
+    loader: &Option<ItemLoader>,
+) -> io::Result<(Vec<String>, Vec<ItemKind>)> {
+    let mut items = items.to_vec();
+    let mut kinds = kinds.to_vec();
+    if let Some(ref loader) = *loader {
+        let loaded = load_with_spinner(render, loader)?;
+        kinds.extend(loaded.iter().map(|_| ItemKind::Enabled));
+        items.extend(loaded);
+    }
+    Ok((items, kinds))
+}
+
+/// Runs `loader` while a spinner is shown in place of the menu.
+///
+/// The accessible mode shows the loading message once instead.
+fn load_with_spinner(
+    render: &mut TermThemeRenderer,
+    loader: &ItemLoader,
+) -> io::Result<Vec<String>> {
+    let message = messages().loading.clone();
+    let loaded = spin_while(loader, |tick| {
+        if render.is_accessible() && tick > 0 {
+            return Ok(());
+        }
+        render.begin_frame();
+        if render.is_accessible() {
+            render.hint(&message)?;
+        } else {
+            render.spinner(&message, tick)?;
+        }
+        render.end_frame()
+    })?;
+    render.clear_interrupted(loaded)
+}
+
+/// Returns the number of lines `render_help` renders.
+/// Runs a shell command and returns the lines of its output.
+fn run_command(command: &str) -> io::Result<Vec<String>> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let output = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().find(|line| !line.trim().is_empty());
+        return Err(io::Error::other(format!(
+            "{} failed: {}",
+            command,
+            reason.map_or_else(|| output.status.to_string(), |line| line.trim().to_string())
+        )));
+    }
+    Ok(output_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Splits the output of a tool into items without styling and blank lines.
+fn output_lines(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|line| strip_ansi_codes(line).trim_end().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// The error for an injected answer that is not an item.
+fn invalid_answer(idx: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("No item {} to answer with", idx),
+    )
+}
+
+fn help_rows(help: &Option<String>, show_keys: bool, keys: &[String]) -> usize {
+    help.is_some() as usize + if show_keys { keys.len() } else { 0 }
+}
+
+/// Renders the help text and, if toggled on, the key bindings of a menu.
+fn render_help(
+    render: &mut TermThemeRenderer,
+    help: &Option<String>,
+    show_keys: bool,
+    keys: &[String],
+) -> io::Result<()> {
+    if let Some(ref help) = *help {
+        render.hint(help)?;
+    }
+    if show_keys {
+        for key in keys {
+            render.hint(key)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the number of columns and the width of a cell for a grid of
+/// `items` that fits into `width` terminal columns.
+fn grid_layout(theme: &dyn Theme, items: &[String], width: usize) -> (usize, usize) {
+    let cell_width = items
+        .iter()
+        .map(|item| {
+            let mut buf = String::new();
+            let _ = theme.format_selection(&mut buf, item, SelectionStyle::MenuSelected);
+            measure_text_width(&buf)
+        })
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let cols = ((width + 2) / cell_width).clamp(1, items.len().max(1));
+    (cols, cell_width)
+}
+
+/// Splits the `&` hotkey marker off an item.
+fn parse_hotkey(item: &str) -> (String, Hotkey) {
+    let mut text = String::with_capacity(item.len());
+    let mut hotkey = None;
+    let mut chars = item.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('&', Some('&')) => {
+                chars.next();
+                text.push('&');
+            }
+            ('&', Some(key)) if hotkey.is_none() => hotkey = Some((text.len(), key)),
+            _ => text.push(c),
+        }
+    }
+    (text, hotkey)
+}
+
+/// Returns the enabled item whose hotkey is `c`, ignoring case.
+fn find_hotkey(hotkeys: &[Hotkey], kinds: &[ItemKind], c: char) -> Option<usize> {
+    hotkeys
+        .iter()
+        .zip(kinds)
+        .position(|(hotkey, &kind)| match *hotkey {
+            Some((_, key)) => kind == ItemKind::Enabled && key.to_lowercase().eq(c.to_lowercase()),
+            None => false,
+        })
+}
+
+/// Styles the hotkey letters in the labels of the items.
+fn mark_hotkeys(
+    theme: &dyn Theme,
+    labels: &mut [String],
+    hotkeys: &[Hotkey],
+    shift: usize,
+) -> fmt::Result {
+    for (label, hotkey) in labels.iter_mut().zip(hotkeys) {
+        if let Some((pos, key)) = *hotkey {
+            let (pos, end) = (pos + shift, pos + shift + key.len_utf8());
+            let mut marked = label[..pos].to_string();
+            theme.format_hotkey(&mut marked, key)?;
+            marked.push_str(&label[end..]);
+            *label = marked;
+        }
+    }
+    Ok(())
+}
+
+/// Formats an item as a cell of the grid layout.
+fn format_cell(
+    theme: &dyn Theme,
+    item: &str,
+    kind: ItemKind,
+    span: Option<(usize, usize)>,
+    selected: bool,
+) -> Result<String, fmt::Error> {
+    let style = if selected {
+        SelectionStyle::MenuSelected
+    } else {
+        SelectionStyle::MenuUnselected
+    };
+    let mut buf = String::new();
+    match kind {
+        ItemKind::Enabled => match span {
+            Some((start, end)) if start < end => {
+                let mut highlighted = item[..start].to_string();
+                theme.format_search_match(&mut highlighted, &item[start..end])?;
+                highlighted.push_str(&item[end..]);
+                theme.format_selection(&mut buf, &highlighted, style)?;
+            }
+            _ => theme.format_selection(&mut buf, item, style)?,
+        },
+        ItemKind::Disabled => theme.format_disabled_item(&mut buf, item, style)?,
+        ItemKind::Separator => theme.format_separator(&mut buf, item)?,
+    }
+    Ok(buf)
+}
+
+/// Updates the visible items after the query or the items changed and
+/// moves the selection to the first visible item if it got hidden.
+fn refilter_items(
+    items: &[String],
+    kinds: &[ItemKind],
+    query: &Option<String>,
+    visible: &mut Vec<usize>,
+    sel: &mut usize,
+) {
+    let query = query.as_ref().map_or("", |query| query.as_str());
+    *visible = filter_items(items, kinds, query);
+    if !visible.contains(sel) {
+        let visible_kinds: Vec<_> = visible.iter().map(|&idx| kinds[idx]).collect();
+        *sel = find_enabled(&visible_kinds, 0, true).map_or(!0, |pos| visible[pos]);
+    }
+}
+
+/// Returns the indices of the items matching a search query.
+fn filter_items(items: &[String], kinds: &[ItemKind], query: &str) -> Vec<usize> {
+    (0..items.len())
+        .filter(|&idx| {
+            kinds[idx] != ItemKind::Separator && find_match(&items[idx], query).is_some()
+        })
+        .collect()
+}
+
+/// Finds the byte range of the first case-insensitive occurrence of `query`.
+fn find_match(text: &str, query: &str) -> Option<(usize, usize)> {
+    let eq = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    for (start, _) in text.char_indices() {
+        let mut chars = text[start..].char_indices();
+        let mut end = start;
+        let mut matched = true;
+        for q in query.chars() {
+            match chars.next() {
+                Some((offset, c)) if eq(c, q) => end = start + offset + c.len_utf8(),
+                _ => {
+                    matched = false;
+                    break;
+                }
+            }
+        }
+        if matched {
+            return Some((start, end));
+        }
+    }
+    if query.is_empty() {
+        Some((0, 0))
+    } else {
+        None
+    }
+}
+
+/// Finds the first selectable item from `start` on, wrapping around.
+fn find_enabled(kinds: &[ItemKind], start: usize, forward: bool) -> Option<usize> {
+    let len = kinds.len();
+    (0..len)
+        .map(|step| {
+            if forward {
+                (start + step) % len
+            } else {
+                (start + len * 2 - step) % len
+            }
+        })
+        .find(|&idx| kinds[idx] == ItemKind::Enabled)
+}
+
+/// Renders the items as a numbered list for the accessible mode.
+///
+/// Only selectable items get a number; it is the one-based item index.
+fn render_numbered_items(
+    render: &mut TermThemeRenderer,
+    items: &[String],
+    kinds: &[ItemKind],
+) -> io::Result<()> {
+    for (idx, (item, kind)) in items.iter().zip(kinds).enumerate() {
+        match *kind {
+            ItemKind::Enabled => render.numbered_item(idx + 1, item)?,
+            ItemKind::Disabled => render.disabled_item(item, SelectionStyle::MenuUnselected)?,
+            ItemKind::Separator => render.separator(item)?,
         }
     }
+    Ok(())
+}
+
+/// Parses a one-based item number as typed in the accessible mode.
+fn parse_item_number(input: &str, kinds: &[ItemKind]) -> Option<usize> {
+    match input.trim().parse::<usize>() {
+        Ok(number) if number >= 1 && kinds.get(number - 1) == Some(&ItemKind::Enabled) => {
+            Some(number - 1)
+        }
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use virtual_term::VirtualTerm;
 
     #[test]
     fn test_str() {
@@ -421,6 +2125,231 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_owned_theme() {
+        use theme::ColorfulTheme;
+
+        fn assert_send_static<T: Send + 'static>(_: T) {}
+
+        let mut select = Select::with_owned_theme(ColorfulTheme::default());
+        select.item("a").item("b");
+        assert_send_static(select);
+        assert_send_static(Select::new());
+        assert_send_static(Checkboxes::new());
+        assert_send_static(Checkboxes::with_owned_theme(ColorfulTheme::default()));
+    }
+
+    #[test]
+    fn test_parse_item_number() {
+        let kinds = [ItemKind::Enabled, ItemKind::Disabled, ItemKind::Enabled];
+        assert_eq!(parse_item_number("1", &kinds), Some(0));
+        assert_eq!(parse_item_number(" 3 ", &kinds), Some(2));
+        assert_eq!(parse_item_number("2", &kinds), None);
+        assert_eq!(parse_item_number("0", &kinds), None);
+        assert_eq!(parse_item_number("4", &kinds), None);
+        assert_eq!(parse_item_number("x", &kinds), None);
+    }
+
+    #[test]
+    fn test_find_match() {
+        assert_eq!(find_match("Ice Cream", "cre"), Some((4, 7)));
+        assert_eq!(find_match("Ice Cream", ""), Some((0, 0)));
+        assert_eq!(find_match("Ice Cream", "x"), None);
+        assert_eq!(find_match("Größe", "SSE"), None);
+        assert_eq!(find_match("Größe", "ÖSS"), None);
+        assert_eq!(find_match("GRÖSSE", "össe"), Some((2, 7)));
+    }
+
+    #[test]
+    fn test_find_enabled() {
+        let kinds = [
+            ItemKind::Separator,
+            ItemKind::Enabled,
+            ItemKind::Disabled,
+            ItemKind::Enabled,
+        ];
+        assert_eq!(find_enabled(&kinds, 0, true), Some(1));
+        assert_eq!(find_enabled(&kinds, 2, true), Some(3));
+        assert_eq!(find_enabled(&kinds, 4, true), Some(1));
+        assert_eq!(find_enabled(&kinds, 2, false), Some(1));
+        assert_eq!(find_enabled(&kinds, 0, false), Some(3));
+        assert_eq!(find_enabled(&[ItemKind::Separator], 0, true), None);
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        use theme::SimpleTheme;
+
+        let items: Vec<_> = ["de", "fr", "nl"].iter().map(|s| s.to_string()).collect();
+        // "> de" plus two columns of spacing
+        assert_eq!(grid_layout(&SimpleTheme, &items, 80), (3, 6));
+        assert_eq!(grid_layout(&SimpleTheme, &items, 10), (2, 6));
+        assert_eq!(grid_layout(&SimpleTheme, &items, 3), (1, 6));
+    }
+
+    #[test]
+    fn test_numbered_labels() {
+        let mut select = Select::new();
+        select.numbered(true);
+        let items: Vec<String> = (0..10).map(|idx| idx.to_string()).collect();
+        let mut kinds = vec![ItemKind::Enabled; 10];
+        kinds[0] = ItemKind::Disabled;
+        let (labels, shift) = select.labels(&items, &kinds);
+        assert_eq!(shift, 4);
+        assert_eq!(labels[0], "    0");
+        assert_eq!(labels[1], " 2. 1");
+        assert_eq!(labels[9], "10. 9");
+    }
+
+    #[test]
+    fn test_items_with_values() {
+        let mut select = Select::new();
+        select.item("none");
+        let values = select.items_with_values(&[("http", 80), ("https", 443)]);
+        assert_eq!(values.value(2).unwrap(), 443);
+        assert!(values.value(0).is_err());
+        assert_eq!(select.items, vec!["none", "http", "https"]);
+    }
+
+    #[test]
+    fn test_parse_hotkey() {
+        assert_eq!(parse_hotkey("&Install"), ("Install".into(), Some((0, 'I'))));
+        assert_eq!(
+            parse_hotkey("Salt && &Pepper"),
+            ("Salt & Pepper".into(), Some((7, 'P')))
+        );
+        assert_eq!(parse_hotkey("plain"), ("plain".into(), None));
+        let hotkeys = [Some((0, 'I')), None, Some((0, 'Q'))];
+        let kinds = [ItemKind::Enabled; 3];
+        assert_eq!(find_hotkey(&hotkeys, &kinds, 'q'), Some(2));
+        assert_eq!(find_hotkey(&hotkeys, &kinds, 'x'), None);
+    }
+
+    #[test]
+    fn test_remember_key() {
+        let items = vec!["eu".to_string(), "us".to_string()];
+        let mut select = Select::new();
+        select.items(&items).default(0).remember_key("test-region");
+        assert_eq!(select.default_in(&items), 0);
+        state::remember("test-region", "us");
+        assert_eq!(select.default_in(&items), 1);
+        state::remember("test-region", "asia");
+        assert_eq!(select.default_in(&items), 0);
+    }
+
+    #[test]
+    fn test_default_from_env() {
+        env::set_var("DIALOGUER_TEST_REGION", "eu-west-1");
+        let items = vec!["us-east-1".to_string(), "eu-west-1".to_string()];
+        let mut select = Select::new();
+        select.default(0).default_from_env("DIALOGUER_TEST_REGION");
+        select.items(&items);
+        assert_eq!(select.default_in(&items), 1);
+        select.default_from_env("DIALOGUER_TEST_UNSET");
+        assert_eq!(select.default_in(&items), 0);
+        select.default_from_env("DIALOGUER_TEST_REGION");
+        env::set_var("DIALOGUER_TEST_REGION", "ap-south-1");
+        assert_eq!(select.default_in(&items), 0);
+    }
+
+    #[test]
+    fn test_check_count() {
+        let mut checkboxes = Checkboxes::new();
+        checkboxes.min_selected(1).max_selected(2);
+        assert!(checkboxes.check_count(0).is_some());
+        assert!(checkboxes.check_count(1).is_none());
+        assert!(checkboxes.check_count(2).is_none());
+        assert!(checkboxes.check_count(3).is_some());
+    }
+
+    #[test]
+    fn test_output_lines() {
+        let output = "* \x1b[32mmain\x1b[m\r\n\n  feature\n";
+        assert_eq!(output_lines(output), vec!["* main", "  feature"]);
+        let mut select = Select::new();
+        select.items_from_reader(output.as_bytes()).unwrap();
+        assert_eq!(select.items, vec!["* main", "  feature"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command() {
+        assert_eq!(run_command("printf 'a\\nb\\n'").unwrap(), vec!["a", "b"]);
+        let err = run_command("echo oops >&2; exit 3").unwrap_err();
+        assert_eq!(err.to_string(), "echo oops >&2; exit 3 failed: oops");
+    }
+
+    #[test]
+    fn test_with_answer() {
+        let term = VirtualTerm::new(10, 40);
+        let mut select = Select::new();
+        select
+            .with_prompt("Region")
+            .items(&["eu", "us"])
+            .with_answer(1);
+        assert_eq!(select.interact_on(&term).unwrap(), 1);
+        assert_eq!(term.screen(), "Region: us");
+        select.with_answer(2);
+        assert_eq!(
+            select.interact_on(&term).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_echo_highlighted() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[Key::ArrowDown, Key::Enter]);
+        let rv = Select::new()
+            .with_prompt("Region")
+            .items(&["eu", "us"])
+            .default(0)
+            .echo_highlighted(true)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(rv, 1);
+        assert_eq!(
+            term.frames(),
+            vec!["Region: eu\n> eu\n  us", "Region: us\n  eu\n> us"]
+        );
+        assert_eq!(term.screen(), "Region: us");
+    }
+
+    #[test]
+    fn test_with_report() {
+        let mut term = VirtualTerm::new(10, 40);
+        term.keys(&[Key::ArrowDown, Key::Enter]);
+        let rv = Select::new()
+            .with_prompt("Zone")
+            .items(&["eu", "us"])
+            .default(0)
+            .with_report(|item| Some(format!("{}-west-1", item)))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(rv, 1);
+        assert_eq!(term.screen(), "Zone: us-west-1");
+        let term = VirtualTerm::new(10, 40);
+        let rv = Checkboxes::new()
+            .with_prompt("Zones")
+            .items(&["eu", "us"])
+            .with_answer(&[0])
+            .with_report(|_| None)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(rv, vec![0]);
+        assert_eq!(term.screen(), "");
+        let term = VirtualTerm::new(10, 40);
+        Checkboxes::new()
+            .with_prompt("Zones")
+            .items(&["eu", "us"])
+            .with_answer(&[0])
+            .with_report(|_| None)
+            .report(true)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(term.screen(), "Zones: eu");
+    }
+
     #[test]
     fn test_ref_str() {
         let a = "a";
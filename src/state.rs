@@ -0,0 +1,299 @@
+//! Answers remembered between prompts and, with a `StateStore`, between
+//! runs of the application.
+#[cfg(feature = "serde")]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+#[cfg(feature = "serde")]
+use std::env;
+#[cfg(feature = "serde")]
+use std::fs;
+#[cfg(feature = "serde")]
+use std::io::{self, Write};
+#[cfg(feature = "serde")]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(feature = "serde")]
+use tempfile::NamedTempFile;
+
+static STATE: Mutex<Option<HashMap<String, Vec<String>>>> = Mutex::new(None);
+
+#[cfg(feature = "serde")]
+static STORE: Mutex<Option<StateStore>> = Mutex::new(None);
+
+/// The stored values by key, the most recent one last.
+#[cfg(feature = "serde")]
+type Values = BTreeMap<String, Vec<String>>;
+
+/// Keeps the state of prompts in a JSON file across runs.
+///
+/// Prompts use the store set with `set_state_store` for the answers they
+/// remember: the last pick of a `Select` or answer of an `Input` with a
+/// `remember_key`, and the values of a `HistoryCompleter::from_state`.
+/// Every key holds a list of values, the most recent one last, so the
+/// store also keeps histories and recently used paths.  An empty file is
+/// an empty store, while reading a file that is not valid JSON fails with
+/// `InvalidData`, and so does storing a value in it, which leaves the file
+/// as it is.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::{set_state_store, Select, StateStore};
+///
+/// set_state_store(Some(StateStore::new("mytool")?));
+/// let region = Select::new()
+///     .items(&["eu-west", "us-east"])
+///     .remember_key("region")
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+///
+/// This requires the `serde` feature.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+}
+
+#[cfg(feature = "serde")]
+impl StateStore {
+    /// Creates the store of an application in the configuration directory
+    /// of the platform, e.g. `~/.config/<app>/state.json` on Linux.
+    ///
+    /// Fails with `NotFound` if the directory cannot be determined.
+    pub fn new(app: &str) -> io::Result<StateStore> {
+        let dir = config_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No configuration directory"))?;
+        Ok(StateStore::with_path(dir.join(app).join("state.json")))
+    }
+
+    /// Creates a store in a specific file.
+    pub fn with_path<P: Into<PathBuf>>(path: P) -> StateStore {
+        StateStore { path: path.into() }
+    }
+
+    /// Returns the file the store is kept in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the most recent value stored under `key`.
+    pub fn get(&self, key: &str) -> io::Result<Option<String>> {
+        Ok(self.recent(key)?.pop())
+    }
+
+    /// Stores a single value under `key`, replacing the previous ones.
+    pub fn set(&self, key: &str, value: &str) -> io::Result<()> {
+        let mut values = self.load()?;
+        values.insert(key.into(), vec![value.into()]);
+        self.save(&values)
+    }
+
+    /// Returns the values stored under `key`, the most recent one last.
+    pub fn recent(&self, key: &str) -> io::Result<Vec<String>> {
+        Ok(self.load()?.remove(key).unwrap_or_default())
+    }
+
+    /// Adds a value to the list under `key`, keeping at most `max` values.
+    ///
+    /// A value already in the list moves to its end.
+    pub fn push_recent(&self, key: &str, value: &str, max: usize) -> io::Result<()> {
+        let mut values = self.load()?;
+        let list = values.entry(key.into()).or_default();
+        list.retain(|item| item != value);
+        list.push(value.into());
+        let excess = list.len().saturating_sub(max);
+        list.drain(..excess);
+        self.save(&values)
+    }
+
+    /// Deletes all stored state.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            rv => rv,
+        }
+    }
+
+    fn load(&self) -> io::Result<Values> {
+        match fs::read_to_string(&self.path) {
+            Ok(ref data) if data.trim().is_empty() => Ok(Values::new()),
+            Ok(data) => serde_json::from_str(&data)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(Values::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the values to a temporary file next to the store first and
+    /// then renames it, so that an interrupted write never leaves a
+    /// truncated store.
+    fn save(&self, values: &Values) -> io::Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(dir)?;
+        let data = serde_json::to_string_pretty(values).map_err(io::Error::other)?;
+        let mut tmp = NamedTempFile::new_in(dir)?;
+        tmp.write_all(data.as_bytes())?;
+        tmp.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Sets the store prompts keep their state in, or `None` to keep it in
+/// memory for the rest of the process only.
+///
+/// This requires the `serde` feature.
+#[cfg(feature = "serde")]
+pub fn set_state_store(store: Option<StateStore>) {
+    *STORE.lock().unwrap_or_else(|err| err.into_inner()) = store;
+}
+
+/// Returns the configuration directory of the platform.
+#[cfg(feature = "serde")]
+fn config_dir() -> Option<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home.map(|home| home.join(".config")))
+    }
+}
+
+/// Returns the current store.
+#[cfg(feature = "serde")]
+fn current_store() -> Option<StateStore> {
+    STORE.lock().unwrap_or_else(|err| err.into_inner()).clone()
+}
+
+/// Returns the values the current store holds under `key`.
+#[cfg(feature = "serde")]
+fn load_stored(key: &str) -> Vec<String> {
+    current_store()
+        .and_then(|store| store.recent(key).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_stored(_key: &str) -> Vec<String> {
+    vec![]
+}
+
+/// Writes a value to the current store, keeping at most `max` values
+/// under `key`.
+#[cfg(feature = "serde")]
+fn save_stored(key: &str, value: &str, max: usize) {
+    if let Some(store) = current_store() {
+        let _ = store.push_recent(key, value, max);
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn save_stored(_key: &str, _value: &str, _max: usize) {}
+
+/// Returns the value remembered under `key`.
+pub(crate) fn recall(key: &str) -> Option<String> {
+    recall_recent(key).pop()
+}
+
+/// Returns the values remembered under `key`, the most recent one last.
+pub(crate) fn recall_recent(key: &str) -> Vec<String> {
+    let stored = load_stored(key);
+    if !stored.is_empty() {
+        return stored;
+    }
+    let state = STATE.lock().unwrap_or_else(|err| err.into_inner());
+    state
+        .as_ref()
+        .and_then(|state| state.get(key).cloned())
+        .unwrap_or_default()
+}
+
+/// Remembers a value under `key`.
+pub(crate) fn remember(key: &str, value: &str) {
+    remember_recent(key, value, 1);
+}
+
+/// Adds a value to those remembered under `key`, keeping at most `max`.
+///
+/// The value is written to the current `StateStore` if there is one; a
+/// store that cannot be written is ignored as remembering is a
+/// convenience only.
+pub(crate) fn remember_recent(key: &str, value: &str, max: usize) {
+    save_stored(key, value, max);
+    let mut state = STATE.lock().unwrap_or_else(|err| err.into_inner());
+    let list = state
+        .get_or_insert_with(HashMap::new)
+        .entry(key.into())
+        .or_default();
+    list.retain(|item| item != value);
+    list.push(value.into());
+    let excess = list.len().saturating_sub(max);
+    list.drain(..excess);
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_state_store() {
+        let file = NamedTempFile::new().unwrap();
+        let store = StateStore::with_path(file.path());
+        assert_eq!(store.get("region").unwrap(), None);
+        store.set("region", "eu").unwrap();
+        assert_eq!(store.get("region").unwrap().as_deref(), Some("eu"));
+        for path in &["a", "b", "a", "c"] {
+            store.push_recent("paths", path, 2).unwrap();
+        }
+        assert_eq!(store.recent("paths").unwrap(), vec!["a", "c"]);
+        store.clear().unwrap();
+        assert_eq!(store.recent("paths").unwrap(), Vec::<String>::new());
+        store.clear().unwrap();
+    }
+
+    #[test]
+    fn test_state_store_corrupt_file() {
+        let file = NamedTempFile::new().unwrap();
+        let data = "{\"region\": [\"eu\"";
+        fs::write(file.path(), data).unwrap();
+        let store = StateStore::with_path(file.path());
+        let err = store.get("region").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = store.set("region", "us").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_state_store_concurrent_writes() {
+        let file = NamedTempFile::new().unwrap();
+        let store = Arc::new(StateStore::with_path(file.path()));
+        let threads: Vec<_> = (0..8)
+            .map(|idx| {
+                let store = store.clone();
+                thread::spawn(move || {
+                    for value in 0..20 {
+                        store
+                            .push_recent(&format!("key{}", idx), &value.to_string(), 5)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert!(store.load().is_ok());
+    }
+}
@@ -0,0 +1,22 @@
+//! Selection of the stream prompts are rendered on.
+use console::Term;
+
+/// The standard stream a prompt is rendered on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stream {
+    /// Standard output.
+    Stdout,
+    /// Standard error, the default.
+    #[default]
+    Stderr,
+}
+
+impl Stream {
+    /// Returns the terminal of the stream.
+    pub(crate) fn term(self) -> Term {
+        match self {
+            Stream::Stdout => Term::stdout(),
+            Stream::Stderr => Term::stderr(),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::io;
+
+use console::{measure_text_width, pad_str, Alignment, Style};
+
+use backend::Backend;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use select::Select;
+use stream::Stream;
+use theme::Theme;
+
+/// Renders a selection menu of table rows with aligned columns.
+///
+/// The column titles are shown above the rows and stay in place when the
+/// menu scrolls.  The result is the index of the selected row.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::TableSelect;
+///
+/// let pod = TableSelect::new()
+///     .with_prompt("Pod")
+///     .header(&["NAME", "STATUS", "AGE"])
+///     .row(&["web-7d4b9c", "Running", "3d"])
+///     .row(&["worker-5f8a1e", "CrashLoopBackOff", "12m"])
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct TableSelect<'a> {
+    select: Select<'a>,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl<'a> TableSelect<'a> {
+    /// Creates a new table menu.
+    pub fn new() -> TableSelect<'static> {
+        TableSelect::with_select(Select::new())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> TableSelect<'a> {
+        TableSelect::with_select(Select::with_theme(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> TableSelect<'static> {
+        TableSelect::with_select(Select::with_owned_theme(theme))
+    }
+
+    fn with_select(select: Select<'a>) -> TableSelect<'a> {
+        TableSelect {
+            select,
+            header: vec![],
+            rows: vec![],
+        }
+    }
+
+    /// Sets the column titles.
+    pub fn header<T: ToString>(&mut self, titles: &[T]) -> &mut TableSelect<'a> {
+        self.header = titles.iter().map(|title| title.to_string()).collect();
+        self.layout()
+    }
+
+    /// Adds a row.
+    pub fn row<T: ToString>(&mut self, cells: &[T]) -> &mut TableSelect<'a> {
+        self.rows
+            .push(cells.iter().map(|cell| cell.to_string()).collect());
+        self.layout()
+    }
+
+    /// Prefaces the menu with a prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut TableSelect<'a> {
+        self.select.with_prompt(prompt);
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut TableSelect<'a> {
+        self.select.prompt_style(style);
+        self
+    }
+
+    /// Sets the style of the highlighted row for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut TableSelect<'a> {
+        self.select.highlight_style(style);
+        self
+    }
+
+    /// Sets the row selected initially.
+    pub fn default(&mut self, val: usize) -> &mut TableSelect<'a> {
+        self.select.default(val);
+        self
+    }
+
+    /// Enables or disables paging.
+    pub fn paged(&mut self, val: bool) -> &mut TableSelect<'a> {
+        self.select.paged(val);
+        self
+    }
+
+    /// Enables searching the rows.
+    pub fn searchable(&mut self, val: bool) -> &mut TableSelect<'a> {
+        self.select.searchable(val);
+        self
+    }
+
+    /// Sets the clear behavior of the menu.
+    pub fn clear(&mut self, val: bool) -> &mut TableSelect<'a> {
+        self.select.clear(val);
+        self
+    }
+
+    /// Sets the stream `interact` renders the menu on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut TableSelect<'a> {
+        self.select.on(stream);
+        self
+    }
+
+    /// Enables user interaction and returns the index of the selected row.
+    pub fn interact(&self) -> io::Result<usize> {
+        self.select.interact()
+    }
+
+    /// Like `interact` but returns `None` if the user cancelled with Esc or 'q'.
+    pub fn interact_opt(&self) -> io::Result<Option<usize>> {
+        self.select.interact_opt()
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<usize>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, TableSelect::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<usize> {
+        self.select.interact_on(term)
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<usize>> {
+        self.select.interact_on_opt(term)
+    }
+
+    /// Rebuilds the menu items from the header and rows.
+    fn layout(&mut self) -> &mut TableSelect<'a> {
+        let (header, rows) = layout_rows(&self.header, &self.rows);
+        self.select.clear_items();
+        self.select.items(&rows);
+        if !self.header.is_empty() {
+            self.select.with_header(&header);
+        }
+        self
+    }
+}
+
+/// Pads the cells of every row to the width of their column.
+fn layout_rows(header: &[String], rows: &[Vec<String>]) -> (String, Vec<String>) {
+    let mut widths: Vec<usize> = vec![];
+    for row in Some(header)
+        .into_iter()
+        .chain(rows.iter().map(|row| &row[..]))
+    {
+        for (idx, cell) in row.iter().enumerate() {
+            let width = measure_text_width(cell);
+            if idx < widths.len() {
+                widths[idx] = widths[idx].max(width);
+            } else {
+                widths.push(width);
+            }
+        }
+    }
+    let format = |row: &[String]| {
+        let cells: Vec<_> = row
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| {
+                if idx + 1 < row.len() {
+                    pad_str(cell, widths[idx], Alignment::Left, None).into_owned()
+                } else {
+                    cell.clone()
+                }
+            })
+            .collect();
+        cells.join("  ")
+    };
+    (format(header), rows.iter().map(|row| format(row)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_rows() {
+        let header = vec!["NAME".to_string(), "AGE".to_string()];
+        let rows = vec![
+            vec!["web".to_string(), "3d".to_string()],
+            vec!["worker-1".to_string(), "12m".to_string()],
+        ];
+        let (header, rows) = layout_rows(&header, &rows);
+        assert_eq!(header, "NAME      AGE");
+        assert_eq!(rows, vec!["web       3d", "worker-1  12m"]);
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(TableSelect::new());
+        assert_send(TableSelect::with_owned_theme(ColorfulTheme::default()));
+    }
+}
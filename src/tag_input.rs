@@ -0,0 +1,236 @@
+use std::io;
+use std::sync::Arc;
+
+use console::{Key, Style};
+
+use backend::{Backend, TermGuard};
+use events::{pop_grapheme, read_key, read_plain_line, wait_for_event, Event};
+use messages::messages;
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders a prompt for entering a list of values one at a time.
+///
+/// Every value confirmed with enter becomes a tag shown above the input.
+/// Backspace on an empty input removes the last tag and enter on an empty
+/// input finishes the list.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::TagInput;
+///
+/// let labels = TagInput::new().with_prompt("Labels").interact()?;
+/// let env = TagInput::new().with_prompt("Environment").interact_pairs()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct TagInput<'a> {
+    prompt: String,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a> TagInput<'a> {
+    /// Creates a new tag input.
+    pub fn new() -> TagInput<'static> {
+        TagInput::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> TagInput<'a> {
+        TagInput::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> TagInput<'static> {
+        TagInput::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> TagInput<'a> {
+        TagInput {
+            prompt: "".into(),
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the input prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut TagInput<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut TagInput<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut TagInput<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the entered values.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<Vec<String>> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<Vec<String>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, TagInput::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<Vec<String>> {
+        self.read_tags(term, false)
+    }
+
+    /// Like `interact` but reads `key=value` pairs.
+    ///
+    /// Values without a `=` are rejected.
+    pub fn interact_pairs(&self) -> io::Result<Vec<(String, String)>> {
+        self.interact_pairs_on(&self.stream.term())
+    }
+
+    /// Like `interact_pairs` but allows a specific terminal to be set.
+    pub fn interact_pairs_on(&self, term: &dyn Backend) -> io::Result<Vec<(String, String)>> {
+        Ok(self
+            .read_tags(term, true)?
+            .iter()
+            .filter_map(|tag| split_pair(tag))
+            .collect())
+    }
+
+    fn read_tags(&self, term: &dyn Backend, pairs: bool) -> io::Result<Vec<String>> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term, pairs),
+            |tags| Some(tags.join(", ")),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend, pairs: bool) -> io::Result<Vec<String>> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.read_tags_accessible(term, &mut render, pairs);
+        }
+        let mut tags: Vec<String> = vec![];
+        let mut input = String::new();
+        let mut error: Option<String> = None;
+        loop {
+            render.begin_frame();
+            if !tags.is_empty() {
+                let tags: Vec<_> = tags.iter().map(|tag| tag.as_str()).collect();
+                render.tags(&tags)?;
+            }
+            render.input_line(&self.prompt, &input)?;
+            if let Some(ref error) = error {
+                render.error(error)?;
+            }
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                continue;
+            }
+            error = None;
+            match render.clear_interrupted(read_key(term))? {
+                Key::Enter if input.is_empty() => break,
+                Key::Enter => match check_tag(&input, pairs) {
+                    Ok(()) => tags.push(input.split_off(0)),
+                    Err(err) => error = Some(err),
+                },
+                Key::Backspace if input.is_empty() => {
+                    tags.pop();
+                }
+                Key::Backspace => {
+                    pop_grapheme(&mut input);
+                }
+                Key::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+        render.clear()?;
+        render.single_prompt_selection(&self.prompt, &tags.join(", "))?;
+        Ok(tags)
+    }
+
+    /// Reads one value per line for the accessible render mode.
+    fn read_tags_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        pairs: bool,
+    ) -> io::Result<Vec<String>> {
+        let mut tags = vec![];
+        render.hint(&messages().tag_hint)?;
+        loop {
+            render.input_prompt(&self.prompt, None)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            if input.is_empty() {
+                return Ok(tags);
+            }
+            match check_tag(&input, pairs) {
+                Ok(()) => tags.push(input),
+                Err(err) => render.error(&err)?,
+            }
+        }
+    }
+}
+
+fn check_tag(tag: &str, pairs: bool) -> Result<(), String> {
+    match split_pair(tag) {
+        _ if !pairs => Ok(()),
+        Some(ref pair) if !pair.0.is_empty() => Ok(()),
+        _ => Err(messages().expected_pair.clone()),
+    }
+}
+
+fn split_pair(tag: &str) -> Option<(String, String)> {
+    let idx = tag.find('=')?;
+    Some((
+        tag[..idx].trim().to_string(),
+        tag[idx + 1..].trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_tag() {
+        assert_eq!(check_tag("rust", false), Ok(()));
+        assert!(check_tag("rust", true).is_err());
+        assert!(check_tag("=x", true).is_err());
+        assert_eq!(check_tag("a = b", true), Ok(()));
+        assert_eq!(split_pair("a = b=c"), Some(("a".into(), "b=c".into())));
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(TagInput::new());
+        assert_send(TagInput::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -1,8 +1,256 @@
 //! Customizes the rendering of the elements.
+use std::borrow::Cow;
+use std::env;
 use std::fmt;
 use std::io;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use console::{Style, Term};
+use console::{
+    measure_text_width, pad_str, strip_ansi_codes, style, truncate_str, Alignment, Attribute,
+    Color, Style, StyledObject,
+};
+
+use backend::Backend;
+use bidi::visual_order;
+use events::{set_line_prompt, stops};
+use messages::{fill, messages};
+use mouse::cursor_row;
+use observer;
+
+const MODE_AUTO: usize = 0;
+const MODE_ON: usize = 1;
+const MODE_OFF: usize = 2;
+
+static PLAIN_MODE: AtomicUsize = AtomicUsize::new(MODE_AUTO);
+static ASCII_MODE: AtomicUsize = AtomicUsize::new(MODE_AUTO);
+static ACCESSIBLE_MODE: AtomicUsize = AtomicUsize::new(MODE_AUTO);
+static SANITIZE_MODE: AtomicUsize = AtomicUsize::new(Sanitize::Strip as usize);
+
+fn store_mode(mode: &AtomicUsize, enabled: Option<bool>) {
+    mode.store(
+        match enabled {
+            None => MODE_AUTO,
+            Some(true) => MODE_ON,
+            Some(false) => MODE_OFF,
+        },
+        Ordering::Relaxed,
+    );
+}
+
+fn load_mode(mode: &AtomicUsize) -> Option<bool> {
+    match mode.load(Ordering::Relaxed) {
+        MODE_ON => Some(true),
+        MODE_OFF => Some(false),
+        _ => None,
+    }
+}
+
+/// Forces plain-text rendering on or off.
+///
+/// In plain mode all ANSI styling is dropped from the rendered prompts
+/// while the textual layout stays the same.  By default (`None`) plain
+/// mode is picked automatically for every prompt: it is used if `NO_COLOR`
+/// is set, `CLICOLOR` is `0`, or the terminal the prompt renders to is not
+/// a color capable TTY.
+pub fn set_plain_mode(enabled: Option<bool>) {
+    store_mode(&PLAIN_MODE, enabled);
+}
+
+/// Returns `true` if prompts rendered to the given terminal should be plain.
+pub(crate) fn is_plain_mode(term: &dyn Backend) -> bool {
+    load_mode(&PLAIN_MODE).unwrap_or_else(|| color_level(term) == ColorLevel::None)
+}
+
+/// The colors a terminal can display, from none to 24-bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    /// No colors, e.g. because the output is not a terminal.
+    None,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+    /// The 256 colors of xterm.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+/// Detects the colors a terminal can display.
+///
+/// There are none if `NO_COLOR` is set, `CLICOLOR` is `0` or the terminal
+/// is not a color capable TTY.  Otherwise `COLORTERM` and `TERM` tell the
+/// rest, as does Windows Terminal.
+pub fn color_level(term: &dyn Backend) -> ColorLevel {
+    let var = |name| env::var(name).unwrap_or_default();
+    if env::var_os("NO_COLOR").is_some()
+        || var("CLICOLOR") == "0"
+        || !term.is_term()
+        || !term.colors_supported()
+    {
+        ColorLevel::None
+    } else if var("COLORTERM") == "truecolor"
+        || var("COLORTERM") == "24bit"
+        || env::var_os("WT_SESSION").is_some()
+    {
+        ColorLevel::TrueColor
+    } else if var("TERM").contains("256color") {
+        ColorLevel::Ansi256
+    } else {
+        ColorLevel::Ansi16
+    }
+}
+
+/// Forces glyphs to ASCII on or off.
+///
+/// In ASCII mode prompts render with the `ascii_fallback` of their theme,
+/// e.g. `>` instead of `❯` and `[x]` instead of `◉`.  By default (`None`)
+/// the mode is used on the Linux console, on Windows consoles other than
+/// Windows Terminal and VS Code, and with a locale that is not UTF-8.
+pub fn set_ascii_mode(enabled: Option<bool>) {
+    store_mode(&ASCII_MODE, enabled);
+}
+
+/// Returns `true` if prompts should only render ASCII glyphs.
+pub(crate) fn is_ascii_mode() -> bool {
+    load_mode(&ASCII_MODE).unwrap_or_else(|| {
+        let var = |name| env::var(name).unwrap_or_default();
+        if cfg!(windows) {
+            return env::var_os("WT_SESSION").is_none() && var("TERM_PROGRAM") != "vscode";
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .map(|&name| var(name))
+            .find(|locale| !locale.is_empty());
+        var("TERM") == "linux"
+            || locale.is_some_and(|locale| {
+                let locale = locale.to_lowercase();
+                !locale.contains("utf-8") && !locale.contains("utf8")
+            })
+    })
+}
+
+/// Forces the accessible render mode on or off.
+///
+/// The accessible mode never moves the cursor or clears lines.  Menus are
+/// printed once as a numbered list and answered by typing a number, and
+/// confirmations are answered by typing `yes` or `no`.  This lets screen
+/// readers and dumb terminals such as the Emacs shell or CI logs drive the
+/// prompts.  By default (`None`) the mode is used if `TERM` is `dumb` or
+/// `INSIDE_EMACS` is set.
+pub fn set_accessible_mode(enabled: Option<bool>) {
+    store_mode(&ACCESSIBLE_MODE, enabled);
+}
+
+/// Returns `true` if prompts should use the accessible render mode.
+pub(crate) fn is_accessible_mode() -> bool {
+    load_mode(&ACCESSIBLE_MODE).unwrap_or_else(|| {
+        env::var("TERM").ok().as_deref() == Some("dumb") || env::var_os("INSIDE_EMACS").is_some()
+    })
+}
+
+/// How control characters and escape sequences in prompts and items are
+/// rendered.
+///
+/// Text from other programs or from users can contain sequences that move
+/// the cursor or clear the screen, which garbles the menu or fakes its
+/// contents.  Style sequences such as colors are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sanitize {
+    /// Removes them; tabs become spaces.  This is the default.
+    Strip = 0,
+    /// Shows them in caret notation, e.g. `^[[2J`, so they can be seen.
+    Escape = 1,
+    /// Passes them to the terminal unchanged.
+    Off = 2,
+}
+
+/// Sets how prompts render control characters and escape sequences in
+/// the texts they show.
+pub fn set_sanitize_mode(mode: Sanitize) {
+    SANITIZE_MODE.store(mode as usize, Ordering::Relaxed);
+}
+
+fn sanitize_mode() -> Sanitize {
+    match SANITIZE_MODE.load(Ordering::Relaxed) {
+        1 => Sanitize::Escape,
+        2 => Sanitize::Off,
+        _ => Sanitize::Strip,
+    }
+}
+
+/// Removes or escapes the control characters and escape sequences of
+/// `text` except for line breaks and styles.
+fn sanitize(text: &str, mode: Sanitize) -> Cow<'_, str> {
+    let clean = |c: char| c == '\n' || !c.is_control();
+    if mode == Sanitize::Off || text.chars().all(clean) && !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+    let mut rv = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            if let Some(len) = style_len(rest) {
+                rv.push_str(&rest[..len]);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        rest = &rest[c.len_utf8()..];
+        match (c, mode) {
+            (c, _) if clean(c) => rv.push(c),
+            ('\t', _) => rv.push(' '),
+            (_, Sanitize::Strip) => {
+                if c == '\x1b' {
+                    rest = &rest[escape_len(rest)..];
+                }
+            }
+            ('\x7f', _) => rv.push_str("^?"),
+            (c, _) if (c as u32) < 0x20 => {
+                rv.push('^');
+                rv.push((c as u8 + b'@') as char);
+            }
+            (c, _) => {
+                rv.push_str("^[");
+                rv.extend(std::char::from_u32(c as u32 - 0x40));
+            }
+        }
+    }
+    Cow::Owned(rv)
+}
+
+/// Returns the length of the style sequence `text` starts with.
+fn style_len(text: &str) -> Option<usize> {
+    let params = text.strip_prefix("\x1b[")?;
+    let len = params.find(|c: char| !c.is_ascii_digit() && c != ';')?;
+    if params[len..].starts_with('m') {
+        Some(len + 3)
+    } else {
+        None
+    }
+}
+
+/// Returns the length of the rest of an escape sequence after the escape
+/// character.
+fn escape_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    match chars.next() {
+        Some((_, '[')) => chars
+            .find(|&(_, c)| ('\x40'..='\x7e').contains(&c))
+            .map_or(text.len(), |(idx, c)| idx + c.len_utf8()),
+        Some((_, ']')) => {
+            let end = text.find(['\x07', '\x1b']).unwrap_or(text.len());
+            let terminator = &text[end..];
+            end + if terminator.starts_with("\x1b\\") {
+                2
+            } else {
+                terminator.chars().next().map_or(0, char::len_utf8)
+            }
+        }
+        Some((_, c)) => c.len_utf8(),
+        None => 0,
+    }
+}
 
 /// Rendering style for a selected item
 #[derive(Debug, Clone, Copy)]
@@ -21,17 +269,85 @@ pub enum SelectionStyle {
     MenuUnselected,
 }
 
+/// The state of a checkbox that stands for a group of items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    /// None of the items is checked.
+    Unchecked,
+    /// Some of the items are checked.
+    Partial,
+    /// All of the items are checked.
+    Checked,
+}
+
 /// Implements a theme for dialoguer.
+///
+/// Every method has a default implementation which renders like the
+/// `SimpleTheme`, so a custom theme only needs to override the hooks it
+/// wants to restyle.
+///
+/// Themes need to be `Sync` so that prompts can be sent to other threads;
+/// themes handed over with `with_owned_theme` also need to be `Send`.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::fmt;
+/// use dialoguer::Input;
+/// use dialoguer::theme::Theme;
+///
+/// struct ShoutingTheme;
+///
+/// impl Theme for ShoutingTheme {
+///     fn format_singleline_prompt(
+///         &self,
+///         f: &mut dyn fmt::Write,
+///         prompt: &str,
+///         _default: Option<&str>,
+///     ) -> fmt::Result {
+///         write!(f, "{}! ", prompt.to_uppercase())
+///     }
+///
+///     fn format_error(&self, f: &mut dyn fmt::Write, err: &str) -> fmt::Result {
+///         write!(f, "NO: {}", err)
+///     }
+/// }
+///
+/// let name = Input::<String>::with_theme(&ShoutingTheme)
+///     .with_prompt("Your name")
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
 pub trait Theme {
+    /// Returns a variant of the theme that only uses ASCII glyphs.
+    ///
+    /// Prompts render with it on terminals that cannot display other
+    /// characters, see `set_ascii_mode`.  The default is `None`, which
+    /// keeps the theme as it is.
+    fn ascii_fallback(&self) -> Option<Box<dyn Theme + Send + Sync>> {
+        None
+    }
+
+    /// Returns the theme with some glyphs and styles changed.
+    ///
+    /// Prompts use this for styles set on them alone, such as
+    /// `Select::highlight_style`.  The default is `None`, which keeps the
+    /// theme as it is.
+    fn with_overrides(&self, overrides: &ThemeOverrides) -> Option<Box<dyn Theme + Send + Sync>> {
+        let _ = overrides;
+        None
+    }
+
     /// Given a prompt this formats out what the prompt should look like (multiline).
-    fn format_prompt(&self, f: &mut fmt::Write, prompt: &str) -> fmt::Result {
+    fn format_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
         write!(f, "{}:", prompt)
     }
 
     /// Given a prompt this formats out what the prompt should look like (singleline).
     fn format_singleline_prompt(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         default: Option<&str>,
     ) -> fmt::Result {
@@ -42,40 +358,84 @@ pub trait Theme {
     }
 
     /// Formats out an error.
-    fn format_error(&self, f: &mut fmt::Write, err: &str) -> fmt::Result {
-        write!(f, "error: {}", err)
+    fn format_error(&self, f: &mut dyn fmt::Write, err: &str) -> fmt::Result {
+        write!(f, "{}: {}", messages().error, err)
+    }
+
+    /// Formats out a warning that does not block the input.
+    fn format_warning(&self, f: &mut dyn fmt::Write, warning: &str) -> fmt::Result {
+        write!(f, "{}: {}", messages().warning, warning)
     }
 
     /// Formats a confirmation prompt.
     fn format_confirmation_prompt(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         default: Option<bool>,
     ) -> fmt::Result {
         write!(f, "{}", &prompt)?;
         match default {
             None => {}
-            Some(true) => write!(f, " [Y/n] ")?,
-            Some(false) => write!(f, " [y/N] ")?,
+            Some(default) => write!(f, " {} ", messages().yes_no_hint(default))?,
         }
         Ok(())
     }
 
+    /// Formats a prompt answered with a single key, e.g.
+    /// `Apply? [a]pply [s]kip [q]uit`.
+    fn format_key_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        choices: &[(char, &str)],
+        default: Option<char>,
+    ) -> fmt::Result {
+        write!(f, "{} {} ", prompt, key_choices(choices, default))
+    }
+
+    /// Formats the question whether to use the default of an input.
+    fn format_default_confirmation(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: &str,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {} ",
+            prompt,
+            default,
+            messages().use_default_hint()
+        )
+    }
+
     /// Formats a confirmation prompt.
     fn format_confirmation_prompt_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         selection: bool,
     ) -> fmt::Result {
-        write!(f, "{} {}", &prompt, if selection { "yes" } else { "no" })
+        self.format_confirmation_report(f, prompt, selection, messages().yes_no(selection))
+    }
+
+    /// Renders the summary line printed after a confirmation, showing
+    /// `answer` for the selection made.
+    fn format_confirmation_report(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        _selection: bool,
+        answer: &str,
+    ) -> fmt::Result {
+        write!(f, "{} {}", &prompt, answer)
     }
 
     /// Renders a prompt and a single selection made.
     fn format_single_prompt_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         sel: &str,
     ) -> fmt::Result {
@@ -85,7 +445,7 @@ pub trait Theme {
     /// Renders a prompt and multiple selections,
     fn format_multi_prompt_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         selections: &[&str],
     ) -> fmt::Result {
@@ -96,15 +456,45 @@ pub trait Theme {
         Ok(())
     }
 
+    /// Renders the summary line printed after a menu selection.
+    fn format_select_report(&self, f: &mut dyn fmt::Write, prompt: &str, sel: &str) -> fmt::Result {
+        self.format_single_prompt_selection(f, prompt, sel)
+    }
+
+    /// Renders the summary line printed after confirming checkboxes.
+    fn format_checkboxes_report(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selections: &[&str],
+    ) -> fmt::Result {
+        self.format_multi_prompt_selection(f, prompt, selections)
+    }
+
     /// Renders a prompt and multiple selections,
-    fn format_password_prompt_selection(&self, f: &mut fmt::Write, prompt: &str) -> fmt::Result {
-        self.format_single_prompt_selection(f, prompt, "[hidden]")
+    fn format_password_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+    ) -> fmt::Result {
+        self.format_password_report(f, prompt, &messages().hidden)
+    }
+
+    /// Renders the summary line printed after a password prompt, showing
+    /// `answer` in place of the password.
+    fn format_password_report(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        answer: &str,
+    ) -> fmt::Result {
+        self.format_single_prompt_selection(f, prompt, answer)
     }
 
     /// Formats a selection.
     fn format_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         text: &str,
         style: SelectionStyle,
     ) -> fmt::Result {
@@ -122,6 +512,233 @@ pub trait Theme {
             text
         )
     }
+
+    /// Formats an item that cannot be selected.
+    ///
+    /// `style` is the style the item would have if it were enabled.
+    fn format_disabled_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        style: SelectionStyle,
+    ) -> fmt::Result {
+        self.format_selection(f, text, style)
+    }
+
+    /// Formats an item of a tree menu.
+    ///
+    /// `expanded` is `None` for leaves and tells whether a group is
+    /// expanded otherwise.
+    fn format_tree_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        style: SelectionStyle,
+    ) -> fmt::Result {
+        let marker = match expanded {
+            Some(true) => "- ",
+            Some(false) => "+ ",
+            None => "",
+        };
+        let text = format!("{}{}{}", "  ".repeat(depth), marker, text);
+        self.format_selection(f, &text, style)
+    }
+
+    /// Formats an item of a checkbox tree.
+    ///
+    /// `expanded` is like for `format_tree_item`; `active` tells whether
+    /// the cursor is on the item.
+    fn format_checkbox_tree_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        check: CheckState,
+        active: bool,
+    ) -> fmt::Result {
+        let marker = match expanded {
+            Some(true) => "- ",
+            Some(false) => "+ ",
+            None => "",
+        };
+        let text = format!("{}{}{}", "  ".repeat(depth), marker, text);
+        let style = match (check, active) {
+            (CheckState::Partial, _) => {
+                return write!(f, "{}[-] {}", if active { "> " } else { "  " }, text);
+            }
+            (CheckState::Checked, true) => SelectionStyle::CheckboxCheckedSelected,
+            (CheckState::Checked, false) => SelectionStyle::CheckboxCheckedUnselected,
+            (CheckState::Unchecked, true) => SelectionStyle::CheckboxUncheckedSelected,
+            (CheckState::Unchecked, false) => SelectionStyle::CheckboxUncheckedUnselected,
+        };
+        self.format_selection(f, &text, style)
+    }
+
+    /// Formats a separator between groups of menu items.
+    fn format_separator(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(f, "{}", text)
+    }
+
+    /// Formats a toggle prompt with both options, `value` tells whether
+    /// the first one is active.
+    fn format_toggle(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        options: (&str, &str),
+        value: bool,
+    ) -> fmt::Result {
+        let (on, off) = if value {
+            (format!("[{}]", options.0), format!(" {} ", options.1))
+        } else {
+            (format!(" {} ", options.0), format!("[{}]", options.1))
+        };
+        write!(f, "{}: {} {}", prompt, on, off)
+    }
+
+    /// Formats a rating prompt with `value` out of `max` symbols filled.
+    fn format_rating(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        value: u8,
+        max: u8,
+    ) -> fmt::Result {
+        write!(f, "{}: ", prompt)?;
+        for idx in 0..max {
+            write!(f, "{}", if idx < value { '*' } else { '.' })?;
+        }
+        Ok(())
+    }
+
+    /// Formats the column titles shown above the rows of a table menu.
+    ///
+    /// The text is indented like a menu item so the columns line up.
+    fn format_table_header(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(f, "  {}", text)
+    }
+
+    /// Formats the values already entered into a tag input.
+    fn format_tags(&self, f: &mut dyn fmt::Write, tags: &[&str]) -> fmt::Result {
+        for (idx, tag) in tags.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "[{}]", tag)?;
+        }
+        Ok(())
+    }
+
+    /// Formats the query of a menu that is being searched.
+    fn format_search_prompt(&self, f: &mut dyn fmt::Write, query: &str) -> fmt::Result {
+        write!(f, "/{}", query)
+    }
+
+    /// Formats the part of an item that matches the search query.
+    ///
+    /// The result is embedded into the text passed to `format_selection`.
+    fn format_search_match(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(f, "{}", text)
+    }
+
+    /// Formats the hotkey letter of a menu item.
+    ///
+    /// The result is embedded into the text passed to `format_selection`.
+    fn format_hotkey(&self, f: &mut dyn fmt::Write, key: char) -> fmt::Result {
+        write!(f, "{}", style(key).underlined())
+    }
+
+    /// Formats an item of a numbered list as used by the accessible mode.
+    fn format_numbered_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        number: usize,
+        text: &str,
+    ) -> fmt::Result {
+        write!(f, "{}. {}", number, text)
+    }
+
+    /// Formats a line of the preview panel below a menu.
+    fn format_preview_line(&self, f: &mut dyn fmt::Write, line: &str) -> fmt::Result {
+        write!(f, "│ {}", line)
+    }
+
+    /// Formats a line of help text such as hints or key bindings.
+    fn format_hint(&self, f: &mut dyn fmt::Write, hint: &str) -> fmt::Result {
+        write!(f, "{}", hint)
+    }
+
+    /// Formats a progress bar at `pos` out of `len` steps.
+    fn format_progress_bar(
+        &self,
+        f: &mut dyn fmt::Write,
+        message: &str,
+        pos: u64,
+        len: u64,
+    ) -> fmt::Result {
+        let filled = progress_cells(pos, len, PROGRESS_WIDTH);
+        write!(
+            f,
+            "{}{}[{}{}] {}/{}",
+            message,
+            if message.is_empty() { "" } else { " " },
+            "#".repeat(filled),
+            "-".repeat(PROGRESS_WIDTH - filled),
+            pos,
+            len
+        )
+    }
+
+    /// Formats a spinner; `tick` counts the updates so far.
+    fn format_spinner(&self, f: &mut dyn fmt::Write, message: &str, tick: usize) -> fmt::Result {
+        let frames = ['|', '/', '-', '\\'];
+        write!(f, "{} {}", frames[tick % frames.len()], message)
+    }
+
+    /// Formats the line a finished progress bar or spinner leaves behind.
+    fn format_progress_done(&self, f: &mut dyn fmt::Write, message: &str) -> fmt::Result {
+        write!(f, "{}", message)
+    }
+}
+
+/// The number of cells of a progress bar.
+const PROGRESS_WIDTH: usize = 30;
+
+/// Returns how many of `width` cells are filled at `pos` out of `len`.
+fn progress_cells(pos: u64, len: u64, width: usize) -> usize {
+    if len == 0 {
+        return width;
+    }
+    (pos.min(len) as u128 * width as u128 / len as u128) as usize
+}
+
+/// Returns the hint of a key prompt such as `[a]pply [s]kip [q]uit`.
+///
+/// The key of the default is shown in upper case.
+fn key_choices(choices: &[(char, &str)], default: Option<char>) -> String {
+    let hints: Vec<String> = choices
+        .iter()
+        .map(|&(key, label)| {
+            let shown: String = if Some(key) == default {
+                key.to_uppercase().collect()
+            } else {
+                key.to_string()
+            };
+            let mut rest = label.chars();
+            if rest
+                .next()
+                .is_some_and(|first| first.to_lowercase().eq(key.to_lowercase()))
+            {
+                format!("[{}]{}", shown, rest.as_str())
+            } else {
+                format!("[{}] {}", shown, label)
+            }
+        })
+        .collect();
+    hints.join(" ")
 }
 
 /// The default theme.
@@ -149,14 +766,14 @@ impl Default for CustomPromptCharacterTheme {
 }
 impl Theme for CustomPromptCharacterTheme {
     /// Given a prompt this formats out what the prompt should look like (multiline).
-    fn format_prompt(&self, f: &mut fmt::Write, prompt: &str) -> fmt::Result {
+    fn format_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
         write!(f, "{}{}", prompt, self.prompt_character)
     }
 
     /// Given a prompt this formats out what the prompt should look like (singleline).
     fn format_singleline_prompt(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         default: Option<&str>,
     ) -> fmt::Result {
@@ -168,7 +785,7 @@ impl Theme for CustomPromptCharacterTheme {
     /// Renders a prompt and a single selection made.
     fn format_single_prompt_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         sel: &str,
     ) -> fmt::Result {
@@ -178,7 +795,7 @@ impl Theme for CustomPromptCharacterTheme {
     /// Renders a prompt and multiple selections,
     fn format_multi_prompt_selection(
         &self,
-        f: &mut fmt::Write,
+        f: &mut dyn fmt::Write,
         prompt: &str,
         selections: &[&str],
     ) -> fmt::Result {
@@ -189,179 +806,941 @@ impl Theme for CustomPromptCharacterTheme {
         Ok(())
     }
 }
-/// A colorful theme
-pub struct ColorfulTheme {
+/// Declares the fields of `ColorfulTheme` once, for the theme itself and
+/// for `ThemeOverrides`, so that every field can be overridden.
+macro_rules! colorful_theme_fields {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty,)*) => {
+        /// A colorful theme
+        ///
+        /// All glyphs and styles are public so individual pieces can be tweaked
+        /// while keeping the rest of the defaults:
+        ///
+        /// ```rust
+        /// # extern crate console;
+        /// # extern crate dialoguer;
+        /// use dialoguer::theme::ColorfulTheme;
+        /// use console::style;
+        ///
+        /// # fn main() {
+        ///
+        /// let theme = ColorfulTheme {
+        ///     prompt_prefix: style("»".to_string()).magenta(),
+        ///     ..ColorfulTheme::default()
+        /// };
+        /// # }
+        /// ```
+        #[derive(Clone)]
+        pub struct ColorfulTheme {
+            $($(#[$doc])* pub $field: $ty,)*
+        }
+
+        /// Changes to some glyphs and styles of a `ColorfulTheme`.
+        ///
+        /// Fields left at `None` keep the look of the theme the overrides are laid
+        /// over with `ColorfulTheme::overlay`, so layers such as a company wide
+        /// look and the tweaks of one tool can be kept apart:
+        ///
+        /// ```rust
+        /// # extern crate console;
+        /// # extern crate dialoguer;
+        /// use console::{style, Style};
+        /// use dialoguer::theme::{ColorfulTheme, ThemeOverrides};
+        ///
+        /// # fn main() {
+        /// let overrides = ThemeOverrides {
+        ///     prompt_prefix: Some(style("»".to_string()).magenta()),
+        ///     error_style: Some(Style::new().magenta()),
+        ///     ..ThemeOverrides::default()
+        /// };
+        /// let theme = ColorfulTheme::overlay(&ColorfulTheme::default(), &overrides);
+        /// # }
+        /// ```
+        #[derive(Clone, Default)]
+        pub struct ThemeOverrides {
+            $($(#[$doc])* pub $field: Option<$ty>,)*
+        }
+
+        impl ThemeOverrides {
+            /// Replaces the fields of `theme` that are overridden.
+            fn apply(&self, theme: &mut ColorfulTheme) {
+                $(overlay_field(&mut theme.$field, &self.$field);)*
+            }
+        }
+    };
+}
+
+colorful_theme_fields! {
     /// The style for default values in prompts and similar
-    pub defaults_style: Style,
+    defaults_style: Style,
+    /// The style for the prompt text
+    prompt_style: Style,
+    /// The glyph in front of a prompt that is waiting for input
+    prompt_prefix: StyledObject<String>,
+    /// The glyph between a prompt and the user input
+    prompt_suffix: StyledObject<String>,
+    /// The glyph in front of an answered prompt
+    success_prefix: StyledObject<String>,
+    /// The glyph between an answered prompt and its value
+    success_suffix: StyledObject<String>,
+    /// The glyph in front of errors
+    error_prefix: StyledObject<String>,
     /// The style for errors indicators
-    pub error_style: Style,
+    error_style: Style,
+    /// The prefix for warnings
+    warning_prefix: StyledObject<String>,
+    /// The style for warnings
+    warning_style: Style,
     /// The style for user interface indicators
-    pub indicator_style: Style,
+    indicator_style: Style,
+    /// The glyph in front of the active item of a menu
+    active_item_prefix: StyledObject<String>,
+    /// The glyph in front of inactive items of a menu
+    inactive_item_prefix: StyledObject<String>,
+    /// The glyph for checked checkbox items
+    checked_item_prefix: StyledObject<String>,
+    /// The glyph for unchecked checkbox items
+    unchecked_item_prefix: StyledObject<String>,
+    /// The glyph for checkbox items of which only some children are checked
+    partial_item_prefix: StyledObject<String>,
     /// The style for inactive elements
-    pub inactive_style: Style,
+    inactive_style: Style,
     /// The style for active elements
-    pub active_style: Style,
+    active_style: Style,
     /// The style for values indicating "yes"
-    pub yes_style: Style,
+    yes_style: Style,
     /// The style for values indicating "no"
-    pub no_style: Style,
+    no_style: Style,
     /// The style for values embedded in prompts
-    pub values_style: Style,
+    values_style: Style,
+    /// The style for help text and hints
+    hint_style: Style,
+    /// The style for disabled menu items and separators
+    disabled_style: Style,
+    /// The style for the parts of items matching a search
+    match_style: Style,
+    /// The style for the hotkey letters of menu items
+    hotkey_style: Style,
+    /// Whether trees, ratings, progress bars and spinners are drawn with
+    /// ASCII characters only
+    ascii: bool,
+}
+
+/// The default glyphs of the colorful theme as field name, text, style
+/// and ASCII fallback.
+pub(crate) const COLORFUL_GLYPHS: &[(&str, &str, &str, &str)] = &[
+    ("prompt_prefix", "?", "yellow.bold", "?"),
+    ("prompt_suffix", "›", "black.bright", ">"),
+    ("success_prefix", "✔", "green", "+"),
+    ("success_suffix", "·", "black.bright", ":"),
+    ("error_prefix", "✘", "red", "x"),
+    ("warning_prefix", "⚠", "yellow", "!"),
+    ("active_item_prefix", "❯", "cyan.bold", ">"),
+    ("inactive_item_prefix", " ", "", " "),
+    ("checked_item_prefix", "◉", "green", "[x]"),
+    ("unchecked_item_prefix", "◯", "black.bright", "[ ]"),
+    ("partial_item_prefix", "◐", "green", "[-]"),
+];
+
+/// Looks up the default glyph for a field of the colorful theme.
+pub(crate) fn colorful_glyph(key: &str) -> Option<(&'static str, Style)> {
+    COLORFUL_GLYPHS
+        .iter()
+        .find(|glyph| glyph.0 == key)
+        .map(|glyph| (glyph.1, Style::from_dotted_str(glyph.2)))
 }
 
 impl Default for ColorfulTheme {
     fn default() -> ColorfulTheme {
+        let glyph = |key| {
+            let (text, glyph_style) = colorful_glyph(key).unwrap();
+            glyph_style.apply_to(text.to_string())
+        };
         ColorfulTheme {
             defaults_style: Style::new().dim(),
+            prompt_style: Style::new().bold(),
+            prompt_prefix: glyph("prompt_prefix"),
+            prompt_suffix: glyph("prompt_suffix"),
+            success_prefix: glyph("success_prefix"),
+            success_suffix: glyph("success_suffix"),
+            error_prefix: glyph("error_prefix"),
             error_style: Style::new().red(),
+            warning_prefix: glyph("warning_prefix"),
+            warning_style: Style::new().yellow(),
             indicator_style: Style::new().cyan().bold(),
+            active_item_prefix: glyph("active_item_prefix"),
+            inactive_item_prefix: glyph("inactive_item_prefix"),
+            checked_item_prefix: glyph("checked_item_prefix"),
+            unchecked_item_prefix: glyph("unchecked_item_prefix"),
+            partial_item_prefix: glyph("partial_item_prefix"),
             inactive_style: Style::new().dim(),
-            active_style: Style::new(),
+            active_style: Style::new().cyan(),
             yes_style: Style::new().green(),
-            no_style: Style::new().green(),
-            values_style: Style::new().cyan(),
+            no_style: Style::new().red(),
+            values_style: Style::new().green(),
+            hint_style: Style::new().black().bright(),
+            disabled_style: Style::new().black().bright().strikethrough(),
+            match_style: Style::new().yellow().bold(),
+            hotkey_style: Style::new().underlined(),
+            ascii: false,
         }
     }
 }
 
-impl Theme for ColorfulTheme {
-    fn format_prompt(&self, f: &mut fmt::Write, prompt: &str) -> fmt::Result {
-        write!(f, "{}:", prompt)
+impl ColorfulTheme {
+    /// Creates a colorful theme without any styling.
+    ///
+    /// This keeps the glyphs and layout of the colorful theme but never
+    /// emits ANSI escape codes.
+    pub fn plain() -> ColorfulTheme {
+        let glyph = |key| style(colorful_glyph(key).unwrap().0.to_string());
+        ColorfulTheme {
+            defaults_style: Style::new(),
+            prompt_style: Style::new(),
+            prompt_prefix: glyph("prompt_prefix"),
+            prompt_suffix: glyph("prompt_suffix"),
+            success_prefix: glyph("success_prefix"),
+            success_suffix: glyph("success_suffix"),
+            error_prefix: glyph("error_prefix"),
+            error_style: Style::new(),
+            warning_prefix: glyph("warning_prefix"),
+            warning_style: Style::new(),
+            indicator_style: Style::new(),
+            active_item_prefix: glyph("active_item_prefix"),
+            inactive_item_prefix: glyph("inactive_item_prefix"),
+            checked_item_prefix: glyph("checked_item_prefix"),
+            unchecked_item_prefix: glyph("unchecked_item_prefix"),
+            partial_item_prefix: glyph("partial_item_prefix"),
+            inactive_style: Style::new(),
+            active_style: Style::new(),
+            yes_style: Style::new(),
+            no_style: Style::new(),
+            values_style: Style::new(),
+            hint_style: Style::new(),
+            disabled_style: Style::new(),
+            match_style: Style::new(),
+            hotkey_style: Style::new(),
+            ascii: false,
+        }
     }
 
-    fn format_singleline_prompt(
-        &self,
-        f: &mut fmt::Write,
-        prompt: &str,
-        default: Option<&str>,
-    ) -> fmt::Result {
-        match default {
-            Some(default) => write!(
-                f,
-                "{} [{}]: ",
-                prompt,
-                self.defaults_style.apply_to(default)
-            ),
-            None => write!(f, "{}: ", prompt),
+    /// Returns the theme with ASCII glyphs only.
+    ///
+    /// Glyphs that are not ASCII are replaced by the ASCII fallback of
+    /// their field, e.g. `[x]` for `◉`, in the style of the glyph.
+    pub fn to_ascii(&self) -> ColorfulTheme {
+        let mut theme = self.clone();
+        for &(key, _, _, fallback) in COLORFUL_GLYPHS {
+            let field = glyph_field(&mut theme, key);
+            let (text, glyph_style) = split_glyph(field);
+            if text.is_ascii() {
+                continue;
+            }
+            *field = glyph_style.apply_to(fallback.to_string());
         }
+        theme.ascii = true;
+        theme
     }
 
-    fn format_error(&self, f: &mut fmt::Write, err: &str) -> fmt::Result {
-        write!(f, "{}: {}", self.error_style.apply_to("error"), err)
+    /// Returns `base` with the fields set in `overrides` replaced.
+    ///
+    /// The result is a theme of its own and can be laid under further
+    /// overrides.
+    pub fn overlay(base: &ColorfulTheme, overrides: &ThemeOverrides) -> ColorfulTheme {
+        let mut theme = base.clone();
+        overrides.apply(&mut theme);
+        theme
     }
 
-    fn format_confirmation_prompt(
-        &self,
-        f: &mut fmt::Write,
-        prompt: &str,
-        default: Option<bool>,
-    ) -> fmt::Result {
-        write!(f, "{}", &prompt)?;
-        match default {
-            None => {}
-            Some(true) => write!(f, " {} ", self.defaults_style.apply_to("[Y/n]"))?,
-            Some(false) => write!(f, " {} ", self.defaults_style.apply_to("[y/N]"))?,
+    /// Returns `unicode` unless the theme is limited to ASCII.
+    fn glyph(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
         }
-        Ok(())
     }
 
-    fn format_confirmation_prompt_selection(
-        &self,
-        f: &mut fmt::Write,
-        prompt: &str,
-        selection: bool,
-    ) -> fmt::Result {
+    fn format_success(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
         write!(
             f,
-            "{} {}",
-            &prompt,
-            if selection {
-                self.yes_style.apply_to("yes")
-            } else {
-                self.no_style.apply_to("no")
-            }
+            "{} {} {} ",
+            self.success_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.success_suffix
         )
     }
+}
 
-    fn format_single_prompt_selection(
-        &self,
-        f: &mut fmt::Write,
-        prompt: &str,
-        sel: &str,
-    ) -> fmt::Result {
-        write!(f, "{}: {}", prompt, self.values_style.apply_to(sel))
+/// Replaces a field of a theme if it is overridden.
+fn overlay_field<T: Clone>(field: &mut T, value: &Option<T>) {
+    if let Some(ref value) = *value {
+        *field = value.clone();
     }
+}
 
-    fn format_multi_prompt_selection(
-        &self,
-        f: &mut fmt::Write,
-        prompt: &str,
-        selections: &[&str],
-    ) -> fmt::Result {
-        write!(f, "{}: ", prompt)?;
-        for (idx, sel) in selections.iter().enumerate() {
-            write!(
-                f,
-                "{}{}",
-                if idx == 0 { "" } else { ", " },
-                self.values_style.apply_to(sel)
-            )?;
-        }
-        Ok(())
+/// Splits a glyph into its text and style.
+///
+/// console keeps both private, so they are read back from the glyph
+/// rendered with styling forced on.  This does not depend on whether
+/// colors are enabled for the terminal.
+fn split_glyph(glyph: &StyledObject<String>) -> (String, Style) {
+    let rendered = glyph.clone().force_styling(true).to_string();
+    let mut glyph_style = Style::new();
+    for seq in rendered
+        .split('\x1b')
+        .filter_map(|seq| seq.strip_prefix('['))
+    {
+        let end = seq.find('m').unwrap_or(seq.len());
+        let codes: Vec<u8> = seq[..end]
+            .split(';')
+            .filter_map(|code| code.parse().ok())
+            .collect();
+        glyph_style = match codes[..] {
+            [38, 5, n] => glyph_style.fg(Color::Color256(n)),
+            [38, 2, r, g, b] => glyph_style.fg(Color::TrueColor(r, g, b)),
+            [48, 5, n] => glyph_style.bg(Color::Color256(n)),
+            [48, 2, r, g, b] => glyph_style.bg(Color::TrueColor(r, g, b)),
+            [n @ 30..=37] => glyph_style.fg(basic_color(n - 30)),
+            [n @ 40..=47] => glyph_style.bg(basic_color(n - 40)),
+            [n @ 1..=9] => glyph_style.attr(attribute(n)),
+            _ => glyph_style,
+        };
     }
+    (strip_ansi_codes(&rendered).into_owned(), glyph_style)
+}
 
-    fn format_selection(&self, f: &mut fmt::Write, text: &str, st: SelectionStyle) -> fmt::Result {
-        match st {
-            SelectionStyle::CheckboxUncheckedSelected => write!(
-                f,
-                "{} [ ] {}",
-                self.indicator_style.apply_to(">"),
-                self.active_style.apply_to(text)
-            ),
-            SelectionStyle::CheckboxUncheckedUnselected => {
-                write!(f, "  [ ] {}", self.inactive_style.apply_to(text))
+/// Returns one of the eight basic colors by its ANSI number.
+fn basic_color(n: u8) -> Color {
+    match n {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::White,
+    }
+}
+
+/// Returns an attribute by its ANSI number.
+fn attribute(n: u8) -> Attribute {
+    match n {
+        1 => Attribute::Bold,
+        2 => Attribute::Dim,
+        3 => Attribute::Italic,
+        4 => Attribute::Underlined,
+        5 => Attribute::Blink,
+        6 => Attribute::BlinkFast,
+        7 => Attribute::Reverse,
+        8 => Attribute::Hidden,
+        _ => Attribute::StrikeThrough,
+    }
+}
+
+/// Returns the field of a glyph of the colorful theme.
+pub(crate) fn glyph_field<'a>(
+    theme: &'a mut ColorfulTheme,
+    key: &str,
+) -> &'a mut StyledObject<String> {
+    match key {
+        "prompt_prefix" => &mut theme.prompt_prefix,
+        "prompt_suffix" => &mut theme.prompt_suffix,
+        "success_prefix" => &mut theme.success_prefix,
+        "success_suffix" => &mut theme.success_suffix,
+        "error_prefix" => &mut theme.error_prefix,
+        "warning_prefix" => &mut theme.warning_prefix,
+        "active_item_prefix" => &mut theme.active_item_prefix,
+        "inactive_item_prefix" => &mut theme.inactive_item_prefix,
+        "checked_item_prefix" => &mut theme.checked_item_prefix,
+        "unchecked_item_prefix" => &mut theme.unchecked_item_prefix,
+        "partial_item_prefix" => &mut theme.partial_item_prefix,
+        _ => unreachable!("unknown glyph {}", key),
+    }
+}
+
+impl Theme for ColorfulTheme {
+    fn ascii_fallback(&self) -> Option<Box<dyn Theme + Send + Sync>> {
+        Some(Box::new(self.to_ascii()))
+    }
+
+    fn with_overrides(&self, overrides: &ThemeOverrides) -> Option<Box<dyn Theme + Send + Sync>> {
+        Some(Box::new(ColorfulTheme::overlay(self, overrides)))
+    }
+
+    fn format_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.prompt_suffix
+        )
+    }
+
+    fn format_singleline_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt)
+        )?;
+        if let Some(default) = default {
+            write!(
+                f,
+                "{} ",
+                self.defaults_style.apply_to(format!("[{}]", default))
+            )?;
+        }
+        write!(f, "{} ", self.prompt_suffix)
+    }
+
+    fn format_error(&self, f: &mut dyn fmt::Write, err: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.error_prefix,
+            self.error_style.apply_to(err)
+        )
+    }
+
+    fn format_warning(&self, f: &mut dyn fmt::Write, warning: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.warning_prefix,
+            self.warning_style.apply_to(warning)
+        )
+    }
+
+    fn format_confirmation_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: Option<bool>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt)
+        )?;
+        match default {
+            None => {}
+            Some(default) => {
+                let hint = messages().yes_no_hint(default);
+                write!(f, "{} ", self.defaults_style.apply_to(hint))?
             }
+        }
+        write!(f, "{} ", self.prompt_suffix)
+    }
+
+    fn format_key_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        choices: &[(char, &str)],
+        default: Option<char>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.defaults_style.apply_to(key_choices(choices, default)),
+            self.prompt_suffix
+        )
+    }
+
+    fn format_default_confirmation(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: &str,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.values_style.apply_to(default),
+            self.defaults_style.apply_to(messages().use_default_hint())
+        )
+    }
+
+    fn format_confirmation_report(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selection: bool,
+        answer: &str,
+    ) -> fmt::Result {
+        self.format_success(f, prompt)?;
+        let style = if selection {
+            &self.yes_style
+        } else {
+            &self.no_style
+        };
+        write!(f, "{}", style.apply_to(answer))
+    }
+
+    fn format_single_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        sel: &str,
+    ) -> fmt::Result {
+        self.format_success(f, prompt)?;
+        write!(f, "{}", self.values_style.apply_to(sel))
+    }
+
+    fn format_multi_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selections: &[&str],
+    ) -> fmt::Result {
+        self.format_success(f, prompt)?;
+        for (idx, sel) in selections.iter().enumerate() {
+            write!(
+                f,
+                "{}{}",
+                if idx == 0 { "" } else { ", " },
+                self.values_style.apply_to(sel)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn format_checkboxes_report(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selections: &[&str],
+    ) -> fmt::Result {
+        self.format_multi_prompt_selection(f, prompt, selections)?;
+        if selections.len() > 1 {
+            write!(
+                f,
+                " {}",
+                self.hint_style.apply_to(fill(
+                    &messages().selected_count,
+                    &[("count", &selections.len())]
+                ))
+            )?;
+        }
+        Ok(())
+    }
+
+    fn format_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        st: SelectionStyle,
+    ) -> fmt::Result {
+        match st {
+            SelectionStyle::CheckboxUncheckedSelected => write!(
+                f,
+                "{} {} {}",
+                self.active_item_prefix,
+                self.unchecked_item_prefix,
+                self.active_style.apply_to(text)
+            ),
+            SelectionStyle::CheckboxUncheckedUnselected => write!(
+                f,
+                "{} {} {}",
+                self.inactive_item_prefix,
+                self.unchecked_item_prefix,
+                self.inactive_style.apply_to(text)
+            ),
             SelectionStyle::CheckboxCheckedSelected => write!(
                 f,
-                "{} [{}] {}",
-                self.indicator_style.apply_to(">"),
-                self.indicator_style.apply_to("x"),
-                self.active_style.apply_to(text),
+                "{} {} {}",
+                self.active_item_prefix,
+                self.checked_item_prefix,
+                self.active_style.apply_to(text)
             ),
             SelectionStyle::CheckboxCheckedUnselected => write!(
                 f,
-                "  [{}] {}",
-                self.indicator_style.apply_to("x"),
+                "{} {} {}",
+                self.inactive_item_prefix,
+                self.checked_item_prefix,
                 self.inactive_style.apply_to(text)
             ),
             SelectionStyle::MenuSelected => write!(
                 f,
                 "{} {}",
-                self.indicator_style.apply_to(">"),
+                self.active_item_prefix,
                 self.active_style.apply_to(text)
             ),
-            SelectionStyle::MenuUnselected => write!(f, "  {}", self.inactive_style.apply_to(text)),
+            SelectionStyle::MenuUnselected => write!(
+                f,
+                "{} {}",
+                self.inactive_item_prefix,
+                self.inactive_style.apply_to(text)
+            ),
+        }
+    }
+
+    fn format_disabled_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        style: SelectionStyle,
+    ) -> fmt::Result {
+        match style {
+            SelectionStyle::MenuSelected | SelectionStyle::MenuUnselected => write!(
+                f,
+                "{} {}",
+                self.inactive_item_prefix,
+                self.disabled_style.apply_to(text)
+            ),
+            _ => write!(
+                f,
+                "{} {} {}",
+                self.inactive_item_prefix,
+                self.unchecked_item_prefix,
+                self.disabled_style.apply_to(text)
+            ),
+        }
+    }
+
+    fn format_tree_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        style: SelectionStyle,
+    ) -> fmt::Result {
+        let marker = match expanded {
+            Some(true) => self.glyph("▾ ", "v "),
+            Some(false) => self.glyph("▸ ", "> "),
+            None => "",
+        };
+        let text = format!("{}{}{}", "  ".repeat(depth), marker, text);
+        self.format_selection(f, &text, style)
+    }
+
+    fn format_checkbox_tree_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        check: CheckState,
+        active: bool,
+    ) -> fmt::Result {
+        let marker = match expanded {
+            Some(true) => self.glyph("▾ ", "v "),
+            Some(false) => self.glyph("▸ ", "> "),
+            None => "",
+        };
+        let text = format!("{}{}{}", "  ".repeat(depth), marker, text);
+        write!(
+            f,
+            "{} {} {}",
+            if active {
+                &self.active_item_prefix
+            } else {
+                &self.inactive_item_prefix
+            },
+            match check {
+                CheckState::Checked => &self.checked_item_prefix,
+                CheckState::Partial => &self.partial_item_prefix,
+                CheckState::Unchecked => &self.unchecked_item_prefix,
+            },
+            if active {
+                self.active_style.apply_to(text)
+            } else {
+                self.inactive_style.apply_to(text)
+            }
+        )
+    }
+
+    fn format_separator(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(f, "{}", self.hint_style.apply_to(text))
+    }
+
+    fn format_toggle(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        options: (&str, &str),
+        value: bool,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.prompt_suffix
+        )?;
+        let option = |label: &str, active: bool| {
+            if active {
+                self.indicator_style.apply_to(format!("[{}]", label))
+            } else {
+                self.inactive_style.apply_to(format!(" {} ", label))
+            }
+        };
+        write!(
+            f,
+            "{} {}",
+            option(options.0, value),
+            option(options.1, !value)
+        )
+    }
+
+    fn format_rating(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        value: u8,
+        max: u8,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} ",
+            self.prompt_prefix,
+            self.prompt_style.apply_to(prompt),
+            self.prompt_suffix
+        )?;
+        for idx in 0..max {
+            if idx < value {
+                write!(f, "{}", self.values_style.apply_to(self.glyph("★", "*")))?;
+            } else {
+                write!(f, "{}", self.hint_style.apply_to(self.glyph("☆", ".")))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn format_table_header(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.inactive_item_prefix,
+            self.hint_style.apply_to(text)
+        )
+    }
+
+    fn format_tags(&self, f: &mut dyn fmt::Write, tags: &[&str]) -> fmt::Result {
+        for (idx, tag) in tags.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(
+                f,
+                "{}{}{}",
+                self.hint_style.apply_to("["),
+                self.values_style.apply_to(tag),
+                self.hint_style.apply_to("]")
+            )?;
+        }
+        Ok(())
+    }
+
+    fn format_search_prompt(&self, f: &mut dyn fmt::Write, query: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.indicator_style.apply_to("/"),
+            self.values_style.apply_to(query)
+        )
+    }
+
+    fn format_search_match(&self, f: &mut dyn fmt::Write, text: &str) -> fmt::Result {
+        write!(f, "{}", self.match_style.apply_to(text))
+    }
+
+    fn format_hotkey(&self, f: &mut dyn fmt::Write, key: char) -> fmt::Result {
+        write!(f, "{}", self.hotkey_style.apply_to(key))
+    }
+
+    fn format_numbered_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        number: usize,
+        text: &str,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.indicator_style.apply_to(format!("{}.", number)),
+            text
+        )
+    }
+
+    fn format_hint(&self, f: &mut dyn fmt::Write, hint: &str) -> fmt::Result {
+        write!(f, "{}", self.hint_style.apply_to(hint))
+    }
+
+    fn format_preview_line(&self, f: &mut dyn fmt::Write, line: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.indicator_style.apply_to(self.glyph("│", "|")),
+            line
+        )
+    }
+
+    fn format_progress_bar(
+        &self,
+        f: &mut dyn fmt::Write,
+        message: &str,
+        pos: u64,
+        len: u64,
+    ) -> fmt::Result {
+        let filled = progress_cells(pos, len, PROGRESS_WIDTH);
+        if !message.is_empty() {
+            write!(f, "{} ", self.prompt_style.apply_to(message))?;
         }
+        write!(
+            f,
+            "{}{} {}",
+            self.values_style
+                .apply_to(self.glyph("█", "#").repeat(filled)),
+            self.hint_style
+                .apply_to(self.glyph("░", "-").repeat(PROGRESS_WIDTH - filled)),
+            self.hint_style.apply_to(format!("{}/{}", pos, len))
+        )
+    }
+
+    fn format_spinner(&self, f: &mut dyn fmt::Write, message: &str, tick: usize) -> fmt::Result {
+        let frames: &[char] = if self.ascii {
+            &['|', '/', '-', '\\']
+        } else {
+            &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏']
+        };
+        write!(
+            f,
+            "{} {}",
+            self.indicator_style.apply_to(frames[tick % frames.len()]),
+            self.prompt_style.apply_to(message)
+        )
+    }
+
+    fn format_progress_done(&self, f: &mut dyn fmt::Write, message: &str) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.success_prefix,
+            self.prompt_style.apply_to(message)
+        )
     }
 }
 
+/// A theme that is either borrowed by or owned by a prompt.
+#[derive(Clone)]
+pub(crate) enum ThemeRef<'a> {
+    Borrowed(&'a (dyn Theme + Sync)),
+    Owned(Arc<dyn Theme + Send + Sync>),
+}
+
+impl<'a> Deref for ThemeRef<'a> {
+    type Target = dyn Theme + Sync + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        match *self {
+            ThemeRef::Borrowed(theme) => theme,
+            ThemeRef::Owned(ref theme) => &**theme,
+        }
+    }
+}
+
+impl<'a> ThemeRef<'a> {
+    /// Returns the theme with `overrides` applied if it supports them.
+    pub(crate) fn overridden(&self, overrides: ThemeOverrides) -> ThemeRef<'a> {
+        match self.with_overrides(&overrides) {
+            Some(theme) => ThemeRef::Owned(Arc::from(theme)),
+            None => self.clone(),
+        }
+    }
+}
+
+/// Rewrites the answer shown on a report line, or leaves the line out
+/// by returning `None`.
+pub(crate) type ReportFn<'a> = Box<dyn Fn(&str) -> Option<String> + Send + 'a>;
+
 /// Helper struct to conveniently render a theme ot a term.
 pub(crate) struct TermThemeRenderer<'a> {
-    term: &'a Term,
-    theme: &'a Theme,
+    term: &'a dyn Backend,
+    theme: ThemeRef<'a>,
     height: usize,
     prompt_height: usize,
+    prompt_rows: usize,
     prompts_reset_height: bool,
+    plain: bool,
+    sanitize: Sanitize,
+    accessible: bool,
+    column: usize,
+    frame: Option<Vec<String>>,
+    prev_frame: Vec<String>,
+    alternate_screen: bool,
+    /// The last prompt written with `write_formatted_prompt`.
+    last_prompt: Option<String>,
+    stops: usize,
+    report: Option<&'a ReportFn<'a>>,
+}
+
+/// The number of renderers showing the alternate screen.
+static ALTERNATE_SCREENS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns `true` while a prompt shows the alternate screen.
+pub(crate) fn alternate_screen_active() -> bool {
+    ALTERNATE_SCREENS.load(Ordering::SeqCst) > 0
 }
 
 impl<'a> TermThemeRenderer<'a> {
-    pub fn new(term: &'a Term, theme: &'a Theme) -> TermThemeRenderer<'a> {
+    pub fn new(term: &'a dyn Backend, theme: &'a (dyn Theme + Sync)) -> TermThemeRenderer<'a> {
+        let fallback = if is_ascii_mode() {
+            theme.ascii_fallback()
+        } else {
+            None
+        };
+        let theme = match fallback {
+            Some(fallback) => ThemeRef::Owned(Arc::from(fallback)),
+            None => ThemeRef::Borrowed(theme),
+        };
         TermThemeRenderer {
             term: term,
             theme: theme,
             height: 0,
             prompt_height: 0,
+            prompt_rows: 0,
             prompts_reset_height: true,
+            plain: is_plain_mode(term),
+            sanitize: sanitize_mode(),
+            accessible: is_accessible_mode(),
+            column: 0,
+            frame: None,
+            prev_frame: vec![],
+            alternate_screen: false,
+            last_prompt: None,
+            stops: stops(),
+            report: None,
+        }
+    }
+
+    /// Passes the answers of report lines through `report`.
+    pub fn set_report(&mut self, report: Option<&'a ReportFn<'a>>) {
+        self.report = report;
+    }
+
+    /// Returns the answer a report line shows, or `None` if the line is
+    /// left out.
+    ///
+    /// The answer is borrowed unless a reporter is set, so callers can
+    /// leave the answer to the theme if nothing rewrites it.
+    fn reported<'s>(&self, sel: &'s str) -> Option<Cow<'s, str>> {
+        match self.report {
+            Some(report) => report(sel).map(Cow::Owned),
+            None => Some(Cow::Borrowed(sel)),
         }
     }
 
@@ -369,39 +1748,249 @@ impl<'a> TermThemeRenderer<'a> {
         self.prompts_reset_height = val;
     }
 
-    pub fn term(&self) -> &Term {
+    /// Switches to the alternate screen of the terminal, which is left
+    /// with `leave_alternate_screen` or when the renderer is dropped.
+    ///
+    /// Nothing happens in the accessible mode or if the output is not a
+    /// terminal.
+    pub fn enter_alternate_screen(&mut self) -> io::Result<()> {
+        if self.accessible || self.alternate_screen || !self.term.is_term() {
+            return Ok(());
+        }
+        self.term.write_str("\x1b[?1049h\x1b[H")?;
+        self.term.flush()?;
+        self.alternate_screen = true;
+        ALTERNATE_SCREENS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Restores the screen shown before `enter_alternate_screen` and
+    /// forgets everything rendered since.
+    pub fn leave_alternate_screen(&mut self) -> io::Result<()> {
+        if !self.alternate_screen {
+            return Ok(());
+        }
+        self.alternate_screen = false;
+        ALTERNATE_SCREENS.fetch_sub(1, Ordering::SeqCst);
+        self.height = 0;
+        self.prompt_height = 0;
+        self.prev_frame.clear();
+        self.term.write_str("\x1b[?1049l")?;
+        self.term.flush()
+    }
+
+    pub fn term(&self) -> &'a dyn Backend {
         self.term
     }
 
+    /// Returns the number of rows the last prompt written with `prompt`
+    /// takes up, counting wrapped lines.
+    pub fn prompt_rows(&self) -> usize {
+        self.prompt_rows
+    }
+
+    /// Returns `true` if the prompt should render in the accessible mode.
+    pub fn is_accessible(&self) -> bool {
+        self.accessible
+    }
+
     pub fn add_line(&mut self) {
         self.height += 1;
     }
 
-    fn write_formatted_str<F: FnOnce(&mut TermThemeRenderer, &mut fmt::Write) -> fmt::Result>(
+    /// Accounts for a line the user typed after the last written prompt.
+    pub fn add_input_line(&mut self, input: &str) {
+        let width = self.term.size().1 as usize;
+        self.height += wrapped_rows(self.column + measure_text_width(input), width);
+    }
+
+    fn format<F: FnOnce(&mut TermThemeRenderer, &mut dyn fmt::Write) -> fmt::Result>(
         &mut self,
         f: F,
-    ) -> io::Result<()> {
+    ) -> io::Result<String> {
         let mut buf = String::new();
         f(self, &mut buf).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
-        self.height += buf.chars().filter(|&x| x == '\n').count();
+        if let Cow::Owned(text) = sanitize(&buf, self.sanitize) {
+            buf = text;
+        }
+        if self.plain {
+            buf = strip_ansi_codes(&buf).into_owned();
+        }
+        if let Cow::Owned(text) = visual_order(&buf) {
+            buf = text;
+        }
+        Ok(buf)
+    }
+
+    /// Returns the number of terminal rows the given text occupies.
+    fn rows(&self, text: &str) -> usize {
+        count_rows(text, self.term.size().1 as usize)
+    }
+
+    fn write_formatted_str<
+        F: FnOnce(&mut TermThemeRenderer, &mut dyn fmt::Write) -> fmt::Result,
+    >(
+        &mut self,
+        f: F,
+    ) -> io::Result<()> {
+        let buf = self.format(f)?;
+        self.height += self.rows(&buf) - 1;
+        self.column = measure_text_width(buf.rsplit('\n').next().unwrap_or(""));
+        set_line_prompt(&buf);
         self.term.write_str(&buf)
     }
 
-    fn write_formatted_line<F: FnOnce(&mut TermThemeRenderer, &mut fmt::Write) -> fmt::Result>(
+    fn write_formatted_line<
+        F: FnOnce(&mut TermThemeRenderer, &mut dyn fmt::Write) -> fmt::Result,
+    >(
         &mut self,
         f: F,
     ) -> io::Result<()> {
-        let mut buf = String::new();
-        f(self, &mut buf).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
-        self.height += buf.chars().filter(|&x| x == '\n').count() + 1;
+        let buf = self.format(f)?;
+        self.write_line_buf(buf)
+    }
+
+    fn write_line_buf(&mut self, buf: String) -> io::Result<()> {
+        if let Some(ref mut frame) = self.frame {
+            frame.extend(buf.split('\n').map(|line| line.to_string()));
+            return Ok(());
+        }
+        self.height += self.rows(&buf);
+        set_line_prompt("");
         self.term.write_line(&buf)
     }
 
-    fn write_formatted_prompt<F: FnOnce(&mut TermThemeRenderer, &mut fmt::Write) -> fmt::Result>(
+    /// Starts collecting lines into a frame instead of writing them.
+    ///
+    /// The frame is written by `end_frame`, which only rewrites the lines
+    /// that changed since the previous frame.
+    pub fn begin_frame(&mut self) {
+        self.frame = Some(vec![]);
+    }
+
+    /// Writes the collected frame, diffing it against the previous one.
+    ///
+    /// The whole frame is assembled into a single buffer so that it reaches
+    /// the terminal in one write.
+    pub fn end_frame(&mut self) -> io::Result<()> {
+        let frame = self.frame.take().unwrap_or_default();
+        let width = self.term.size().1 as usize;
+        let single_rows = |lines: &[String]| {
+            lines
+                .iter()
+                .all(|line| wrapped_rows(measure_text_width(line), width) == 1)
+        };
+        let mut out = String::new();
+        if self.stops != stops() {
+            // The shell wrote below the prompt while the process was
+            // stopped, so render it anew from the cursor.
+            self.stops = stops();
+            self.height = 0;
+            self.prev_frame.clear();
+            if let (Some(ref prompt), true) = (&self.last_prompt, self.prompts_reset_height) {
+                out.push_str(prompt);
+                out.push('\n');
+            }
+        }
+        set_line_prompt("");
+        if self.prev_frame.is_empty()
+            || self.height != self.prev_frame.len()
+            || !single_rows(&self.prev_frame)
+            || !single_rows(&frame)
+        {
+            cursor_up(&mut out, self.height);
+            for _ in 0..self.height {
+                out.push_str("\r\x1b[2K");
+                cursor_down(&mut out, 1);
+            }
+            cursor_up(&mut out, self.height);
+            self.height = 0;
+            for line in &frame {
+                self.height += self.rows(line);
+                out.push_str(line);
+                out.push('\n');
+            }
+        } else {
+            let prev_len = self.prev_frame.len();
+            let mut skipped = 0;
+            cursor_up(&mut out, prev_len);
+            for (idx, line) in frame.iter().enumerate() {
+                if idx < prev_len && self.prev_frame[idx] == *line {
+                    skipped += 1;
+                    continue;
+                }
+                cursor_down(&mut out, skipped);
+                skipped = 0;
+                if idx >= prev_len {
+                    out.push_str(line);
+                    out.push('\n');
+                } else {
+                    out.push_str("\r\x1b[2K");
+                    out.push_str(line);
+                    out.push('\r');
+                    cursor_down(&mut out, 1);
+                }
+            }
+            if prev_len > frame.len() {
+                cursor_down(&mut out, skipped);
+                skipped = 0;
+                for _ in frame.len()..prev_len {
+                    out.push_str("\r\x1b[2K");
+                    cursor_down(&mut out, 1);
+                }
+                cursor_up(&mut out, prev_len - frame.len());
+            }
+            cursor_down(&mut out, skipped);
+            self.height = frame.len();
+        }
+        self.prev_frame = frame;
+        self.term.write_str(&out)?;
+        self.term.flush()
+    }
+
+    /// Returns the line of the last frame shown at a screen row.
+    ///
+    /// Asks the terminal where the cursor is, so this must only be called
+    /// right after `end_frame`.  Frames with wrapped lines give `None`.
+    pub fn frame_line_at(&self, row: usize) -> io::Result<Option<usize>> {
+        if self.height != self.prev_frame.len() {
+            return Ok(None);
+        }
+        Ok(cursor_row(self.term)?.and_then(|cursor| {
+            let top = cursor.checked_sub(self.height)?;
+            row.checked_sub(top).filter(|&line| line < self.height)
+        }))
+    }
+
+    /// Like `write_formatted_line` but cuts every line off at the terminal
+    /// width instead of letting it wrap.
+    fn write_truncated_line<
+        F: FnOnce(&mut TermThemeRenderer, &mut dyn fmt::Write) -> fmt::Result,
+    >(
+        &mut self,
+        f: F,
+    ) -> io::Result<()> {
+        let buf = self.format(f)?;
+        let width = self.term.size().1 as usize;
+        let buf = buf
+            .split('\n')
+            .map(|line| truncate_str(line, width, "…"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.write_line_buf(buf)
+    }
+
+    fn write_formatted_prompt<
+        F: FnOnce(&mut TermThemeRenderer, &mut dyn fmt::Write) -> fmt::Result,
+    >(
         &mut self,
         f: F,
     ) -> io::Result<()> {
-        self.write_formatted_line(f)?;
+        let height = self.height;
+        let buf = self.format(f)?;
+        self.last_prompt = Some(buf.clone());
+        self.write_line_buf(buf)?;
+        self.prompt_rows = self.height - height;
         if self.prompts_reset_height {
             self.prompt_height = self.height;
             self.height = 0;
@@ -410,9 +1999,14 @@ impl<'a> TermThemeRenderer<'a> {
     }
 
     pub fn error(&mut self, err: &str) -> io::Result<()> {
+        observer::invalid(err);
         self.write_formatted_line(|this, buf| this.theme.format_error(buf, err))
     }
 
+    pub fn warning(&mut self, warning: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_warning(buf, warning))
+    }
+
     pub fn prompt(&mut self, prompt: &str) -> io::Result<()> {
         self.write_formatted_prompt(|this, buf| this.theme.format_prompt(buf, prompt))
     }
@@ -424,10 +2018,8 @@ impl<'a> TermThemeRenderer<'a> {
     }
 
     pub fn password_prompt(&mut self, prompt: &str) -> io::Result<()> {
-        self.write_formatted_str(|this, buf| {
-            write!(buf, "\r")?;
-            this.theme.format_singleline_prompt(buf, prompt, None)
-        })
+        self.term.write_str("\r")?;
+        self.write_formatted_str(|this, buf| this.theme.format_singleline_prompt(buf, prompt, None))
     }
 
     pub fn confirmation_prompt(&mut self, prompt: &str, default: Option<bool>) -> io::Result<()> {
@@ -436,60 +2028,518 @@ impl<'a> TermThemeRenderer<'a> {
         })
     }
 
+    pub fn key_prompt(
+        &mut self,
+        prompt: &str,
+        choices: &[(char, &str)],
+        default: Option<char>,
+    ) -> io::Result<()> {
+        self.write_formatted_str(|this, buf| {
+            this.theme.format_key_prompt(buf, prompt, choices, default)
+        })
+    }
+
+    pub fn default_confirmation(&mut self, prompt: &str, default: &str) -> io::Result<()> {
+        self.write_formatted_str(|this, buf| {
+            this.theme.format_default_confirmation(buf, prompt, default)
+        })
+    }
+
     pub fn confirmation_prompt_selection(&mut self, prompt: &str, sel: bool) -> io::Result<()> {
-        self.write_formatted_prompt(|this, buf| {
+        let answer = messages().yes_no(sel).to_string();
+        match self.reported(&answer) {
+            Some(Cow::Borrowed(_)) => self.write_formatted_prompt(|this, buf| {
+                this.theme
+                    .format_confirmation_prompt_selection(buf, prompt, sel)
+            }),
+            Some(Cow::Owned(answer)) => self.write_formatted_prompt(|this, buf| {
+                this.theme
+                    .format_confirmation_report(buf, prompt, sel, &answer)
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn single_prompt_selection(&mut self, prompt: &str, sel: &str) -> io::Result<()> {
+        match self.reported(sel) {
+            Some(sel) => self.write_formatted_prompt(|this, buf| {
+                this.theme.format_single_prompt_selection(buf, prompt, &sel)
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of rows `single_prompt_selection` would write.
+    pub fn report_rows(&mut self, prompt: &str, sel: &str) -> io::Result<usize> {
+        let buf =
+            self.format(|this, buf| this.theme.format_single_prompt_selection(buf, prompt, sel))?;
+        Ok(self.rows(&buf))
+    }
+
+    pub fn select_report(&mut self, prompt: &str, sel: &str) -> io::Result<()> {
+        let sel = match self.reported(sel) {
+            Some(sel) => sel,
+            None => return Ok(()),
+        };
+        self.write_formatted_prompt(|this, buf| this.theme.format_select_report(buf, prompt, &sel))
+    }
+
+    pub fn checkboxes_report(&mut self, prompt: &str, selections: &[&str]) -> io::Result<()> {
+        let joined = selections.join(", ");
+        match self.reported(&joined) {
+            Some(Cow::Borrowed(_)) => self.write_formatted_prompt(|this, buf| {
+                this.theme.format_checkboxes_report(buf, prompt, selections)
+            }),
+            Some(Cow::Owned(joined)) => self.write_formatted_prompt(|this, buf| {
+                this.theme.format_checkboxes_report(buf, prompt, &[&joined])
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn password_prompt_selection(&mut self, prompt: &str) -> io::Result<()> {
+        let hidden = messages().hidden.clone();
+        match self.reported(&hidden) {
+            Some(Cow::Borrowed(_)) => self.write_formatted_prompt(|this, buf| {
+                this.theme.format_password_prompt_selection(buf, prompt)
+            }),
+            Some(Cow::Owned(hidden)) => self.write_formatted_prompt(|this, buf| {
+                this.theme.format_password_report(buf, prompt, &hidden)
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn selection(&mut self, text: &str, style: SelectionStyle) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_selection(buf, text, style))
+    }
+
+    /// Renders a selection with the given byte range highlighted as a match.
+    pub fn selection_with_match(
+        &mut self,
+        text: &str,
+        span: Option<(usize, usize)>,
+        style: SelectionStyle,
+    ) -> io::Result<()> {
+        let (start, end) = match span {
+            Some(span) if span.0 < span.1 => span,
+            _ => return self.selection(text, style),
+        };
+        self.write_truncated_line(|this, buf| {
+            let mut highlighted = text[..start].to_string();
             this.theme
-                .format_confirmation_prompt_selection(buf, prompt, sel)
+                .format_search_match(&mut highlighted, &text[start..end])?;
+            highlighted.push_str(&text[end..]);
+            this.theme.format_selection(buf, &highlighted, style)
         })
     }
 
-    pub fn single_prompt_selection(&mut self, prompt: &str, sel: &str) -> io::Result<()> {
-        self.write_formatted_prompt(|this, buf| {
-            this.theme.format_single_prompt_selection(buf, prompt, sel)
+    /// Renders a single line prompt followed by the typed text as a line.
+    pub fn input_line(&mut self, prompt: &str, input: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| {
+            this.theme.format_singleline_prompt(buf, prompt, None)?;
+            write!(buf, "{}", input)
         })
     }
 
-    pub fn multi_prompt_selection(&mut self, prompt: &str, selections: &[&str]) -> io::Result<()> {
-        self.write_formatted_prompt(|this, buf| {
+    pub fn search_prompt(&mut self, query: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_search_prompt(buf, query))
+    }
+
+    pub fn disabled_item(&mut self, text: &str, style: SelectionStyle) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_disabled_item(buf, text, style))
+    }
+
+    pub fn tree_item(
+        &mut self,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        style: SelectionStyle,
+    ) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| {
             this.theme
-                .format_multi_prompt_selection(buf, prompt, selections)
+                .format_tree_item(buf, text, depth, expanded, style)
         })
     }
 
-    pub fn password_prompt_selection(&mut self, prompt: &str) -> io::Result<()> {
-        self.write_formatted_prompt(|this, buf| {
-            this.theme.format_password_prompt_selection(buf, prompt)
+    /// Renders formatted cells side by side, each padded to `width` columns.
+    pub fn grid_row(&mut self, cells: &[String], width: usize) -> io::Result<()> {
+        self.write_truncated_line(|_, buf| {
+            for (idx, cell) in cells.iter().enumerate() {
+                if idx + 1 < cells.len() {
+                    write!(buf, "{}", pad_str(cell, width, Alignment::Left, None))?;
+                } else {
+                    write!(buf, "{}", cell)?;
+                }
+            }
+            Ok(())
         })
     }
 
-    pub fn selection(&mut self, text: &str, style: SelectionStyle) -> io::Result<()> {
-        self.write_formatted_line(|this, buf| this.theme.format_selection(buf, text, style))
+    pub fn checkbox_tree_item(
+        &mut self,
+        text: &str,
+        depth: usize,
+        expanded: Option<bool>,
+        check: CheckState,
+        active: bool,
+    ) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| {
+            this.theme
+                .format_checkbox_tree_item(buf, text, depth, expanded, check, active)
+        })
+    }
+
+    pub fn separator(&mut self, text: &str) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_separator(buf, text))
+    }
+
+    pub fn toggle(&mut self, prompt: &str, options: (&str, &str), value: bool) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_toggle(buf, prompt, options, value))
+    }
+
+    pub fn rating(&mut self, prompt: &str, value: u8, max: u8) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_rating(buf, prompt, value, max))
+    }
+
+    pub fn table_header(&mut self, text: &str) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_table_header(buf, text))
+    }
+
+    pub fn tags(&mut self, tags: &[&str]) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_tags(buf, tags))
+    }
+
+    pub fn preview_line(&mut self, line: &str) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_preview_line(buf, line))
+    }
+
+    pub fn hint(&mut self, hint: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_hint(buf, hint))
+    }
+
+    pub fn progress_bar(&mut self, message: &str, pos: u64, len: u64) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| {
+            this.theme.format_progress_bar(buf, message, pos, len)
+        })
+    }
+
+    pub fn spinner(&mut self, message: &str, tick: usize) -> io::Result<()> {
+        self.write_truncated_line(|this, buf| this.theme.format_spinner(buf, message, tick))
+    }
+
+    pub fn progress_done(&mut self, message: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_progress_done(buf, message))
+    }
+
+    pub fn numbered_item(&mut self, number: usize, text: &str) -> io::Result<()> {
+        self.write_formatted_line(|this, buf| this.theme.format_numbered_item(buf, number, text))
     }
 
     pub fn clear(&mut self) -> io::Result<()> {
+        self.prev_frame.clear();
+        if self.accessible {
+            self.height = 0;
+            return Ok(());
+        }
         self.term
             .clear_last_lines(self.height + self.prompt_height)?;
         self.height = 0;
         Ok(())
     }
 
-    pub fn clear_preserve_prompt(&mut self, size_vec: &Vec<usize>) -> io::Result<()> {
-        let mut new_height = self.height;
-        //Check each item size, increment on finding an overflow
-        for size in size_vec {
-            if *size > self.term.size().1 as usize {
-                new_height += 1;
+    /// Clears the partially rendered prompt if the user hit Ctrl+C.
+    pub fn clear_interrupted<T>(&mut self, rv: io::Result<T>) -> io::Result<T> {
+        if let Err(ref err) = rv {
+            if err.kind() == io::ErrorKind::Interrupted && self.accessible {
+                self.term.write_line("")?;
+            } else if err.kind() == io::ErrorKind::Interrupted {
+                self.term.clear_line()?;
+                self.clear()?;
+                self.term.show_cursor()?;
             }
         }
-        self.term.clear_last_lines(new_height)?;
+        rv
+    }
+
+    pub fn clear_preserve_prompt(&mut self) -> io::Result<()> {
+        self.prev_frame.clear();
+        if self.accessible {
+            self.height = 0;
+            return Ok(());
+        }
+        self.term.clear_last_lines(self.height)?;
         self.height = 0;
         Ok(())
     }
 }
 
+impl<'a> Drop for TermThemeRenderer<'a> {
+    fn drop(&mut self) {
+        let _ = self.leave_alternate_screen();
+    }
+}
+
+fn cursor_up(out: &mut String, n: usize) {
+    if n > 0 {
+        out.push_str(&format!("\x1b[{}A", n));
+    }
+}
+
+fn cursor_down(out: &mut String, n: usize) {
+    if n > 0 {
+        out.push_str(&format!("\x1b[{}B", n));
+    }
+}
+
+/// Counts the terminal rows `text` takes up when wrapped at `width` columns.
+fn count_rows(text: &str, width: usize) -> usize {
+    text.split('\n')
+        .map(|line| wrapped_rows(measure_text_width(line), width))
+        .sum()
+}
+
+/// Counts the terminal rows a single line of `line_width` columns takes up.
+fn wrapped_rows(line_width: usize, width: usize) -> usize {
+    if width == 0 || line_width == 0 {
+        1
+    } else {
+        line_width.div_ceil(width)
+    }
+}
+
 /// Returns the default theme.
 ///
 /// (This returns the simple theme)
-pub(crate) fn get_default_theme() -> &'static Theme {
+pub(crate) fn get_default_theme() -> &'static (dyn Theme + Sync) {
     &SimpleTheme
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_rows() {
+        assert_eq!(count_rows("", 10), 1);
+        assert_eq!(count_rows("0123456789", 10), 1);
+        assert_eq!(count_rows("0123456789a", 10), 2);
+        assert_eq!(count_rows("ab\ncd", 10), 2);
+        assert_eq!(count_rows("日本語日本語", 10), 2);
+        assert_eq!(count_rows("\x1b[1mbold\x1b[0m", 4), 1);
+    }
+
+    #[test]
+    fn test_progress_cells() {
+        assert_eq!(progress_cells(0, 10, 30), 0);
+        assert_eq!(progress_cells(5, 10, 30), 15);
+        assert_eq!(progress_cells(12, 10, 30), 30);
+        assert_eq!(progress_cells(0, 0, 30), 30);
+    }
+
+    #[test]
+    fn test_key_choices() {
+        let choices = [('a', "apply"), ('s', "Skip"), ('?', "help")];
+        assert_eq!(key_choices(&choices, Some('s')), "[a]pply [S]kip [?] help");
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let text = "\x1b[1mmain\x1b[0m\x1b[2J\ta\x07\x1b]8;;http://x\x1b\\b\n";
+        assert_eq!(sanitize(text, Sanitize::Strip), "\x1b[1mmain\x1b[0m ab\n");
+        assert_eq!(
+            sanitize(text, Sanitize::Escape),
+            "\x1b[1mmain\x1b[0m^[[2J a^G^[]8;;http://x^[\\b\n"
+        );
+        assert_eq!(sanitize(text, Sanitize::Off), text);
+        assert!(matches!(
+            sanitize("plain\n", Sanitize::Strip),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_plain_colorful_theme() {
+        let theme = ColorfulTheme::plain();
+        let mut buf = String::new();
+        theme
+            .format_selection(&mut buf, "Vanilla", SelectionStyle::CheckboxCheckedSelected)
+            .unwrap();
+        assert_eq!(buf, "❯ ◉ Vanilla");
+        buf.clear();
+        theme
+            .format_single_prompt_selection(&mut buf, "Flavor", "Vanilla")
+            .unwrap();
+        assert_eq!(buf, "✔ Flavor · Vanilla");
+        buf.clear();
+        theme.format_numbered_item(&mut buf, 2, "Vanilla").unwrap();
+        assert_eq!(buf, "2. Vanilla");
+        buf.clear();
+        theme
+            .format_checkboxes_report(&mut buf, "Features", &["tls", "metrics"])
+            .unwrap();
+        assert_eq!(buf, "✔ Features · tls, metrics (2 selected)");
+        buf.clear();
+        theme
+            .format_toggle(&mut buf, "Completions", ("Install", "Skip"), false)
+            .unwrap();
+        assert_eq!(buf, "? Completions ›  Install  [Skip]");
+        buf.clear();
+        theme.format_rating(&mut buf, "Rate", 2, 4).unwrap();
+        assert_eq!(buf, "? Rate › ★★☆☆");
+    }
+
+    #[test]
+    fn test_ascii_colorful_theme() {
+        let theme = ColorfulTheme {
+            active_item_prefix: style("»".to_string()),
+            ..ColorfulTheme::plain()
+        }
+        .to_ascii();
+        let mut buf = String::new();
+        theme
+            .format_selection(&mut buf, "Vanilla", SelectionStyle::CheckboxCheckedSelected)
+            .unwrap();
+        assert_eq!(buf, "> [x] Vanilla");
+        buf.clear();
+        theme
+            .format_single_prompt_selection(&mut buf, "Flavor", "Vanilla")
+            .unwrap();
+        assert_eq!(buf, "+ Flavor : Vanilla");
+        buf.clear();
+        theme.format_rating(&mut buf, "Rate", 2, 4).unwrap();
+        assert_eq!(buf, "? Rate > **..");
+    }
+
+    #[test]
+    fn test_ascii_keeps_glyph_styles() {
+        console::set_colors_enabled(false);
+        let forced = |glyph: &StyledObject<String>| glyph.clone().force_styling(true).to_string();
+        let theme = ColorfulTheme {
+            active_item_prefix: style("»".to_string()).magenta().underlined(),
+            checked_item_prefix: style("◉".to_string()).color256(208).on_blue(),
+            ..ColorfulTheme::default()
+        }
+        .to_ascii();
+        let expected = style(">".to_string()).magenta().underlined();
+        assert_eq!(forced(&theme.active_item_prefix), forced(&expected));
+        let expected = style("[x]".to_string()).color256(208).on_blue();
+        assert_eq!(forced(&theme.checked_item_prefix), forced(&expected));
+        let expected = style("x".to_string()).red();
+        assert_eq!(forced(&theme.error_prefix), forced(&expected));
+        assert_eq!(theme.checked_item_prefix.to_string(), "[x]");
+    }
+
+    #[test]
+    fn test_overlay() {
+        let overrides = ThemeOverrides {
+            prompt_prefix: Some(style("»".to_string())),
+            success_suffix: Some(style("=".to_string())),
+            ..ThemeOverrides::default()
+        };
+        let theme = ColorfulTheme::overlay(&ColorfulTheme::plain(), &overrides);
+        let mut buf = String::new();
+        theme
+            .format_toggle(&mut buf, "Completions", ("Install", "Skip"), true)
+            .unwrap();
+        assert_eq!(buf, "» Completions › [Install]  Skip ");
+        buf.clear();
+        theme
+            .format_single_prompt_selection(&mut buf, "Flavor", "Vanilla")
+            .unwrap();
+        assert_eq!(buf, "✔ Flavor = Vanilla");
+    }
+
+    #[test]
+    fn test_overlay_every_field() {
+        let glyph = || Some(style("»".to_string()).magenta());
+        let magenta = || Some(Style::new().magenta());
+        // no `..ThemeOverrides::default()` so that new fields must be added
+        let overrides = ThemeOverrides {
+            defaults_style: magenta(),
+            prompt_style: magenta(),
+            prompt_prefix: glyph(),
+            prompt_suffix: glyph(),
+            success_prefix: glyph(),
+            success_suffix: glyph(),
+            error_prefix: glyph(),
+            error_style: magenta(),
+            warning_prefix: glyph(),
+            warning_style: magenta(),
+            indicator_style: magenta(),
+            active_item_prefix: glyph(),
+            inactive_item_prefix: glyph(),
+            checked_item_prefix: glyph(),
+            unchecked_item_prefix: glyph(),
+            partial_item_prefix: glyph(),
+            inactive_style: magenta(),
+            active_style: magenta(),
+            yes_style: magenta(),
+            no_style: magenta(),
+            values_style: magenta(),
+            hint_style: magenta(),
+            disabled_style: magenta(),
+            match_style: magenta(),
+            hotkey_style: magenta(),
+            ascii: Some(true),
+        };
+        let theme = ColorfulTheme::overlay(&ColorfulTheme::default(), &overrides);
+        let forced = |glyph: &StyledObject<String>| glyph.clone().force_styling(true).to_string();
+        let expected = forced(&glyph().unwrap());
+        let glyphs = [
+            &theme.prompt_prefix,
+            &theme.prompt_suffix,
+            &theme.success_prefix,
+            &theme.success_suffix,
+            &theme.error_prefix,
+            &theme.warning_prefix,
+            &theme.active_item_prefix,
+            &theme.inactive_item_prefix,
+            &theme.checked_item_prefix,
+            &theme.unchecked_item_prefix,
+            &theme.partial_item_prefix,
+        ];
+        for glyph in glyphs.iter() {
+            assert_eq!(forced(glyph), expected);
+        }
+        let styles = [
+            &theme.defaults_style,
+            &theme.prompt_style,
+            &theme.error_style,
+            &theme.warning_style,
+            &theme.indicator_style,
+            &theme.inactive_style,
+            &theme.active_style,
+            &theme.yes_style,
+            &theme.no_style,
+            &theme.values_style,
+            &theme.hint_style,
+            &theme.disabled_style,
+            &theme.match_style,
+            &theme.hotkey_style,
+        ];
+        for style in styles.iter() {
+            assert_eq!(**style, magenta().unwrap());
+        }
+        assert!(theme.ascii);
+    }
+
+    #[test]
+    fn test_overridden() {
+        let cyan = Style::new().cyan().force_styling(true);
+        let overrides = || ThemeOverrides {
+            prompt_style: Some(cyan.clone()),
+            ..ThemeOverrides::default()
+        };
+        let colorful = ColorfulTheme::plain();
+        let theme = ThemeRef::Borrowed(&colorful).overridden(overrides());
+        let mut buf = String::new();
+        theme.format_prompt(&mut buf, "Name").unwrap();
+        assert_eq!(buf, format!("? {} ›", cyan.apply_to("Name")));
+        let theme = ThemeRef::Borrowed(&SimpleTheme).overridden(overrides());
+        buf.clear();
+        theme.format_prompt(&mut buf, "Name").unwrap();
+        assert_eq!(buf, "Name:");
+    }
+}
@@ -0,0 +1,158 @@
+//! Loading of themes from configuration files.
+use std::collections::HashMap;
+use std::io;
+
+use console::Style;
+use serde::Deserialize;
+
+use theme::{colorful_glyph, glyph_field, ColorfulTheme, COLORFUL_GLYPHS};
+
+/// The on-disk representation of a `ColorfulTheme`.
+///
+/// Every key is optional; missing keys keep the default look.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeConfig {
+    glyphs: HashMap<String, String>,
+    styles: HashMap<String, String>,
+}
+
+impl ColorfulTheme {
+    /// Loads a theme from a TOML document.
+    ///
+    /// The document can contain a `glyphs` and a `styles` table which
+    /// override the defaults of `ColorfulTheme`.  Glyphs are keyed by their
+    /// field name, styles by the field name without the `_style` suffix or
+    /// by the name of the glyph they apply to.  Styles are given as dotted
+    /// strings as understood by `console::Style::from_dotted_str`:
+    ///
+    /// ```toml
+    /// [glyphs]
+    /// prompt_prefix = "»"
+    /// active_item_prefix = ">"
+    ///
+    /// [styles]
+    /// prompt_prefix = "magenta.bold"
+    /// error = "red.bold"
+    /// values = "cyan"
+    /// ```
+    ///
+    /// This requires the `serde` feature.
+    pub fn from_toml(s: &str) -> io::Result<ColorfulTheme> {
+        let config: ThemeConfig =
+            toml::from_str(s).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        config.into_theme()
+    }
+
+    /// Loads a theme from a JSON document.
+    ///
+    /// The structure is the same as for `from_toml`.
+    ///
+    /// This requires the `serde` feature.
+    pub fn from_json(s: &str) -> io::Result<ColorfulTheme> {
+        let config: ThemeConfig = serde_json::from_str(s)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        config.into_theme()
+    }
+}
+
+impl ThemeConfig {
+    fn into_theme(mut self) -> io::Result<ColorfulTheme> {
+        let mut theme = ColorfulTheme::default();
+
+        for &(key, _, _, _) in COLORFUL_GLYPHS {
+            let text = self.glyphs.remove(key);
+            let spec = self.styles.remove(key);
+            if text.is_none() && spec.is_none() {
+                continue;
+            }
+            let (default_text, default_style) = colorful_glyph(key).unwrap();
+            let glyph_style = spec.map_or(default_style, |spec| Style::from_dotted_str(&spec));
+            *glyph_field(&mut theme, key) =
+                glyph_style.apply_to(text.unwrap_or_else(|| default_text.into()));
+        }
+        if let Some(key) = self.glyphs.keys().next() {
+            return Err(unknown_key("glyph", key));
+        }
+
+        for (key, spec) in self.styles {
+            *style_field(&mut theme, &key)? = Style::from_dotted_str(&spec);
+        }
+
+        Ok(theme)
+    }
+}
+
+fn unknown_key(kind: &str, key: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unknown {} '{}' in theme", kind, key),
+    )
+}
+
+fn style_field<'a>(theme: &'a mut ColorfulTheme, key: &str) -> io::Result<&'a mut Style> {
+    Ok(match key {
+        "defaults" => &mut theme.defaults_style,
+        "prompt" => &mut theme.prompt_style,
+        "error" => &mut theme.error_style,
+        "warning" => &mut theme.warning_style,
+        "indicator" => &mut theme.indicator_style,
+        "inactive" => &mut theme.inactive_style,
+        "active" => &mut theme.active_style,
+        "yes" => &mut theme.yes_style,
+        "no" => &mut theme.no_style,
+        "values" => &mut theme.values_style,
+        "hint" => &mut theme.hint_style,
+        "disabled" => &mut theme.disabled_style,
+        "match" => &mut theme.match_style,
+        "hotkey" => &mut theme.hotkey_style,
+        _ => return Err(unknown_key("style", key)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let theme = ColorfulTheme::from_toml(
+            r#"
+            [glyphs]
+            prompt_prefix = "»"
+            error_prefix = "!"
+
+            [styles]
+            prompt_prefix = "magenta"
+            error = "red.bold"
+            "#,
+        )
+        .unwrap();
+        let expected = Style::new().magenta().force_styling(true);
+        assert_eq!(
+            theme.prompt_prefix.force_styling(true).to_string(),
+            expected.apply_to("»").to_string()
+        );
+        let expected = Style::from_dotted_str("red").force_styling(true);
+        assert_eq!(
+            theme.error_prefix.force_styling(true).to_string(),
+            expected.apply_to("!").to_string()
+        );
+        assert_eq!(
+            format!("{:?}", theme.error_style),
+            format!("{:?}", Style::new().red().bold())
+        );
+    }
+
+    #[test]
+    fn test_from_json_unknown_key() {
+        let err = ColorfulTheme::from_json(r#"{"styles": {"nope": "red"}}"#)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = ColorfulTheme::from_json(r#"{"glyphs": {"nope": "x"}}"#)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
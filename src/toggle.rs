@@ -0,0 +1,295 @@
+use std::io;
+use std::sync::Arc;
+
+use console::{Key, Style};
+
+use backend::{Backend, HiddenCursor, TermGuard};
+use events::{read_key, read_plain_line, wait_for_event, Event};
+use messages::{fill, messages};
+use observer::observe;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef};
+
+/// Renders a switch between two options.
+///
+/// The options are shown side by side and switched with the left and
+/// right arrow keys (or `h`, `l` and tab).  Enter confirms the active
+/// option; the result is `true` for the first one.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::Toggle;
+///
+/// let install = Toggle::new()
+///     .with_prompt("Shell completions")
+///     .options("Install", "Skip")
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Toggle<'a> {
+    prompt: String,
+    options: (String, String),
+    default: bool,
+    answer: Option<bool>,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a> Toggle<'a> {
+    /// Creates a new toggle with the options "Yes" and "No".
+    pub fn new() -> Toggle<'static> {
+        Toggle::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> Toggle<'a> {
+        Toggle::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> Toggle<'static> {
+        Toggle::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> Toggle<'a> {
+        Toggle {
+            prompt: "".into(),
+            options: ("Yes".into(), "No".into()),
+            default: true,
+            answer: None,
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Sets the prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut Toggle<'a> {
+        self.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut Toggle<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the chosen option for this toggle alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut Toggle<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            indicator_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the labels of the two options.
+    pub fn options(&mut self, on: &str, off: &str) -> &mut Toggle<'a> {
+        self.options = (on.into(), off.into());
+        self
+    }
+
+    /// Sets whether the first option is active initially.
+    ///
+    /// The default is `true`.
+    pub fn default(&mut self, val: bool) -> &mut Toggle<'a> {
+        self.default = val;
+        self
+    }
+
+    /// Answers the prompt with `value` instead of asking the user.
+    ///
+    /// `interact` returns it right away and only prints the report line,
+    /// e.g. to drive a CLI through its prompts in integration tests.
+    pub fn with_answer(&mut self, value: bool) -> &mut Toggle<'a> {
+        self.answer = Some(value);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut Toggle<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns whether the first option was
+    /// picked.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<bool> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<bool>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, Toggle::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<bool> {
+        observe(
+            &self.prompt,
+            || self.read_answer(term),
+            |&value| Some(self.label(value).into()),
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend) -> io::Result<bool> {
+        let _guard = TermGuard::new(term);
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if let Some(value) = self.answer {
+            render.single_prompt_selection(&self.prompt, self.label(value))?;
+            return Ok(value);
+        }
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render);
+        }
+        let options = (self.options.0.as_str(), self.options.1.as_str());
+        let _cursor = HiddenCursor::new(term)?;
+        let mut value = self.default;
+        loop {
+            render.begin_frame();
+            render.toggle(&self.prompt, options, value)?;
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                continue;
+            }
+            match render.clear_interrupted(read_key(term))? {
+                Key::ArrowLeft | Key::Char('h') => value = true,
+                Key::ArrowRight | Key::Char('l') => value = false,
+                Key::Tab | Key::Char(' ') => value = !value,
+                Key::Enter => {
+                    render.clear()?;
+                    render.single_prompt_selection(&self.prompt, self.label(value))?;
+                    return Ok(value);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the label of an option as a typed line for the accessible
+    /// render mode.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+    ) -> io::Result<bool> {
+        let prompt = format!("{} ({}/{})", self.prompt, self.options.0, self.options.1);
+        loop {
+            render.input_prompt(&prompt, Some(self.label(self.default)))?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let input = input.trim().to_lowercase();
+            let value = if input.is_empty() {
+                self.default
+            } else if self.options.0.to_lowercase().starts_with(&input) {
+                true
+            } else if self.options.1.to_lowercase().starts_with(&input) {
+                false
+            } else {
+                let options: [(&str, &dyn ToString); 2] =
+                    [("on", &self.options.0), ("off", &self.options.1)];
+                render.error(&fill(&messages().answer_either, &options))?;
+                continue;
+            };
+            render.single_prompt_selection(&self.prompt, self.label(value))?;
+            return Ok(value);
+        }
+    }
+
+    fn label(&self, value: bool) -> &str {
+        if value {
+            &self.options.0
+        } else {
+            &self.options.1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use theme::ColorfulTheme;
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_toggle_styles() {
+        let bold = Style::new().bold().force_styling(true);
+        let magenta = Style::new().magenta().force_styling(true);
+        let mut term = VirtualTerm::new(5, 60);
+        term.colors(true).keys(&[Key::Enter]);
+        Toggle::with_owned_theme(ColorfulTheme::plain())
+            .with_prompt("Completions")
+            .prompt_style(bold.clone())
+            .highlight_style(magenta.clone())
+            .interact_on(&term)
+            .unwrap();
+        let output = term.output();
+        assert!(output.contains(&bold.apply_to("Completions").to_string()));
+        assert!(output.contains(&magenta.apply_to("[Yes]").to_string()));
+        assert_eq!(term.frames()[0], "? Completions › [Yes]  No");
+    }
+
+    #[test]
+    fn test_toggle_keys() {
+        let mut term = VirtualTerm::new(5, 60);
+        term.keys(&[
+            Key::ArrowRight,
+            Key::Tab,
+            Key::Char(' '),
+            Key::ArrowLeft,
+            Key::Enter,
+        ]);
+        let install = Toggle::new()
+            .with_prompt("Completions")
+            .options("Install", "Skip")
+            .interact_on(&term)
+            .unwrap();
+        assert!(install);
+        assert_eq!(
+            term.frames(),
+            vec![
+                "Completions: [Install]  Skip",
+                "Completions:  Install  [Skip]",
+                "Completions: [Install]  Skip",
+                "Completions:  Install  [Skip]",
+                "Completions: [Install]  Skip",
+            ]
+        );
+        assert_eq!(term.screen(), "Completions: Install");
+
+        let mut term = VirtualTerm::new(5, 60);
+        term.keys(&[Key::Char('h'), Key::Char('l'), Key::Enter]);
+        let install = Toggle::new()
+            .with_prompt("Completions")
+            .interact_on(&term)
+            .unwrap();
+        assert!(!install);
+        assert_eq!(term.screen(), "Completions: No");
+    }
+
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(Toggle::new());
+        assert_send(Toggle::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,520 @@
+use std::io;
+use std::sync::Arc;
+
+use console::{Key, Style};
+
+use backend::{Backend, HiddenCursor, TermGuard};
+use events::{read_key, read_plain_line, wait_for_event, Event};
+use keymap::{Action, KeyMap};
+use messages::messages;
+use observer::observe;
+use paging::Paging;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{
+    get_default_theme, SelectionStyle, TermThemeRenderer, Theme, ThemeOverrides, ThemeRef,
+};
+
+/// An item or group header of a tree menu.
+struct Node {
+    label: String,
+    depth: usize,
+    group: bool,
+}
+
+/// Renders a selection menu with items nested under collapsible groups.
+///
+/// Groups are expanded and collapsed with the right and left arrow keys
+/// (or `l` and `h`), enter on a group toggles it.  The result is the path
+/// to the selected leaf: the index of each group among its siblings,
+/// followed by the index of the leaf within its group.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::TreeSelect;
+///
+/// let path = TreeSelect::new()
+///     .with_prompt("Availability zone")
+///     .group("eu-west-1")
+///     .items(&["eu-west-1a", "eu-west-1b"])
+///     .end_group()
+///     .group("us-east-1")
+///     .items(&["us-east-1a", "us-east-1b", "us-east-1c"])
+///     .end_group()
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct TreeSelect<'a> {
+    nodes: Vec<Node>,
+    depth: usize,
+    prompt: Option<String>,
+    clear: bool,
+    expanded: bool,
+    theme: ThemeRef<'a>,
+    paged: bool,
+    help: Option<String>,
+    keymap: KeyMap,
+    stream: Stream,
+}
+
+impl<'a> TreeSelect<'a> {
+    /// Creates the prompt with a specific text.
+    pub fn new() -> TreeSelect<'static> {
+        TreeSelect::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> TreeSelect<'a> {
+        TreeSelect::with_theme_ref(ThemeRef::Borrowed(theme))
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> TreeSelect<'static> {
+        TreeSelect::with_theme_ref(ThemeRef::Owned(Arc::new(theme)))
+    }
+
+    fn with_theme_ref(theme: ThemeRef<'a>) -> TreeSelect<'a> {
+        TreeSelect {
+            nodes: vec![],
+            depth: 0,
+            prompt: None,
+            clear: true,
+            expanded: false,
+            theme,
+            paged: false,
+            help: None,
+            keymap: TreeSelect::default_keymap(),
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// The default key bindings with arrow keys and `h`/`l` bound to
+    /// collapsing and expanding groups instead of paging.
+    pub fn default_keymap() -> KeyMap {
+        let mut keymap = KeyMap::default();
+        keymap
+            .bind(Key::ArrowLeft, Action::Collapse)
+            .bind(Key::Char('h'), Action::Collapse)
+            .bind(Key::ArrowRight, Action::Expand)
+            .bind(Key::Char('l'), Action::Expand);
+        keymap
+    }
+
+    /// Enables or disables paging
+    pub fn paged(&mut self, val: bool) -> &mut TreeSelect<'a> {
+        self.paged = val;
+        self
+    }
+
+    /// Sets the clear behavior of the menu.
+    ///
+    /// The default is to clear the menu.
+    pub fn clear(&mut self, val: bool) -> &mut TreeSelect<'a> {
+        self.clear = val;
+        self
+    }
+
+    /// Shows all groups expanded initially.
+    ///
+    /// By default all groups start collapsed.
+    pub fn expanded(&mut self, val: bool) -> &mut TreeSelect<'a> {
+        self.expanded = val;
+        self
+    }
+
+    /// Shows a help text below the items.
+    pub fn with_help(&mut self, help: &str) -> &mut TreeSelect<'a> {
+        self.help = Some(help.to_string());
+        self
+    }
+
+    /// Sets the key bindings of the menu.
+    ///
+    /// Start from `TreeSelect::default_keymap` to keep the bindings for
+    /// expanding and collapsing groups.
+    pub fn with_keymap(&mut self, keymap: KeyMap) -> &mut TreeSelect<'a> {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Starts a group; following items are nested under it until
+    /// `end_group` is called.  Groups can be nested.
+    pub fn group(&mut self, label: &str) -> &mut TreeSelect<'a> {
+        self.push_node(label.to_string(), true);
+        self.depth += 1;
+        self
+    }
+
+    /// Ends the innermost open group.
+    pub fn end_group(&mut self) -> &mut TreeSelect<'a> {
+        self.depth = self.depth.saturating_sub(1);
+        self
+    }
+
+    /// Add a single item to the current group.
+    pub fn item(&mut self, item: &str) -> &mut TreeSelect<'a> {
+        self.push_node(item.to_string(), false);
+        self
+    }
+
+    /// Adds multiple items to the current group.
+    pub fn items<T: ToString>(&mut self, items: &[T]) -> &mut TreeSelect<'a> {
+        for item in items {
+            self.push_node(item.to_string(), false);
+        }
+        self
+    }
+
+    /// Prefaces the menu with a prompt.
+    ///
+    /// When a prompt is set the system also prints out a confirmation after
+    /// the selection.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut TreeSelect<'a> {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut TreeSelect<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the style of the highlighted item for this menu alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn highlight_style(&mut self, style: Style) -> &mut TreeSelect<'a> {
+        self.theme = self.theme.overridden(ThemeOverrides {
+            active_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    fn push_node(&mut self, label: String, group: bool) {
+        self.nodes.push(Node {
+            label,
+            depth: self.depth,
+            group,
+        });
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut TreeSelect<'a> {
+        self.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the path of the selected item.
+    ///
+    /// The dialog is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<Vec<usize>> {
+        self.interact_on(&self.stream.term())
+    }
+
+    /// Like `interact` but returns `None` if the user cancelled with Esc or 'q'.
+    pub fn interact_opt(&self) -> io::Result<Option<Vec<usize>>> {
+        self._interact_on(&self.stream.term(), true)
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<Vec<usize>>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, TreeSelect::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<Vec<usize>> {
+        self._interact_on(term, false)?
+            .ok_or_else(|| io::Error::other("Quit not allowed in this case"))
+    }
+
+    /// Like `interact_opt` but allows a specific terminal to be set.
+    pub fn interact_on_opt(&self, term: &dyn Backend) -> io::Result<Option<Vec<usize>>> {
+        self._interact_on(term, true)
+    }
+
+    fn _interact_on(&self, term: &dyn Backend, allow_quit: bool) -> io::Result<Option<Vec<usize>>> {
+        let prompt = self.prompt.as_deref().unwrap_or("");
+        observe(
+            prompt,
+            || self.read_answer(term, allow_quit),
+            |rv| {
+                rv.as_ref().map(|path| {
+                    let idx = (0..self.nodes.len()).find(|&idx| self.path(idx) == *path);
+                    idx.map_or_else(String::new, |idx| self.label_path(idx))
+                })
+            },
+        )
+    }
+
+    fn read_answer(&self, term: &dyn Backend, allow_quit: bool) -> io::Result<Option<Vec<usize>>> {
+        let _guard = TermGuard::new(term);
+        if !self.nodes.iter().any(|node| !node.group) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tree menu has no items",
+            ));
+        }
+        let mut render = TermThemeRenderer::new(term, &*self.theme);
+        if render.is_accessible() {
+            return self.interact_accessible(term, &mut render, allow_quit);
+        }
+        let mut expanded = vec![self.expanded; self.nodes.len()];
+        let _cursor = HiddenCursor::new(term)?;
+        let mut paging = Paging::new(self.nodes.len(), self.paged);
+        let mut sel = 0;
+        let mut show_keys = false;
+        let mut actions = vec![
+            Action::Up,
+            Action::Down,
+            Action::Expand,
+            Action::Collapse,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Confirm,
+        ];
+        if allow_quit {
+            actions.push(Action::Cancel);
+        }
+        actions.push(Action::Help);
+        let keys = self.keymap.help_lines(&actions);
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        loop {
+            let visible = self.visible(&expanded);
+            let pos = visible.iter().position(|&idx| idx == sel).unwrap_or(0);
+            let reserved = render.prompt_rows()
+                + self.help.is_some() as usize
+                + if show_keys { keys.len() } else { 0 };
+            paging.set_items(visible.len());
+            paging.update(term, reserved, pos);
+            render.begin_frame();
+            for &idx in visible.iter().skip(paging.offset()).take(paging.capacity()) {
+                let node = &self.nodes[idx];
+                render.tree_item(
+                    &node.label,
+                    node.depth,
+                    if node.group {
+                        Some(expanded[idx])
+                    } else {
+                        None
+                    },
+                    if idx == sel {
+                        SelectionStyle::MenuSelected
+                    } else {
+                        SelectionStyle::MenuUnselected
+                    },
+                )?;
+            }
+            if let Some(indicator) = paging.indicator(pos) {
+                render.hint(&indicator)?;
+            }
+            if let Some(ref help) = self.help {
+                render.hint(help)?;
+            }
+            if show_keys {
+                for key in &keys {
+                    render.hint(key)?;
+                }
+            }
+            render.end_frame()?;
+            if wait_for_event(term, None, true)? == Event::Resize {
+                render.clear()?;
+                if let Some(ref prompt) = self.prompt {
+                    render.prompt(prompt)?;
+                }
+                continue;
+            }
+            let action = self
+                .keymap
+                .action(&render.clear_interrupted(read_key(term))?);
+            if let Some(new_pos) = action.and_then(|action| paging.handle_action(action, pos)) {
+                sel = visible[new_pos];
+                continue;
+            }
+            let node = &self.nodes[sel];
+            match action {
+                Some(Action::Down) => sel = visible[(pos + 1) % visible.len()],
+                Some(Action::Up) => sel = visible[(pos + visible.len() - 1) % visible.len()],
+                Some(Action::Expand) if node.group => expanded[sel] = true,
+                Some(Action::Collapse) if node.group && expanded[sel] => expanded[sel] = false,
+                Some(Action::Collapse) => {
+                    if let Some(parent) = self.parent(sel) {
+                        sel = parent;
+                    }
+                }
+                Some(Action::Confirm) | Some(Action::Toggle) if node.group => {
+                    expanded[sel] = !expanded[sel];
+                }
+                Some(Action::Confirm) | Some(Action::Toggle) => {
+                    return self.finish(&mut render, sel).map(Some);
+                }
+                Some(Action::Cancel) if allow_quit => {
+                    if self.clear {
+                        render.clear_preserve_prompt()?;
+                    }
+                    return Ok(None);
+                }
+                Some(Action::Help) => show_keys = !show_keys,
+                _ => {}
+            }
+        }
+    }
+
+    /// Prints the leaves as a numbered list below their groups and reads
+    /// the typed number.
+    fn interact_accessible(
+        &self,
+        term: &dyn Backend,
+        render: &mut TermThemeRenderer,
+        allow_quit: bool,
+    ) -> io::Result<Option<Vec<usize>>> {
+        if let Some(ref prompt) = self.prompt {
+            render.prompt(prompt)?;
+        }
+        let mut leaves = vec![];
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let label = format!("{}{}", "  ".repeat(node.depth), node.label);
+            if node.group {
+                render.separator(&label)?;
+            } else {
+                leaves.push(idx);
+                render.numbered_item(leaves.len(), &label)?;
+            }
+        }
+        if let Some(ref help) = self.help {
+            render.hint(help)?;
+        }
+        loop {
+            render.input_prompt(&messages().enter_number, None)?;
+            let input = render.clear_interrupted(read_plain_line(term))?;
+            let input = input.trim();
+            if allow_quit && input == "q" {
+                return Ok(None);
+            }
+            match input.parse::<usize>() {
+                Ok(number) if number >= 1 && number <= leaves.len() => {
+                    return self.finish(render, leaves[number - 1]).map(Some);
+                }
+                _ => render.error(&messages().invalid_number)?,
+            }
+        }
+    }
+
+    /// Clears the menu and reports the selection of a leaf.
+    fn finish(&self, render: &mut TermThemeRenderer, sel: usize) -> io::Result<Vec<usize>> {
+        if self.clear {
+            render.clear()?;
+        }
+        if let Some(ref prompt) = self.prompt {
+            render.single_prompt_selection(prompt, &self.label_path(sel))?;
+        }
+        Ok(self.path(sel))
+    }
+
+    /// Returns the labels of a node and its groups as `group / leaf`.
+    fn label_path(&self, sel: usize) -> String {
+        let mut labels = vec![self.nodes[sel].label.as_str()];
+        let mut idx = sel;
+        while let Some(parent) = self.parent(idx) {
+            labels.insert(0, &self.nodes[parent].label);
+            idx = parent;
+        }
+        labels.join(" / ")
+    }
+
+    /// Returns the indices of the nodes not hidden in collapsed groups.
+    fn visible(&self, expanded: &[bool]) -> Vec<usize> {
+        let mut visible = vec![];
+        let mut collapsed_depth = None;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            match collapsed_depth {
+                Some(depth) if node.depth > depth => continue,
+                _ => collapsed_depth = None,
+            }
+            visible.push(idx);
+            if node.group && !expanded[idx] {
+                collapsed_depth = Some(node.depth);
+            }
+        }
+        visible
+    }
+
+    /// Returns the group a node is nested in.
+    fn parent(&self, idx: usize) -> Option<usize> {
+        let depth = self.nodes[idx].depth;
+        (0..idx)
+            .rev()
+            .find(|&parent| self.nodes[parent].depth < depth)
+    }
+
+    /// Returns the index of a node and of each of its groups among their
+    /// siblings, outermost first.
+    fn path(&self, idx: usize) -> Vec<usize> {
+        let mut path: Vec<usize> = vec![];
+        for node in &self.nodes[..=idx] {
+            if path.len() > node.depth {
+                path.truncate(node.depth + 1);
+                path[node.depth] += 1;
+            } else {
+                path.push(0);
+            }
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_navigation() {
+        let mut tree = TreeSelect::new();
+        tree.group("eu")
+            .items(&["eu-1a", "eu-1b"])
+            .end_group()
+            .group("us")
+            .item("us-1a")
+            .group("us-2")
+            .item("us-2a")
+            .end_group()
+            .end_group()
+            .item("local");
+        assert_eq!(tree.path(2), vec![0, 1]);
+        assert_eq!(tree.path(6), vec![1, 1, 0]);
+        assert_eq!(tree.path(7), vec![2]);
+        assert_eq!(tree.parent(6), Some(5));
+        assert_eq!(tree.parent(7), None);
+
+        let mut expanded = vec![false; tree.nodes.len()];
+        assert_eq!(tree.visible(&expanded), vec![0, 3, 7]);
+        expanded[3] = true;
+        assert_eq!(tree.visible(&expanded), vec![0, 3, 4, 5, 7]);
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(TreeSelect::new());
+        assert_send(TreeSelect::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -0,0 +1,543 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use console::Style;
+
+use backend::Backend;
+use messages::{fill, messages};
+use prompts::Input;
+#[cfg(feature = "tokio")]
+use rt::{run_detached_mut, PromptFuture};
+use stream::Stream;
+use theme::{get_default_theme, Theme, ThemeOverrides, ThemeRef};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The units of a duration in nanoseconds, largest first.
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("d", 86_400 * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// The decimal units of a byte size, largest first.
+const DECIMAL_UNITS: &[(&str, u128)] = &[
+    ("PB", 1_000_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("kB", 1_000),
+    ("B", 1),
+];
+
+/// The binary units of a byte size, largest first.
+const BINARY_UNITS: &[(&str, u128)] = &[
+    ("PiB", 1 << 50),
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+];
+
+/// Renders an input prompt for a duration such as `90s`, `5m` or `1h30m`.
+///
+/// Numbers may have decimals and several of them add up, e.g. `1.5h` is
+/// the same as `1h 30m`.  The units are `d`, `h`, `m`, `s`, `ms`, `us`
+/// and `ns`.  The report line shows the duration normalized, e.g. `90m`
+/// as `1h30m`.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::time::Duration;
+/// use dialoguer::DurationInput;
+///
+/// let timeout = DurationInput::new()
+///     .with_prompt("Timeout")
+///     .default(Duration::from_secs(30))
+///     .max(Duration::from_secs(3600))
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct DurationInput<'a> {
+    spec: Spec<'a, Duration>,
+}
+
+/// Renders an input prompt for a number of bytes such as `512MB` or
+/// `1.5GiB`.
+///
+/// Decimal units (`kB`, `MB`, `GB`, `TB`, `PB`) and binary ones (`KiB`,
+/// `MiB`, `GiB`, `TiB`, `PiB`) are understood regardless of case, a
+/// number without a unit counts bytes.  The report line shows the size
+/// normalized, e.g. `1536KiB` as `1.5MiB`.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use dialoguer::ByteSizeInput;
+///
+/// let cache = ByteSizeInput::new().with_prompt("Cache size").min(1 << 20).interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct ByteSizeInput<'a> {
+    spec: Spec<'a, u64>,
+}
+
+impl<'a> DurationInput<'a> {
+    /// Creates a new duration input.
+    pub fn new() -> DurationInput<'static> {
+        DurationInput::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> DurationInput<'a> {
+        DurationInput {
+            spec: Spec::new(ThemeRef::Borrowed(theme)),
+        }
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> DurationInput<'static> {
+        DurationInput {
+            spec: Spec::new(ThemeRef::Owned(Arc::new(theme))),
+        }
+    }
+
+    /// Sets the input prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut DurationInput<'a> {
+        self.spec.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut DurationInput<'a> {
+        self.spec.theme = self.spec.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the duration used when the user just hits enter.
+    pub fn default(&mut self, value: Duration) -> &mut DurationInput<'a> {
+        self.spec.default = Some(value);
+        self
+    }
+
+    /// Rejects durations shorter than `value`.
+    pub fn min(&mut self, value: Duration) -> &mut DurationInput<'a> {
+        self.spec.min = Some(value);
+        self
+    }
+
+    /// Rejects durations longer than `value`.
+    pub fn max(&mut self, value: Duration) -> &mut DurationInput<'a> {
+        self.spec.max = Some(value);
+        self
+    }
+
+    /// Answers the prompt with `value` instead of asking the user.
+    pub fn with_answer(&mut self, value: Duration) -> &mut DurationInput<'a> {
+        self.spec.answer = Some(value);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut DurationInput<'a> {
+        self.spec.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the duration.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<Duration> {
+        self.interact_on(&self.spec.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<Duration>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, DurationInput::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<Duration> {
+        self.spec.interact_on(term)
+    }
+}
+
+impl<'a> ByteSizeInput<'a> {
+    /// Creates a new byte size input.
+    pub fn new() -> ByteSizeInput<'static> {
+        ByteSizeInput::with_theme(get_default_theme())
+    }
+
+    /// Same as `new` but with a specific theme.
+    pub fn with_theme(theme: &'a (dyn Theme + Sync)) -> ByteSizeInput<'a> {
+        ByteSizeInput {
+            spec: Spec::new(ThemeRef::Borrowed(theme)),
+        }
+    }
+
+    /// Like `with_theme` but takes ownership of the theme.
+    pub fn with_owned_theme<T: Theme + Send + Sync + 'static>(theme: T) -> ByteSizeInput<'static> {
+        ByteSizeInput {
+            spec: Spec::new(ThemeRef::Owned(Arc::new(theme))),
+        }
+    }
+
+    /// Sets the input prompt.
+    pub fn with_prompt(&mut self, prompt: &str) -> &mut ByteSizeInput<'a> {
+        self.spec.prompt = prompt.into();
+        self
+    }
+
+    /// Sets the style of the prompt text for this prompt alone.
+    ///
+    /// Themes without styles, such as the default theme, ignore it.
+    pub fn prompt_style(&mut self, style: Style) -> &mut ByteSizeInput<'a> {
+        self.spec.theme = self.spec.theme.overridden(ThemeOverrides {
+            prompt_style: Some(style),
+            ..ThemeOverrides::default()
+        });
+        self
+    }
+
+    /// Sets the number of bytes used when the user just hits enter.
+    pub fn default(&mut self, value: u64) -> &mut ByteSizeInput<'a> {
+        self.spec.default = Some(value);
+        self
+    }
+
+    /// Rejects sizes below `value` bytes.
+    pub fn min(&mut self, value: u64) -> &mut ByteSizeInput<'a> {
+        self.spec.min = Some(value);
+        self
+    }
+
+    /// Rejects sizes above `value` bytes.
+    pub fn max(&mut self, value: u64) -> &mut ByteSizeInput<'a> {
+        self.spec.max = Some(value);
+        self
+    }
+
+    /// Answers the prompt with `value` bytes instead of asking the user.
+    pub fn with_answer(&mut self, value: u64) -> &mut ByteSizeInput<'a> {
+        self.spec.answer = Some(value);
+        self
+    }
+
+    /// Sets the stream `interact` renders the prompt on.
+    ///
+    /// The default is stderr.
+    pub fn on(&mut self, stream: Stream) -> &mut ByteSizeInput<'a> {
+        self.spec.stream = stream;
+        self
+    }
+
+    /// Enables user interaction and returns the number of bytes.
+    ///
+    /// The prompt is rendered on stderr unless changed with `on`.
+    pub fn interact(&self) -> io::Result<u64> {
+        self.interact_on(&self.spec.stream.term())
+    }
+
+    /// Like `interact` but returns a future for use within a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn interact_async(&mut self) -> PromptFuture<'_, io::Result<u64>>
+    where
+        'a: 'static,
+    {
+        run_detached_mut(self, ByteSizeInput::new(), |prompt| prompt.interact())
+    }
+
+    /// Like `interact` but allows a specific terminal to be set.
+    pub fn interact_on(&self, term: &dyn Backend) -> io::Result<u64> {
+        self.spec.interact_on(term)
+    }
+}
+
+/// A value typed with a unit.
+trait Quantity: Copy + Ord + Send + 'static {
+    fn parse(text: &str) -> Result<Self, String>;
+
+    fn format(self) -> String;
+}
+
+impl Quantity for Duration {
+    fn parse(text: &str) -> Result<Duration, String> {
+        parse_duration(text).ok_or_else(|| messages().invalid_duration.clone())
+    }
+
+    fn format(self) -> String {
+        format_duration(self)
+    }
+}
+
+impl Quantity for u64 {
+    fn parse(text: &str) -> Result<u64, String> {
+        parse_byte_size(text).ok_or_else(|| messages().invalid_byte_size.clone())
+    }
+
+    fn format(self) -> String {
+        format_byte_size(self)
+    }
+}
+
+/// The settings shared by the prompts for quantities.
+struct Spec<'a, Q> {
+    prompt: String,
+    default: Option<Q>,
+    min: Option<Q>,
+    max: Option<Q>,
+    answer: Option<Q>,
+    theme: ThemeRef<'a>,
+    stream: Stream,
+}
+
+impl<'a, Q: Quantity> Spec<'a, Q> {
+    fn new(theme: ThemeRef<'a>) -> Spec<'a, Q> {
+        Spec {
+            prompt: "".into(),
+            default: None,
+            min: None,
+            max: None,
+            answer: None,
+            theme,
+            stream: Stream::Stderr,
+        }
+    }
+
+    /// Reads the quantity with an `Input`, which normalizes the typed text
+    /// before checking the range and parsing it.
+    fn interact_on(&self, term: &dyn Backend) -> io::Result<Q> {
+        let mut input = Input::<Typed<Q>>::with_theme_ref(self.theme.clone());
+        input.with_prompt(&self.prompt).on(self.stream);
+        input.with_post_process(|text| Q::parse(text).map_or_else(|_| text.into(), Q::format));
+        let (min, max) = (self.min, self.max);
+        input.validate_with(move |text: &str| check_range(text, min, max));
+        if let Some(default) = self.default {
+            input.default(Typed(default));
+        }
+        if let Some(answer) = self.answer {
+            input.with_answer(Typed(answer));
+        }
+        input.interact_on(term).map(|typed| typed.0)
+    }
+}
+
+/// A quantity parsed from and shown in its normalized text.
+#[derive(Clone)]
+struct Typed<Q>(Q);
+
+impl<Q: Quantity> FromStr for Typed<Q> {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Typed<Q>, String> {
+        Q::parse(text).map(Typed)
+    }
+}
+
+impl<Q: Quantity> fmt::Display for Typed<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0.format())
+    }
+}
+
+/// Rejects quantities out of range.
+///
+/// Text that does not parse is left to the parse error.
+fn check_range<Q: Quantity>(text: &str, min: Option<Q>, max: Option<Q>) -> Result<(), String> {
+    let value = match Q::parse(text) {
+        Ok(value) => value,
+        Err(_) => return Ok(()),
+    };
+    if let Some(min) = min.filter(|&min| value < min) {
+        return Err(fill(&messages().value_at_least, &[("min", &min.format())]));
+    }
+    if let Some(max) = max.filter(|&max| value > max) {
+        return Err(fill(&messages().value_at_most, &[("max", &max.format())]));
+    }
+    Ok(())
+}
+
+/// Parses a duration such as `1h30m`, `1.5h` or `250ms`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut nanos: u128 = 0;
+    while !rest.is_empty() {
+        let (number, tail) = split_number(rest);
+        let tail = tail.trim_start();
+        let end = tail
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(end);
+        let &(_, unit) = DURATION_UNITS.iter().find(|&&(name, _)| name == unit)?;
+        nanos = nanos.checked_add(scale(number, unit)?)?;
+        rest = tail.trim_start();
+    }
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+/// Formats a duration with its largest units first, e.g. `1h30m`.
+fn format_duration(duration: Duration) -> String {
+    let mut nanos = duration.as_nanos();
+    if nanos == 0 {
+        return "0s".into();
+    }
+    let mut text = String::new();
+    for &(name, unit) in DURATION_UNITS {
+        if nanos >= unit {
+            text.push_str(&format!("{}{}", nanos / unit, name));
+            nanos %= unit;
+        }
+    }
+    text
+}
+
+/// Parses a byte size such as `512MB`, `1.5 GiB` or `4096`.
+fn parse_byte_size(text: &str) -> Option<u64> {
+    let (number, unit) = split_number(text.trim());
+    let unit = unit.trim_start();
+    let unit = if unit.is_empty() {
+        1
+    } else {
+        DECIMAL_UNITS
+            .iter()
+            .chain(BINARY_UNITS)
+            .find(|&&(name, _)| name.eq_ignore_ascii_case(unit))?
+            .1
+    };
+    u64::try_from(scale(number, unit)?).ok()
+}
+
+/// Formats a byte size in the largest unit that takes at most two
+/// decimals, e.g. `512MB` or `1.5GiB`.
+///
+/// Of the decimal and the binary units the shorter result wins.
+fn format_byte_size(bytes: u64) -> String {
+    let bytes = u128::from(bytes);
+    let format = |units: &[(&str, u128)]| {
+        units
+            .iter()
+            .find(|&&(_, unit)| bytes >= unit && bytes * 100 % unit == 0)
+            .map(|&(name, unit)| {
+                let hundredths = bytes * 100 / unit;
+                let fraction = format!(".{:02}", hundredths % 100);
+                let fraction = fraction.trim_end_matches('0').trim_end_matches('.');
+                format!("{}{}{}", hundredths / 100, fraction, name)
+            })
+    };
+    match (format(DECIMAL_UNITS), format(BINARY_UNITS)) {
+        (Some(decimal), Some(binary)) if binary.len() < decimal.len() => binary,
+        (Some(decimal), _) => decimal,
+        (None, binary) => binary.unwrap_or_else(|| format!("{}B", bytes)),
+    }
+}
+
+/// Splits the leading number, digits with an optional decimal point, off
+/// a text.
+fn split_number(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+/// Multiplies a decimal number by `unit`, rounded to a whole number.
+fn scale(number: &str, unit: u128) -> Option<u128> {
+    let (whole, fraction) = match number.find('.') {
+        Some(idx) => (&number[..idx], &number[idx + 1..]),
+        None => (number, ""),
+    };
+    if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+        return None;
+    }
+    let fraction = &fraction[..fraction.len().min(18)];
+    let digits: u128 = format!("{}{}", whole, fraction).parse().ok()?;
+    let divisor = 10u128.pow(fraction.len() as u32);
+    Some((digits.checked_mul(unit)? + divisor / 2) / divisor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use console::Key;
+
+    use virtual_term::VirtualTerm;
+
+    #[test]
+    fn test_parse_and_format() {
+        let parse = |text| parse_duration(text).map(format_duration);
+        assert_eq!(parse("90m").as_deref(), Some("1h30m"));
+        assert_eq!(parse(" 1.5h 20s ").as_deref(), Some("1h30m20s"));
+        assert_eq!(parse("1500ms").as_deref(), Some("1s500ms"));
+        assert_eq!(parse("0s").as_deref(), Some("0s"));
+        assert_eq!(parse("90"), None);
+        assert_eq!(parse("5 fortnights"), None);
+        assert_eq!(parse("1..5s"), None);
+
+        let parse = |text| parse_byte_size(text).map(format_byte_size);
+        assert_eq!(parse("512mb").as_deref(), Some("512MB"));
+        assert_eq!(parse("1.5 GiB").as_deref(), Some("1.5GiB"));
+        assert_eq!(parse("1536KiB").as_deref(), Some("1.5MiB"));
+        assert_eq!(parse("4097").as_deref(), Some("4097B"));
+        assert_eq!(parse("1.25kB").as_deref(), Some("1.25kB"));
+        assert_eq!(parse("20000PiB"), None);
+        assert_eq!(parse("MB"), None);
+    }
+
+    #[test]
+    fn test_duration_input() {
+        let mut term = VirtualTerm::new(10, 60);
+        term.text("1 day\n4h\n90m\n");
+        let timeout = DurationInput::new()
+            .with_prompt("Timeout")
+            .max(Duration::from_secs(3 * 3600))
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(timeout, Duration::from_secs(90 * 60));
+        assert_eq!(term.screen(), "Timeout: 1h30m");
+
+        let mut term = VirtualTerm::new(10, 60);
+        term.keys(&[Key::Enter]);
+        let size = ByteSizeInput::new()
+            .with_prompt("Cache")
+            .default(1 << 30)
+            .interact_on(&term)
+            .unwrap();
+        assert_eq!(size, 1 << 30);
+        assert_eq!(term.screen(), "Cache: 1GiB");
+    }
+
+    #[test]
+    fn test_send() {
+        use theme::ColorfulTheme;
+
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(DurationInput::new());
+        assert_send(DurationInput::with_owned_theme(ColorfulTheme::default()));
+        assert_send(ByteSizeInput::new());
+        assert_send(ByteSizeInput::with_owned_theme(ColorfulTheme::default()));
+    }
+}
@@ -1,5 +1,8 @@
 //! Provides validation for text inputs
-use std::fmt::{Debug, Display};
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::io;
+
 pub trait Validator {
     type Err: Debug + Display;
 
@@ -8,6 +11,70 @@ pub trait Validator {
     /// If this produces `Ok(())` then the value is used and parsed, if
     /// an error is returned validation fails with that error.
     fn validate(&self, text: &str) -> Result<(), Self::Err>;
+
+    /// Like `validate` but may let the value through with a warning.
+    ///
+    /// The default reports every failed validation as an error.
+    fn check(&self, text: &str) -> Validation<Self::Err> {
+        self.validate(text).into()
+    }
+}
+
+/// The outcome of a validation that may only warn about a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validation<E> {
+    /// The value is used.
+    Valid,
+    /// The message is shown and the value is used once the user submits
+    /// it again.
+    Warning(E),
+    /// The message is shown and the value is rejected.
+    Error(E),
+}
+
+impl<E> From<Result<(), E>> for Validation<E> {
+    fn from(rv: Result<(), E>) -> Validation<E> {
+        match rv {
+            Ok(()) => Validation::Valid,
+            Err(err) => Validation::Error(err),
+        }
+    }
+}
+
+/// Turns the errors of a validator into warnings.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::path::Path;
+/// use dialoguer::{Input, Warn};
+///
+/// let path = Input::<String>::new()
+///     .with_prompt("Output file")
+///     .validate_with(Warn(|path: &str| {
+///         if Path::new(path).exists() {
+///             Err("the file already exists")
+///         } else {
+///             Ok(())
+///         }
+///     }))
+///     .interact()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct Warn<V>(pub V);
+
+impl<V: Validator> Validator for Warn<V> {
+    type Err = V::Err;
+
+    fn validate(&self, _text: &str) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn check(&self, text: &str) -> Validation<Self::Err> {
+        match self.0.check(text) {
+            Validation::Error(err) => Validation::Warning(err),
+            rv => rv,
+        }
+    }
 }
 
 impl<T: Fn(&str) -> Result<(), E>, E: Debug + Display> Validator for T {
@@ -16,4 +83,68 @@ impl<T: Fn(&str) -> Result<(), E>, E: Debug + Display> Validator for T {
     fn validate(&self, text: &str) -> Result<(), Self::Err> {
         self(text)
     }
-}
\ No newline at end of file
+}
+
+/// The error a prompt fails with once its input was rejected too often.
+///
+/// It is returned wrapped in an `io::Error` of kind `Other` and can be
+/// recovered with `io::Error::get_ref` and `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyAttempts {
+    attempts: usize,
+}
+
+impl TooManyAttempts {
+    /// Returns the number of rejected inputs.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+}
+
+impl Display for TooManyAttempts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "too many invalid attempts ({})", self.attempts)
+    }
+}
+
+impl Error for TooManyAttempts {}
+
+/// Counts a rejected input and fails once `max` inputs were rejected.
+pub(crate) fn count_attempt(attempts: &mut usize, max: Option<usize>) -> io::Result<()> {
+    *attempts += 1;
+    match max {
+        Some(max) if *attempts >= max => Err(io::Error::other(TooManyAttempts {
+            attempts: *attempts,
+        })),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warn() {
+        let check = |text: &str| {
+            if text.is_empty() {
+                Err("empty")
+            } else {
+                Ok(())
+            }
+        };
+        assert_eq!(check.check(""), Validation::Error("empty"));
+        assert_eq!(Warn(check).check(""), Validation::Warning("empty"));
+        assert_eq!(Warn(check).check("x"), Validation::Valid);
+    }
+
+    #[test]
+    fn test_count_attempt() {
+        let mut attempts = 0;
+        assert!(count_attempt(&mut attempts, None).is_ok());
+        assert!(count_attempt(&mut attempts, Some(3)).is_ok());
+        let err = count_attempt(&mut attempts, Some(3)).unwrap_err();
+        let err = err.get_ref().unwrap().downcast_ref::<TooManyAttempts>();
+        assert_eq!(err.map(|err| err.attempts()), Some(3));
+    }
+}
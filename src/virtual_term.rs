@@ -0,0 +1,347 @@
+//! A terminal in memory for snapshot tests.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::thread;
+use std::time::Duration;
+
+use console::{measure_text_width, Key};
+
+use backend::Backend;
+
+/// A terminal of a fixed size that keeps what prompts render in memory.
+///
+/// The output is interpreted like a terminal would, so `screen` returns
+/// exactly the text a user sees, without styling.  Keys are queued with
+/// `keys` and `text`; before each key is read the screen is saved as a
+/// frame, so tests can assert every step of a prompt.  Once all keys are
+/// used up reading fails with `UnexpectedEof`.  A `pause` between keys
+/// lets prompts with a timeout run into it.
+///
+/// ```rust
+/// # extern crate console;
+/// # extern crate dialoguer;
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use console::Key;
+/// use dialoguer::{Select, VirtualTerm};
+///
+/// let mut term = VirtualTerm::new(10, 40);
+/// term.keys(&[Key::ArrowDown, Key::Enter]);
+/// let region = Select::new()
+///     .with_prompt("Region")
+///     .items(&["eu-west", "us-east"])
+///     .default(0)
+///     .interact_on(&term)?;
+/// assert_eq!(region, 1);
+/// assert_eq!(term.frames()[1], "Region:\n  eu-west\n> us-east");
+/// assert_eq!(term.screen(), "Region: us-east");
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+///
+/// A `Replay` of a recorded session can render to a virtual terminal to
+/// turn the recording into a snapshot test.
+pub struct VirtualTerm {
+    rows: u16,
+    cols: u16,
+    colors: bool,
+    /// The queued keys, with `None` for a pause.
+    keys: RefCell<VecDeque<Option<Key>>>,
+    screen: RefCell<Screen>,
+    frames: RefCell<Vec<String>>,
+    output: RefCell<String>,
+}
+
+impl VirtualTerm {
+    /// Creates an empty terminal with `rows` rows and `cols` columns.
+    pub fn new(rows: u16, cols: u16) -> VirtualTerm {
+        VirtualTerm {
+            rows,
+            cols,
+            colors: false,
+            keys: RefCell::default(),
+            screen: RefCell::new(Screen::new(rows as usize, cols as usize)),
+            frames: RefCell::default(),
+            output: RefCell::default(),
+        }
+    }
+
+    /// Queues key presses.
+    pub fn keys(&mut self, keys: &[Key]) -> &mut VirtualTerm {
+        self.keys.get_mut().extend(keys.iter().cloned().map(Some));
+        self
+    }
+
+    /// Queues a pause after the keys queued so far.
+    ///
+    /// Waiting for a key times out until the key after the pause is read.
+    pub fn pause(&mut self) -> &mut VirtualTerm {
+        self.keys.get_mut().push_back(None);
+        self
+    }
+
+    /// Queues text as key presses, with `\n` pressing enter.
+    pub fn text(&mut self, text: &str) -> &mut VirtualTerm {
+        let keys = text.chars().map(|c| match c {
+            '\n' => Some(Key::Enter),
+            c => Some(Key::Char(c)),
+        });
+        self.keys.get_mut().extend(keys);
+        self
+    }
+
+    /// Sets whether the terminal reports color support.
+    ///
+    /// The default is `false` so prompts render plain.  Styling never
+    /// shows on the `screen` but is part of the `output`.
+    pub fn colors(&mut self, colors: bool) -> &mut VirtualTerm {
+        self.colors = colors;
+        self
+    }
+
+    /// Returns the text on the screen, without trailing blanks and empty
+    /// lines.
+    pub fn screen(&self) -> String {
+        self.screen.borrow().text()
+    }
+
+    /// Returns the screens seen before each key was read.
+    pub fn frames(&self) -> Vec<String> {
+        self.frames.borrow().clone()
+    }
+
+    /// Returns everything written to the terminal, escape sequences
+    /// included.
+    pub fn output(&self) -> String {
+        self.output.borrow().clone()
+    }
+}
+
+impl Backend for VirtualTerm {
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.output.borrow_mut().push_str(s);
+        self.screen.borrow_mut().write(s);
+        Ok(())
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        self.frames.borrow_mut().push(self.screen());
+        let mut keys = self.keys.borrow_mut();
+        while let Some(None) = keys.front() {
+            keys.pop_front();
+        }
+        keys.pop_front().and_then(|key| key).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The virtual terminal has no more keys",
+            )
+        })
+    }
+
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        if let Some(None) = self.keys.borrow().front() {
+            thread::sleep(timeout);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.rows, self.cols)
+    }
+
+    fn colors_supported(&self) -> bool {
+        self.colors
+    }
+}
+
+/// The cells of a terminal screen and its cursor.
+struct Screen {
+    cols: usize,
+    /// Every cell holds a character plus any zero width ones after it;
+    /// the cell after a wide character is empty.
+    lines: Vec<Vec<String>>,
+    row: usize,
+    col: usize,
+    /// The main screen while the alternate screen is shown.
+    saved: Option<(Vec<Vec<String>>, usize, usize)>,
+    /// An escape sequence not complete yet.
+    pending: String,
+}
+
+impl Screen {
+    fn new(rows: usize, cols: usize) -> Screen {
+        Screen {
+            cols,
+            lines: vec![Screen::blank_line(cols); rows.max(1)],
+            row: 0,
+            col: 0,
+            saved: None,
+            pending: String::new(),
+        }
+    }
+
+    fn blank_line(cols: usize) -> Vec<String> {
+        vec![" ".to_string(); cols]
+    }
+
+    fn text(&self) -> String {
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| line.concat().trim_end().to_string())
+            .collect();
+        lines.join("\n").trim_end_matches('\n').to_string()
+    }
+
+    fn write(&mut self, s: &str) {
+        let mut input = mem::take(&mut self.pending);
+        input.push_str(s);
+        let mut chars = input.char_indices();
+        while let Some((start, c)) = chars.next() {
+            match c {
+                '\x1b' => {
+                    let mut seq = String::new();
+                    let complete = match chars.next() {
+                        Some((_, '[')) => loop {
+                            match chars.next() {
+                                Some((_, c @ '\x40'..='\x7e')) => {
+                                    self.csi(&seq, c);
+                                    break true;
+                                }
+                                Some((_, c)) => seq.push(c),
+                                None => break false,
+                            }
+                        },
+                        Some(_) => true,
+                        None => false,
+                    };
+                    if !complete {
+                        self.pending = input[start..].to_string();
+                        return;
+                    }
+                }
+                '\r' => self.col = 0,
+                '\n' => {
+                    self.col = 0;
+                    self.line_feed();
+                }
+                '\x08' => self.col = self.col.saturating_sub(1),
+                '\t' => self.col = ((self.col / 8 + 1) * 8).min(self.cols.saturating_sub(1)),
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        let width = measure_text_width(c.encode_utf8(&mut [0; 4]));
+        if width == 0 {
+            let col = self.col.saturating_sub(1).min(self.cols.saturating_sub(1));
+            self.lines[self.row][col].push(c);
+            return;
+        }
+        if self.col + width > self.cols {
+            self.col = 0;
+            self.line_feed();
+        }
+        let line = &mut self.lines[self.row];
+        line[self.col] = c.to_string();
+        if width > 1 && self.col + 1 < self.cols {
+            line[self.col + 1] = String::new();
+        }
+        self.col += width;
+    }
+
+    fn line_feed(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+        } else {
+            self.lines.remove(0);
+            self.lines.push(Screen::blank_line(self.cols));
+        }
+    }
+
+    /// Applies a control sequence with the parameters `params`.
+    fn csi(&mut self, params: &str, action: char) {
+        let rows = self.lines.len();
+        let args: Vec<usize> = params
+            .trim_start_matches('?')
+            .split(';')
+            .map(|arg| arg.parse().unwrap_or(0))
+            .collect();
+        let arg = args[0];
+        let count = arg.max(1);
+        match (params.starts_with('?'), action) {
+            (true, 'h') if arg == 1049 => {
+                let blank = vec![Screen::blank_line(self.cols); rows];
+                self.saved = Some((mem::replace(&mut self.lines, blank), self.row, self.col));
+                self.row = 0;
+                self.col = 0;
+            }
+            (true, 'l') if arg == 1049 => {
+                if let Some((lines, row, col)) = self.saved.take() {
+                    self.lines = lines;
+                    self.row = row;
+                    self.col = col;
+                }
+            }
+            (true, _) => {}
+            (false, 'A') => self.row = self.row.saturating_sub(count),
+            (false, 'B') => self.row = (self.row + count).min(rows - 1),
+            (false, 'C') => self.col = (self.col + count).min(self.cols.saturating_sub(1)),
+            (false, 'D') => self.col = self.col.saturating_sub(count),
+            (false, 'G') => self.col = (count - 1).min(self.cols.saturating_sub(1)),
+            (false, 'H') | (false, 'f') => {
+                self.row = (count - 1).min(rows - 1);
+                self.col =
+                    (args.get(1).map_or(1, |&col| col.max(1)) - 1).min(self.cols.saturating_sub(1));
+            }
+            (false, 'J') => {
+                let (row, col) = (self.row, self.col);
+                let cleared = match arg {
+                    0 => (row + 1..rows, col..self.cols),
+                    1 => (0..row, 0..col + 1),
+                    _ => (0..rows, 0..0),
+                };
+                for line in cleared.0 {
+                    self.lines[line] = Screen::blank_line(self.cols);
+                }
+                self.clear_cells(cleared.1);
+            }
+            (false, 'K') => match arg {
+                0 => self.clear_cells(self.col..self.cols),
+                1 => self.clear_cells(0..self.col + 1),
+                _ => self.clear_cells(0..self.cols),
+            },
+            _ => {}
+        }
+    }
+
+    /// Blanks cells of the cursor's line.
+    fn clear_cells(&mut self, cols: std::ops::Range<usize>) {
+        let end = cols.end.min(self.cols);
+        for cell in &mut self.lines[self.row][cols.start.min(end)..end] {
+            *cell = " ".into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen() {
+        let mut screen = Screen::new(3, 10);
+        screen.write("one\ntwo\nthree\nfour");
+        assert_eq!(screen.text(), "two\nthree\nfour");
+        screen.write("\x1b[2A\r\x1b[0Jx\x1b[1");
+        screen.write("mwide 日本");
+        assert_eq!(screen.text(), "xwide 日本");
+        screen.write("\x1b[?1049hmenu");
+        assert_eq!(screen.text(), "menu");
+        screen.write("\x1b[?1049l\x1b[2K\rdone");
+        assert_eq!(screen.text(), "done");
+    }
+}
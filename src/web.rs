@@ -0,0 +1,256 @@
+//! A backend for web based terminals such as xterm.js.
+use std::io;
+use std::str;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use console::Key;
+
+use backend::Backend;
+
+/// A backend that renders to an xterm.js compatible stream.
+///
+/// Output is handed to a callback, e.g. one that calls `terminal.write`,
+/// while key presses arrive through the `WebInput` half which is fed the
+/// data of xterm.js' `onData` event.  Reading a key blocks, so the prompt
+/// has to run on a worker thread while the JS side keeps feeding input.
+///
+/// ```rust,no_run
+/// # fn test() -> Result<(), Box<std::error::Error>> {
+/// use std::thread;
+///
+/// use dialoguer::{Confirmation, WebTerm};
+///
+/// let (term, input) = WebTerm::new(|data| {
+///     // forward `data` to `terminal.write`
+/// });
+/// let prompt = thread::spawn(move || {
+///     Confirmation::new().with_text("Deploy?").interact_on(&term)
+/// });
+/// // from the `onData` and `onResize` handlers:
+/// input.resize(30, 100);
+/// input.feed("y");
+/// let deploy = prompt.join().unwrap()?;
+/// # Ok(()) } fn main() { test().unwrap(); }
+/// ```
+pub struct WebTerm {
+    output: Box<dyn Fn(&str) + Send + Sync>,
+    keys: Mutex<Receiver<Key>>,
+    pending: Mutex<Option<Key>>,
+    size: Arc<Mutex<(u16, u16)>>,
+}
+
+/// The input half of a `WebTerm`.
+#[derive(Clone)]
+pub struct WebInput {
+    keys: Sender<Key>,
+    size: Arc<Mutex<(u16, u16)>>,
+    partial: Arc<Mutex<Vec<u8>>>,
+}
+
+impl WebTerm {
+    /// Creates a backend writing to `output` and the handle to feed it.
+    ///
+    /// The terminal is assumed to be 24 rows by 80 columns until
+    /// `WebInput::resize` is called.
+    pub fn new<F: Fn(&str) + Send + Sync + 'static>(output: F) -> (WebTerm, WebInput) {
+        let (tx, rx) = channel();
+        let size = Arc::new(Mutex::new((24, 80)));
+        let term = WebTerm {
+            output: Box::new(output),
+            keys: Mutex::new(rx),
+            pending: Mutex::new(None),
+            size: size.clone(),
+        };
+        let input = WebInput {
+            keys: tx,
+            size,
+            partial: Arc::new(Mutex::new(vec![])),
+        };
+        (term, input)
+    }
+}
+
+impl Backend for WebTerm {
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        // there is no tty that translates newlines for us
+        let mut out = String::with_capacity(s.len());
+        let mut prev = '\0';
+        for c in s.chars() {
+            if c == '\n' && prev != '\r' {
+                out.push('\r');
+            }
+            out.push(c);
+            prev = c;
+        }
+        (self.output)(&out);
+        Ok(())
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        if let Some(key) = self.pending.lock().unwrap().take() {
+            return Ok(key);
+        }
+        self.keys
+            .lock()
+            .unwrap()
+            .recv()
+            .map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "web terminal closed"))
+    }
+
+    fn size(&self) -> (u16, u16) {
+        *self.size.lock().unwrap()
+    }
+
+    fn poll_key(&self, timeout: Duration) -> io::Result<bool> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.is_some() {
+            return Ok(true);
+        }
+        match self.keys.lock().unwrap().recv_timeout(timeout) {
+            Ok(key) => {
+                *pending = Some(key);
+                Ok(true)
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(false),
+            // let `read_key` report the closed input
+            Err(RecvTimeoutError::Disconnected) => Ok(true),
+        }
+    }
+}
+
+impl WebInput {
+    /// Feeds data as delivered by xterm.js' `onData` event.
+    pub fn feed(&self, data: &str) {
+        for key in parse_keys(data) {
+            // the prompt may have finished already
+            let _ = self.keys.send(key);
+        }
+    }
+
+    /// Feeds raw input bytes, e.g. from a WebSocket or an SSH channel.
+    ///
+    /// A character whose UTF-8 encoding is split across calls is held back
+    /// until the rest of it arrives, so characters committed by an input
+    /// method never show up as partial bytes.  Invalid bytes are dropped.
+    pub fn feed_bytes(&self, data: &[u8]) {
+        let mut partial = self.partial.lock().unwrap();
+        partial.extend_from_slice(data);
+        let mut text = String::new();
+        loop {
+            match str::from_utf8(&partial) {
+                Ok(s) => {
+                    text.push_str(s);
+                    partial.clear();
+                    break;
+                }
+                Err(err) => {
+                    let valid = err.valid_up_to();
+                    text.push_str(str::from_utf8(&partial[..valid]).unwrap());
+                    match err.error_len() {
+                        Some(len) => {
+                            partial.drain(..valid + len);
+                        }
+                        None => {
+                            partial.drain(..valid);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.feed(&text);
+    }
+
+    /// Sends a single key press.
+    pub fn send_key(&self, key: Key) {
+        let _ = self.keys.send(key);
+    }
+
+    /// Updates the size after the terminal was resized.
+    pub fn resize(&self, rows: u16, cols: u16) {
+        *self.size.lock().unwrap() = (rows, cols);
+    }
+}
+
+/// Splits terminal input into key presses.
+fn parse_keys(data: &str) -> Vec<Key> {
+    let mut keys = vec![];
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        keys.push(match c {
+            '\r' | '\n' => Key::Enter,
+            '\t' => Key::Tab,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x03' => Key::CtrlC,
+            '\x1b' => match chars.peek() {
+                Some(&'[') | Some(&'O') => {
+                    chars.next();
+                    let mut seq = String::new();
+                    while let Some(&c) = chars.peek() {
+                        chars.next();
+                        seq.push(c);
+                        if c.is_ascii_alphabetic() || c == '~' {
+                            break;
+                        }
+                    }
+                    escape_key(&seq)
+                }
+                _ => Key::Escape,
+            },
+            c => Key::Char(c),
+        });
+    }
+    keys
+}
+
+fn escape_key(seq: &str) -> Key {
+    match seq {
+        "A" => Key::ArrowUp,
+        "B" => Key::ArrowDown,
+        "C" => Key::ArrowRight,
+        "D" => Key::ArrowLeft,
+        "H" | "1~" => Key::Home,
+        "F" | "4~" => Key::End,
+        "2~" => Key::Insert,
+        "3~" => Key::Del,
+        "5~" => Key::PageUp,
+        "6~" => Key::PageDown,
+        "Z" => Key::BackTab,
+        _ => Key::UnknownEscSeq(seq.chars().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_bytes() {
+        let (term, input) = WebTerm::new(|_| {});
+        let bytes = "日a".as_bytes();
+        input.feed_bytes(&bytes[..1]);
+        input.feed_bytes(&bytes[1..2]);
+        assert!(!term.poll_key(Duration::from_millis(0)).unwrap());
+        input.feed_bytes(&[bytes[2], 0xff, bytes[3]]);
+        assert_eq!(term.read_key().unwrap(), Key::Char('日'));
+        assert_eq!(term.read_key().unwrap(), Key::Char('a'));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse_keys("a\x1b[B\x1bOH\x1b[5~\r\x7f\x1b"),
+            vec![
+                Key::Char('a'),
+                Key::ArrowDown,
+                Key::Home,
+                Key::PageUp,
+                Key::Enter,
+                Key::Backspace,
+                Key::Escape,
+            ]
+        );
+    }
+}